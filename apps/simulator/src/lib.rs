@@ -0,0 +1,6 @@
+//! Blackjack 模拟器：按规则和策略逐局发牌、行动和结算
+
+pub mod round;
+pub mod simulator;
+pub mod statistics;
+pub mod types;
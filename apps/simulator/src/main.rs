@@ -1,19 +1,117 @@
 //! Blackjack 模拟器主程序
 
-mod simulator;
-mod types;
-mod statistics;
-
 use anyhow::Result;
+use calculator::{GameRules, KellyBetting};
+use clap::Parser;
+use simulator::simulator::{Simulator, SimulatorConfig};
+use simulator::statistics::Statistics;
+
+/// 命令行参数
+#[derive(Debug, Parser)]
+#[command(name = "simulator", about = "Blackjack 模拟器")]
+struct Args {
+    /// 规则预设名称或规则文件路径（.toml / .json）
+    #[arg(long, default_value = "default")]
+    rules: String,
+    /// 模拟局数
+    #[arg(long)]
+    rounds: Option<u64>,
+    /// 并发线程数
+    #[arg(long)]
+    threads: Option<usize>,
+    /// 随机数种子（相同种子和线程数的结果可复现）
+    #[arg(long)]
+    seed: Option<u64>,
+    /// 基础策略（builtin / generated / 策略表文件路径 .csv / .json），generated 按规则生成
+    #[arg(long, default_value = "builtin")]
+    strategy: String,
+    /// 指数打法表（none / i18 / fab4 / i18-fab4），玩家按 Hi-Lo true count 偏离基础策略
+    #[arg(long, default_value = "none")]
+    deviations: String,
+    /// Kelly 下注的资金（设置后按 Hi-Lo true count 估算优势下注，否则每局 1 个单位）
+    #[arg(long)]
+    bankroll: Option<f64>,
+    /// Kelly 比例（0.5 为半 Kelly）
+    #[arg(long, default_value_t = KellyBetting::default().kelly_fraction)]
+    kelly_fraction: f64,
+    /// 每局净收益的方差
+    #[arg(long, default_value_t = KellyBetting::default().variance)]
+    variance: f64,
+    /// 桌台最小注
+    #[arg(long, default_value_t = KellyBetting::default().min_bet)]
+    min_bet: f64,
+    /// 桌台最大注
+    #[arg(long, default_value_t = KellyBetting::default().max_bet)]
+    max_bet: f64,
+    /// 筹码单位
+    #[arg(long, default_value_t = KellyBetting::default().chip_unit)]
+    chip_unit: f64,
+    /// 列出所有规则预设
+    #[arg(long)]
+    list_rules: bool,
+}
 
 fn main() -> Result<()> {
     env_logger::init();
-    
+    let args = Args::parse();
+
     println!("Blackjack Simulator");
     println!("===================");
-    
-    // TODO: 实现模拟器逻辑
-    
+
+    if args.list_rules {
+        for preset in GameRules::presets() {
+            println!("{:<18} {}", preset.name, preset.description);
+        }
+        return Ok(());
+    }
+
+    let mut config = SimulatorConfig::default()
+        .with_rules_spec(&args.rules)?
+        .with_strategy_spec(&args.strategy)?
+        .with_deviations_spec(&args.deviations)?;
+    if let Some(rounds) = args.rounds {
+        config.rounds = rounds;
+    }
+    if let Some(threads) = args.threads {
+        config.threads = threads;
+    }
+    config.seed = args.seed;
+    config.betting = args.bankroll.map(|bankroll| KellyBetting {
+        bankroll,
+        kelly_fraction: args.kelly_fraction,
+        variance: args.variance,
+        min_bet: args.min_bet,
+        max_bet: args.max_bet,
+        chip_unit: args.chip_unit,
+    });
+    println!("规则: {:?}", config.rules);
+    println!("局数: {}", config.rounds);
+    println!("基础策略: {}", args.strategy);
+    println!("指数打法: {}", args.deviations);
+    if let Some(kelly) = &config.betting {
+        println!(
+            "Kelly 下注: 资金 {} × {}，下注 {}-{}（筹码 {}）",
+            kelly.bankroll, kelly.kelly_fraction, kelly.min_bet, kelly.max_bet, kelly.chip_unit
+        );
+    }
+
+    let kelly_betting = config.betting.is_some();
+    let result = Simulator::new(config).run()?;
+    let statistics = Statistics::new(result.clone());
+    println!("玩家胜 / 庄家胜 / 平局: {} / {} / {}", result.player_wins, result.dealer_wins, result.pushes);
+    println!("胜率: {:.4}%", statistics.win_rate() * 100.0);
+    if kelly_betting {
+        println!("总投注: {:.2}", result.total_bet);
+        println!("每局平均收益: {:+.4}", result.expected_value);
+    } else {
+        println!("每局期望收益: {:+.4}%", result.expected_value * 100.0);
+    }
+    println!("投注回报率: {:+.4}%", statistics.return_rate() * 100.0);
+    println!(
+        "每局标准差: {:.4}（期望收益的标准误差 {:.4}）",
+        statistics.standard_deviation(),
+        statistics.standard_error()
+    );
+
     Ok(())
 }
-
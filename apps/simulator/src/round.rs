@@ -0,0 +1,370 @@
+//! 单局游戏逻辑：洗牌发牌、玩家按基础策略行动、庄家补牌和结算
+
+use calculator::error::Result;
+use calculator::rules::DealerRules;
+use calculator::{
+    Action, Calculator, Card, CardCounts, GameRules, Hand, HoleCardRule, PlayingCard, RunningCounter,
+    Suit,
+};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// 按顺序发牌的牌靴
+pub struct DealingShoe {
+    cards: Vec<PlayingCard>,
+    /// 下一张要发的牌
+    next: usize,
+    /// 发到这张牌之后重新洗牌
+    cut_card: usize,
+    /// 玩家未见的牌（洗牌时为整个牌靴，庄家暗牌翻开之前仍在其中）
+    unseen: CardCounts,
+    /// 玩家的算牌计数（只记录玩家看到的牌，洗牌时清零）
+    counter: Option<RunningCounter>,
+}
+
+/// 牌靴中每种牌的张数
+fn composition(cards: &[PlayingCard]) -> CardCounts {
+    let mut counts = CardCounts::new();
+    for card in cards {
+        *counts.entry(card.card).or_insert(0) += 1;
+    }
+    counts
+}
+
+impl DealingShoe {
+    /// 创建 `deck_count` 副牌的牌靴，`penetration` 为洗牌前发出的比例
+    pub fn new(deck_count: u8, penetration: f64) -> Self {
+        let mut cards = Vec::with_capacity(deck_count as usize * 52);
+        for _ in 0..deck_count {
+            for suit in Suit::ALL {
+                cards.push(PlayingCard { card: Card::Ace, suit });
+                for n in 2..=10 {
+                    cards.push(PlayingCard { card: Card::Number(n), suit });
+                }
+                for _ in 0..3 {
+                    cards.push(PlayingCard { card: Card::Face, suit });
+                }
+            }
+        }
+        let cut_card = (cards.len() as f64 * penetration.clamp(0.1, 1.0)) as usize;
+        Self {
+            next: cards.len(),
+            unseen: composition(&cards),
+            cards,
+            cut_card,
+            counter: None,
+        }
+    }
+
+    /// 按 `cards` 的顺序发牌的牌靴（发完之前不洗牌），用于复现指定的牌局
+    pub fn from_cards(cards: Vec<PlayingCard>) -> Self {
+        Self {
+            next: 0,
+            cut_card: cards.len(),
+            unseen: composition(&cards),
+            cards,
+            counter: None,
+        }
+    }
+
+    /// 玩家按 `counter` 记录看到的牌
+    pub fn with_counter(mut self, counter: RunningCounter) -> Self {
+        self.counter = Some(counter);
+        self
+    }
+
+    /// 玩家当前的 true count（不算牌时为 None）
+    pub fn true_count(&self) -> Option<f64> {
+        self.counter.as_ref().map(RunningCounter::true_count)
+    }
+
+    /// 玩家未见的牌（决策时交给策略）
+    pub fn unseen(&self) -> &CardCounts {
+        &self.unseen
+    }
+
+    /// 是否已经发到切牌位置
+    pub fn needs_shuffle(&self) -> bool {
+        self.next >= self.cut_card
+    }
+
+    /// 重新洗牌
+    pub fn shuffle<R: Rng>(&mut self, rng: &mut R) {
+        self.cards.shuffle(rng);
+        self.next = 0;
+        self.unseen = composition(&self.cards);
+        if let Some(counter) = &mut self.counter {
+            counter.reset();
+        }
+    }
+
+    /// 发一张明牌（牌发完时自动重新洗牌）
+    pub fn draw<R: Rng>(&mut self, rng: &mut R) -> PlayingCard {
+        let card = self.draw_hidden(rng);
+        self.reveal(card);
+        card
+    }
+
+    /// 发一张暗牌，翻开时调用 `reveal` 计入玩家的计数
+    pub fn draw_hidden<R: Rng>(&mut self, rng: &mut R) -> PlayingCard {
+        if self.next >= self.cards.len() {
+            self.shuffle(rng);
+        }
+        let card = self.cards[self.next];
+        self.next += 1;
+        card
+    }
+
+    /// 玩家看到一张牌
+    pub fn reveal(&mut self, card: PlayingCard) {
+        if let Some(count) = self.unseen.get_mut(&card.card) {
+            *count = count.saturating_sub(1);
+        }
+        if let Some(counter) = &mut self.counter {
+            // 牌靴中只有有效牌面
+            let _ = counter.count(card.card);
+        }
+    }
+}
+
+/// 玩家一手牌的最终状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HandState {
+    Active,
+    Stood,
+    Busted,
+    Surrendered,
+    Charlie,
+}
+
+/// 玩家的一手牌（分牌后每手单独下注）
+struct PlayerHand {
+    hand: Hand,
+    bet: f64,
+    split_aces: bool,
+    state: HandState,
+}
+
+impl PlayerHand {
+    fn new(hand: Hand, split_aces: bool) -> Self {
+        Self {
+            hand,
+            bet: 1.0,
+            split_aces,
+            state: HandState::Active,
+        }
+    }
+}
+
+/// 一局的结果（以一个单位的原始投注计）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RoundResult {
+    /// 总投注（含加倍、分牌和保险）
+    pub wagered: f64,
+    /// 净收益
+    pub profit: f64,
+}
+
+impl RoundResult {
+    /// 原始投注为 `bet` 时的结果
+    pub fn scaled(self, bet: f64) -> Self {
+        Self {
+            wagered: self.wagered * bet,
+            profit: self.profit * bet,
+        }
+    }
+
+    /// 加上保险注（原始投注的一半，庄家黑杰克时 2:1 赔付）的结果
+    ///
+    /// 玩家黑杰克时购买保险即等额赔付
+    fn with_insurance(self, insured: bool, dealer: &Hand) -> Self {
+        if !insured {
+            return self;
+        }
+        let insurance = if dealer.is_blackjack() { 1.0 } else { -0.5 };
+        Self {
+            wagered: self.wagered + 0.5,
+            profit: self.profit + insurance,
+        }
+    }
+}
+
+/// 按规则和基础策略（算牌时加上指数打法）进行一局游戏
+///
+/// 庄家明牌为A时，指数打法表带保险指数且玩家的 true count 达到时购买保险
+///
+/// # Errors
+/// 规则无效或牌面无效（策略查询失败）
+pub fn play_round<R: Rng>(calculator: &Calculator, shoe: &mut DealingShoe, rng: &mut R) -> Result<RoundResult> {
+    let rules = calculator.rules();
+    let mut player = Hand::new();
+    player.add_card(shoe.draw(rng));
+    let up_card = shoe.draw(rng);
+    player.add_card(shoe.draw(rng));
+    let up = up_card.card;
+
+    let mut dealer = Hand::new();
+    dealer.add_card(up_card);
+    let peek = rules.hole_card == HoleCardRule::Peek;
+    let hole_card = peek.then(|| shoe.draw_hidden(rng));
+    if let Some(card) = hole_card {
+        dealer.add_card(card);
+    }
+
+    // 早投降在庄家检查黑杰克之前，投降后不再买保险
+    if !player.is_blackjack()
+        && rules.surrender.is_early_against(up)
+        && decide(calculator, shoe, &player, up, 1)? == Action::Surrender
+    {
+        if let Some(card) = hole_card {
+            shoe.reveal(card);
+        }
+        return Ok(RoundResult { wagered: 1.0, profit: -0.5 });
+    }
+
+    let insured = up == Card::Ace
+        && match (calculator.deviations(), shoe.true_count()) {
+            (Some(table), Some(true_count)) => table.takes_insurance(true_count),
+            _ => false,
+        };
+
+    // 玩家黑杰克：庄家也是黑杰克时平局
+    if player.is_blackjack() {
+        match hole_card {
+            Some(card) => shoe.reveal(card),
+            None => dealer.add_card(shoe.draw(rng)),
+        }
+        let profit = if dealer.is_blackjack() { 0.0 } else { rules.blackjack_payout };
+        return Ok(RoundResult { wagered: 1.0, profit }.with_insurance(insured, &dealer));
+    }
+
+    // 偷看：庄家黑杰克只收原始投注
+    if peek && dealer.is_blackjack() {
+        if let Some(card) = hole_card {
+            shoe.reveal(card);
+        }
+        return Ok(RoundResult { wagered: 1.0, profit: -1.0 }.with_insurance(insured, &dealer));
+    }
+
+    let mut hands = vec![PlayerHand::new(player, false)];
+    let mut index = 0;
+    while index < hands.len() {
+        play_hand(calculator, &mut hands, index, up, shoe, rng)?;
+        index += 1;
+    }
+
+    match hole_card {
+        Some(card) => shoe.reveal(card),
+        None => dealer.add_card(shoe.draw(rng)),
+    }
+    let wagered: f64 = hands.iter().map(|hand| hand.bet).sum();
+
+    // 无暗牌：庄家黑杰克收走全部投注（OBO 只收原始投注）
+    if dealer.is_blackjack() {
+        let profit = match rules.hole_card {
+            HoleCardRule::NoHoleCardOriginalBetsOnly => -1.0,
+            _ => -wagered,
+        };
+        return Ok(RoundResult { wagered, profit }.with_insurance(insured, &dealer));
+    }
+
+    while DealerRules::should_hit(&dealer, rules.dealer_stands_on_soft_17) {
+        dealer.add_card(shoe.draw(rng));
+    }
+
+    let profit = hands
+        .iter()
+        .map(|hand| settle(rules, hand, &dealer))
+        .sum();
+    Ok(RoundResult { wagered, profit }.with_insurance(insured, &dealer))
+}
+
+/// 按基础策略（算牌时加上指数打法）打完一手牌（分牌产生的新手牌追加到末尾）
+fn play_hand<R: Rng>(
+    calculator: &Calculator,
+    hands: &mut Vec<PlayerHand>,
+    index: usize,
+    up: Card,
+    shoe: &mut DealingShoe,
+    rng: &mut R,
+) -> Result<()> {
+    let rules = calculator.rules();
+    // 分牌后的手牌先补第二张
+    if hands[index].hand.card_count() == 1 {
+        hands[index].hand.add_card(shoe.draw(rng));
+    }
+
+    while hands[index].state == HandState::Active {
+        let current = &mut hands[index];
+        if current.hand.is_busted() {
+            current.state = HandState::Busted;
+            break;
+        }
+        if rules.is_charlie(&current.hand) {
+            current.state = HandState::Charlie;
+            break;
+        }
+        // 分A后每手只发一张牌
+        if current.split_aces {
+            current.state = HandState::Stood;
+            break;
+        }
+
+        let hand_count = hands.len() as u8;
+        let action = decide(calculator, shoe, &hands[index].hand, up, hand_count)?;
+        let current = &mut hands[index];
+        match action {
+            Action::Stand => current.state = HandState::Stood,
+            Action::Hit => current.hand.add_card(shoe.draw(rng)),
+            Action::Double => {
+                current.bet *= 2.0;
+                current.hand.add_card(shoe.draw(rng));
+                current.state = if current.hand.is_busted() {
+                    HandState::Busted
+                } else if rules.is_charlie(&current.hand) {
+                    HandState::Charlie
+                } else {
+                    HandState::Stood
+                };
+            }
+            Action::Split => {
+                let second = current.hand.cards.pop().expect("分牌的手牌有两张牌");
+                let split_aces = second.card == Card::Ace;
+                current.split_aces = split_aces;
+                current.hand.add_card(shoe.draw(rng));
+                let mut new_hand = Hand::new();
+                new_hand.add_card(second);
+                hands.push(PlayerHand::new(new_hand, split_aces));
+            }
+            Action::Surrender => current.state = HandState::Surrendered,
+        }
+    }
+    Ok(())
+}
+
+/// 玩家的动作：算牌时按 true count 使用指数打法，否则按基础策略；策略看到玩家未见的牌
+fn decide(calculator: &Calculator, shoe: &DealingShoe, hand: &Hand, up: Card, hand_count: u8) -> Result<Action> {
+    match shoe.true_count() {
+        Some(true_count) => calculator.index_action(hand, up, hand_count, shoe.unseen(), true_count),
+        None => calculator.basic_action(hand, up, hand_count, shoe.unseen()),
+    }
+}
+
+/// 庄家没有黑杰克时一手牌的净收益
+fn settle(rules: &GameRules, hand: &PlayerHand, dealer: &Hand) -> f64 {
+    match hand.state {
+        HandState::Busted => -hand.bet,
+        HandState::Surrendered => -0.5 * hand.bet,
+        HandState::Charlie => hand.bet * rules.charlie.map_or(1.0, |charlie| charlie.payout),
+        HandState::Stood | HandState::Active => {
+            let player_value = hand.hand.value();
+            if dealer.is_busted() || player_value > dealer.value() {
+                hand.bet
+            } else if player_value < dealer.value() {
+                -hand.bet
+            } else {
+                0.0
+            }
+        }
+    }
+}
@@ -1,29 +1,84 @@
 //! Blackjack 模拟器核心逻辑
 
-use calculator::{Calculator, rules::GameRules};
+use calculator::{
+    BasicStrategy, Calculator, CountAdvantage, CountSystem, DeviationTable, KellyBetting, RunningCounter,
+    rules::GameRules,
+};
 use anyhow::Result;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+
+use crate::round::{play_round, DealingShoe, RoundResult};
 
 /// 模拟器配置
 #[derive(Debug, Clone)]
 pub struct SimulatorConfig {
     /// 游戏规则
     pub rules: GameRules,
+    /// 玩家的基础策略（None 时使用内置的手写策略表）
+    pub strategy: Option<BasicStrategy>,
     /// 模拟局数
     pub rounds: u64,
     /// 并发线程数
     pub threads: usize,
+    /// 洗牌前发出的牌靴比例
+    pub penetration: f64,
+    /// 随机数种子（None 时随机选择）
+    pub seed: Option<u64>,
+    /// 玩家的指数打法（None 时只按基础策略）
+    pub deviations: Option<DeviationTable>,
+    /// 按 true count 的 Kelly 下注（None 时每局下注 1 个单位）
+    pub betting: Option<KellyBetting>,
 }
 
 impl Default for SimulatorConfig {
     fn default() -> Self {
         Self {
             rules: GameRules::default(),
+            strategy: None,
             rounds: 1000000,
             threads: num_cpus::get(),
+            penetration: 0.75,
+            seed: None,
+            deviations: None,
+            betting: None,
         }
     }
 }
 
+impl SimulatorConfig {
+    /// 使用预设名称（如 `strip-s17`）或规则文件（`.toml` / `.json`）指定的规则
+    pub fn with_rules_spec(mut self, spec: &str) -> Result<Self> {
+        self.rules = GameRules::from_spec(spec)?;
+        Ok(self)
+    }
+
+    /// 使用基础策略：`builtin` 为内置的手写策略表，`generated` 按当前规则生成，其他按策略表文件（`.csv` / `.json`）加载
+    ///
+    /// `generated` 需要在设置规则之后调用
+    pub fn with_strategy_spec(mut self, spec: &str) -> Result<Self> {
+        self.strategy = if spec.eq_ignore_ascii_case("builtin") {
+            None
+        } else if spec.eq_ignore_ascii_case("generated") {
+            Some(BasicStrategy::for_rules(&self.rules)?)
+        } else {
+            Some(BasicStrategy::from_file(spec)?)
+        };
+        Ok(self)
+    }
+
+    /// 使用内置指数打法表（`i18`、`fab4`、`i18-fab4`），`none` 表示只按基础策略
+    pub fn with_deviations_spec(mut self, spec: &str) -> Result<Self> {
+        self.deviations = if spec.eq_ignore_ascii_case("none") {
+            None
+        } else {
+            Some(DeviationTable::by_name(spec)?)
+        };
+        Ok(self)
+    }
+}
+
 /// 模拟器
 pub struct Simulator {
     config: SimulatorConfig,
@@ -33,17 +88,92 @@ pub struct Simulator {
 impl Simulator {
     /// 创建新的模拟器实例
     pub fn new(config: SimulatorConfig) -> Self {
-        let calculator = Calculator::new(config.rules.clone());
         Self {
+            calculator: Self::player_calculator(&config),
             config,
-            calculator,
         }
     }
 
+    /// 按配置中的规则、策略和指数打法决策的计算器
+    ///
+    /// 每个线程各自创建一个：计算器的引擎带锁，线程之间共享会让每个决策都排队
+    fn player_calculator(config: &SimulatorConfig) -> Calculator {
+        let mut calculator = Calculator::new(config.rules.clone());
+        if let Some(strategy) = &config.strategy {
+            calculator = calculator.with_strategy(strategy.clone());
+        }
+        if let Some(deviations) = &config.deviations {
+            calculator = calculator.with_deviations(deviations.clone());
+        }
+        calculator
+    }
+
     /// 运行模拟
+    ///
+    /// 局数平均分配到各线程，每个线程使用独立的牌靴、随机数种子和计算器，
+    /// 玩家按基础策略（遵守全部规则）行动，设置了指数打法时按算牌的 true count 偏离。
+    /// 每局原始投注为 1 个单位；设置了 Kelly 下注时按发牌前的 true count 估算优势下注，
+    /// 资金固定为设置中的 `bankroll`（不随输赢变化）
     pub fn run(&self) -> Result<SimulationResult> {
-        // TODO: 实现模拟逻辑
-        Ok(SimulationResult::default())
+        self.config.rules.validate()?;
+        let betting = match &self.config.betting {
+            Some(kelly) => {
+                kelly.validate()?;
+                Some((*kelly, CountAdvantage::from_calculator(&self.calculator)?))
+            }
+            None => None,
+        };
+        let threads = self.config.threads.max(1) as u64;
+        let seed = self.config.seed.unwrap_or_else(rand::random);
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads as usize)
+            .build()?;
+
+        let result = pool.install(|| {
+            (0..threads)
+                .into_par_iter()
+                .map(|worker| {
+                    let rounds = self.config.rounds / threads
+                        + u64::from(worker < self.config.rounds % threads);
+                    self.run_worker(seed.wrapping_add(worker), rounds, betting.as_ref())
+                })
+                .try_reduce(SimulationResult::default, |a, b| Ok(a.merge(b)))
+        })?;
+        Ok(result)
+    }
+
+    /// 单个线程用独立的牌靴模拟指定局数
+    fn run_worker(
+        &self,
+        seed: u64,
+        rounds: u64,
+        betting: Option<&(KellyBetting, CountAdvantage)>,
+    ) -> calculator::error::Result<SimulationResult> {
+        let calculator = Self::player_calculator(&self.config);
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut shoe = DealingShoe::new(self.config.rules.deck_count, self.config.penetration);
+        // 指数打法和下注使用同一个计数，只下注时按 Hi-Lo 计数
+        let system = match &self.config.deviations {
+            Some(deviations) => Some(deviations.system.clone()),
+            None => betting.map(|_| CountSystem::hi_lo()),
+        };
+        if let Some(system) = system {
+            shoe = shoe.with_counter(RunningCounter::new(system, &self.config.rules));
+        }
+        let mut result = SimulationResult::default();
+        for _ in 0..rounds {
+            if shoe.needs_shuffle() {
+                shoe.shuffle(&mut rng);
+            }
+            let bet = match (betting, shoe.true_count()) {
+                (Some((kelly, advantage)), Some(true_count)) => {
+                    kelly.bet_for_true_count(true_count, advantage)
+                }
+                _ => 1.0,
+            };
+            result.record(play_round(&calculator, &mut shoe, &mut rng)?.scaled(bet));
+        }
+        Ok(result)
     }
 }
 
@@ -62,7 +192,44 @@ pub struct SimulationResult {
     pub total_bet: f64,
     /// 总收益
     pub total_profit: f64,
+    /// 每局收益的平方和（用于计算标准差）
+    pub total_profit_squared: f64,
     /// 期望值
     pub expected_value: f64,
 }
 
+impl SimulationResult {
+    /// 记录一局的结果
+    fn record(&mut self, round: RoundResult) {
+        self.total_rounds += 1;
+        if round.profit > 0.0 {
+            self.player_wins += 1;
+        } else if round.profit < 0.0 {
+            self.dealer_wins += 1;
+        } else {
+            self.pushes += 1;
+        }
+        self.total_bet += round.wagered;
+        self.total_profit += round.profit;
+        self.total_profit_squared += round.profit * round.profit;
+        self.expected_value = self.total_profit / self.total_rounds as f64;
+    }
+
+    /// 合并两个线程的结果
+    fn merge(mut self, other: SimulationResult) -> SimulationResult {
+        self.total_rounds += other.total_rounds;
+        self.player_wins += other.player_wins;
+        self.dealer_wins += other.dealer_wins;
+        self.pushes += other.pushes;
+        self.total_bet += other.total_bet;
+        self.total_profit += other.total_profit;
+        self.total_profit_squared += other.total_profit_squared;
+        self.expected_value = if self.total_rounds == 0 {
+            0.0
+        } else {
+            self.total_profit / self.total_rounds as f64
+        };
+        self
+    }
+}
+
@@ -29,10 +29,23 @@ impl Statistics {
         self.result.total_profit / self.result.total_bet
     }
 
-    /// 计算标准差
+    /// 计算每局收益的样本标准差
     pub fn standard_deviation(&self) -> f64 {
-        // TODO: 实现标准差计算
-        0.0
+        let rounds = self.result.total_rounds as f64;
+        if rounds < 2.0 {
+            return 0.0;
+        }
+        let mean = self.result.total_profit / rounds;
+        let sum_of_squares = self.result.total_profit_squared - rounds * mean * mean;
+        (sum_of_squares.max(0.0) / (rounds - 1.0)).sqrt()
+    }
+
+    /// 每局期望收益的标准误差
+    pub fn standard_error(&self) -> f64 {
+        if self.result.total_rounds == 0 {
+            return 0.0;
+        }
+        self.standard_deviation() / (self.result.total_rounds as f64).sqrt()
     }
 }
 
@@ -0,0 +1,254 @@
+//! 单局游戏测试：按指定顺序发牌，检查玩家行动和结算
+
+use calculator::{
+    Action, Calculator, Card, CharlieRule, CountSystem, DeviationTable, GameRules, Hand, HoleCardRule,
+    PlayingCard, PointCounts, RunningCounter, Strategy, Suit, SurrenderRule,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use simulator::round::{play_round, DealingShoe, RoundResult};
+use simulator::simulator::{Simulator, SimulatorConfig};
+
+/// 按发牌顺序进行一局：玩家第一张、庄家明牌、玩家第二张、偷看规则下的暗牌，之后按需发牌
+///
+/// 检查这一局恰好用完 `cards`（多发的牌会触发随机洗牌）
+fn play_stacked(calculator: &Calculator, cards: &[Card]) -> RoundResult {
+    play_stacked_in(calculator, DealingShoe::from_cards(stacked(cards)))
+}
+
+fn stacked(cards: &[Card]) -> Vec<PlayingCard> {
+    cards.iter().map(|&card| PlayingCard { card, suit: Suit::Spades }).collect()
+}
+
+/// 玩家已经看到一百五十张小牌（Hi-Lo true count 约 +29）时，按 `cards` 进行一局
+fn play_stacked_high_count(calculator: &Calculator, cards: &[Card]) -> RoundResult {
+    let mut counter = RunningCounter::new(CountSystem::hi_lo(), calculator.rules());
+    for point in 2..=6 {
+        for _ in 0..30 {
+            counter.count(Card::Number(point)).unwrap();
+        }
+    }
+    play_stacked_in(calculator, DealingShoe::from_cards(stacked(cards)).with_counter(counter))
+}
+
+fn play_stacked_in(calculator: &Calculator, mut shoe: DealingShoe) -> RoundResult {
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+    let result = play_round(calculator, &mut shoe, &mut rng).unwrap();
+    assert!(shoe.needs_shuffle(), "这一局应当用完全部指定的牌");
+    result
+}
+
+fn rules_with(hole_card: HoleCardRule) -> GameRules {
+    GameRules {
+        hole_card,
+        ..GameRules::default()
+    }
+}
+
+/// 能加倍时总是加倍
+struct AlwaysDouble;
+
+impl Strategy for AlwaysDouble {
+    fn decide(&self, _hand: &Hand, _dealer_up_card: Card, _shoe: &PointCounts, _rules: &GameRules) -> Action {
+        Action::Double
+    }
+}
+
+/// 未见的牌里还有十点牌时停牌，否则一直要到 21 点
+///
+/// 同时检查策略收到的剩余牌组：十点牌合并在索引 9，索引 10 为 0
+struct StandOnTens;
+
+impl Strategy for StandOnTens {
+    fn decide(&self, hand: &Hand, _dealer_up_card: Card, shoe: &PointCounts, _rules: &GameRules) -> Action {
+        assert_eq!(shoe[10], 0);
+        if hand.value() >= 21 || shoe[9] > 0 {
+            Action::Stand
+        } else {
+            Action::Hit
+        }
+    }
+}
+
+#[test]
+fn test_blackjacks() {
+    let calculator = Calculator::with_default_rules();
+    let ten = Card::Face;
+
+    // 双方黑杰克平局；只有玩家黑杰克时按赔率赢
+    let result = play_stacked(&calculator, &[Card::Ace, Card::Ace, ten, ten]);
+    assert_eq!((result.wagered, result.profit), (1.0, 0.0));
+    let result = play_stacked(&calculator, &[Card::Ace, Card::Number(9), ten, Card::Number(8)]);
+    assert_eq!((result.wagered, result.profit), (1.0, 1.5));
+
+    let six_to_five = Calculator::new(GameRules {
+        blackjack_payout: 1.2,
+        ..GameRules::default()
+    });
+    let result = play_stacked(&six_to_five, &[ten, Card::Number(9), Card::Ace, Card::Number(8)]);
+    assert_eq!(result.profit, 1.2);
+
+    // 无暗牌：庄家的第二张牌同样可以凑成黑杰克
+    let enhc = Calculator::new(rules_with(HoleCardRule::NoHoleCard));
+    let result = play_stacked(&enhc, &[Card::Ace, ten, ten, Card::Ace]);
+    assert_eq!((result.wagered, result.profit), (1.0, 0.0));
+}
+
+#[test]
+fn test_peek_blackjack_takes_original_bet_only() {
+    // 偷看到黑杰克后立即结算：11 对 10 不会加倍，也不再发牌
+    let calculator = Calculator::with_default_rules();
+    let result = play_stacked(&calculator, &[Card::Number(6), Card::Face, Card::Number(5), Card::Ace]);
+    assert_eq!((result.wagered, result.profit), (1.0, -1.0));
+}
+
+#[test]
+fn test_early_surrender() {
+    // 16 对 A、暗牌为十点牌：早投降在偷看之前，只输一半；后投降时庄家黑杰克收走原始投注
+    let cards = [Card::Face, Card::Ace, Card::Number(6), Card::Face];
+    let surrender_rules = |surrender| GameRules {
+        surrender,
+        ..GameRules::default()
+    };
+    let early = Calculator::new(surrender_rules(SurrenderRule::Early));
+    assert_eq!(play_stacked(&early, &cards).profit, -0.5);
+    let late = Calculator::new(surrender_rules(SurrenderRule::Late));
+    assert_eq!(play_stacked(&late, &cards).profit, -1.0);
+}
+
+#[test]
+fn test_no_hole_card_blackjack_takes_extra_bets() {
+    // A,A 对 10 分牌后庄家补到黑杰克：ENHC 收走两手投注，OBO 只收原始投注
+    let split_cards = [Card::Ace, Card::Face, Card::Ace, Card::Number(9), Card::Number(8), Card::Ace];
+    let enhc = Calculator::new(rules_with(HoleCardRule::NoHoleCard));
+    let result = play_stacked(&enhc, &split_cards);
+    assert_eq!((result.wagered, result.profit), (2.0, -2.0));
+    let obo = Calculator::new(rules_with(HoleCardRule::NoHoleCardOriginalBetsOnly));
+    let result = play_stacked(&obo, &split_cards);
+    assert_eq!((result.wagered, result.profit), (2.0, -1.0));
+
+    // 加倍的追加投注同理
+    let double_cards = [Card::Number(6), Card::Face, Card::Number(5), Card::Number(9), Card::Ace];
+    let enhc = Calculator::new(rules_with(HoleCardRule::NoHoleCard)).with_strategy(AlwaysDouble);
+    let result = play_stacked(&enhc, &double_cards);
+    assert_eq!((result.wagered, result.profit), (2.0, -2.0));
+    let obo = Calculator::new(rules_with(HoleCardRule::NoHoleCardOriginalBetsOnly)).with_strategy(AlwaysDouble);
+    let result = play_stacked(&obo, &double_cards);
+    assert_eq!((result.wagered, result.profit), (2.0, -1.0));
+}
+
+#[test]
+fn test_strategy_sees_unseen_cards() {
+    let calculator = Calculator::new(GameRules {
+        surrender: SurrenderRule::None,
+        ..GameRules::default()
+    })
+    .with_strategy(StandOnTens);
+
+    // 17 对 9：暗牌 J 对玩家仍是未见的牌，停牌后输给庄家 19
+    let result = play_stacked(&calculator, &[Card::Face, Card::Number(9), Card::Number(7), Card::Face]);
+    assert_eq!((result.wagered, result.profit), (1.0, -1.0));
+
+    // 未见的牌里没有十点牌：要到 21 点，赢庄家 17
+    let cards = [Card::Number(10), Card::Number(9), Card::Number(7), Card::Number(8), Card::Number(4)];
+    let result = play_stacked(&calculator, &cards);
+    assert_eq!((result.wagered, result.profit), (1.0, 1.0));
+}
+
+#[test]
+fn test_split_aces_are_not_resplit() {
+    // 分A后每手只发一张牌：再拿到A也不能再分，两手软12停牌；庄家16点要牌爆牌
+    let calculator = Calculator::new(GameRules {
+        allow_resplit: true,
+        max_split_hands: 4,
+        ..GameRules::default()
+    });
+    let cards = [
+        Card::Ace,
+        Card::Number(6),
+        Card::Ace,
+        Card::Face,
+        Card::Ace,
+        Card::Ace,
+        Card::Face,
+    ];
+    let result = play_stacked(&calculator, &cards);
+    assert_eq!((result.wagered, result.profit), (2.0, 2.0));
+
+    // 其他对子可以再分牌
+    let cards = [
+        Card::Number(8),
+        Card::Number(6),
+        Card::Number(8),
+        Card::Face,
+        Card::Number(8),
+        Card::Face,
+        Card::Face,
+        Card::Face,
+        Card::Face,
+    ];
+    let result = play_stacked(&calculator, &cards);
+    assert_eq!((result.wagered, result.profit), (3.0, 3.0));
+}
+
+#[test]
+fn test_charlie_wins_before_dealer_plays() {
+    // 2,3,2,2,2 对 10：五张牌 13 点达到 Charlie，庄家 17 点也输
+    let calculator = Calculator::new(GameRules {
+        charlie: Some(CharlieRule::five_card()),
+        ..GameRules::default()
+    });
+    let two = Card::Number(2);
+    let cards = [two, Card::Face, Card::Number(3), Card::Number(7), two, two, two];
+    let result = play_stacked(&calculator, &cards);
+    assert_eq!((result.wagered, result.profit), (1.0, 1.0));
+}
+
+#[test]
+fn test_insurance_by_count() {
+    let ten = Card::Face;
+    let counting = Calculator::with_default_rules().with_deviations(DeviationTable::illustrious_18());
+
+    // 20 对 A：true count 达到保险指数时买保险，庄家黑杰克时保险赔 2:1 抵消原始投注
+    let result = play_stacked_high_count(&counting, &[ten, Card::Ace, ten, ten]);
+    assert_eq!((result.wagered, result.profit), (1.5, 0.0));
+    let result = play_stacked_high_count(&counting, &[ten, Card::Ace, ten, Card::Number(7)]);
+    assert_eq!((result.wagered, result.profit), (1.5, 0.5));
+
+    // 玩家黑杰克时购买保险即等额赔付
+    let result = play_stacked_high_count(&counting, &[ten, Card::Ace, Card::Ace, Card::Number(7)]);
+    assert_eq!((result.wagered, result.profit), (1.5, 1.0));
+    let result = play_stacked_high_count(&counting, &[ten, Card::Ace, Card::Ace, ten]);
+    assert_eq!((result.wagered, result.profit), (1.5, 1.0));
+
+    // 不算牌或 true count 不够时不买保险
+    let result = play_stacked(&counting, &[ten, Card::Ace, ten, ten]);
+    assert_eq!((result.wagered, result.profit), (1.0, -1.0));
+    let basic = Calculator::with_default_rules();
+    let result = play_stacked_high_count(&basic, &[ten, Card::Ace, ten, ten]);
+    assert_eq!((result.wagered, result.profit), (1.0, -1.0));
+
+    // 无暗牌：庄家的第二张牌在玩家行动之后才发；20 对 20 平局只输保险
+    let enhc = Calculator::new(rules_with(HoleCardRule::NoHoleCard)).with_deviations(DeviationTable::illustrious_18());
+    let result = play_stacked_high_count(&enhc, &[ten, Card::Ace, ten, ten]);
+    assert_eq!((result.wagered, result.profit), (1.5, 0.0));
+    let result = play_stacked_high_count(&enhc, &[ten, Card::Ace, ten, Card::Number(9)]);
+    assert_eq!((result.wagered, result.profit), (1.5, -0.5));
+}
+
+#[test]
+fn test_seeded_simulation_is_reproducible() {
+    let config = SimulatorConfig {
+        rounds: 20_000,
+        threads: 2,
+        seed: Some(42),
+        ..SimulatorConfig::default()
+    };
+    let first = Simulator::new(config.clone()).run().unwrap();
+    let second = Simulator::new(config).run().unwrap();
+    assert_eq!(first.total_rounds, 20_000);
+    assert_eq!(first.total_profit, second.total_profit);
+    assert_eq!(first.total_bet, second.total_bet);
+    // 默认规则下基础策略的 EV 约为 -0.5%，两万局的标准误差约 0.8%
+    assert!(first.expected_value.abs() < 0.04, "EV: {}", first.expected_value);
+}
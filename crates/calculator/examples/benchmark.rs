@@ -43,7 +43,7 @@ fn main() {
     let start = Instant::now();
     
     // 计算上桌 EV
-    let result = calculator.calculate_table_ev(&deck);
+    let result = calculator.calculate_table_ev(&deck).unwrap();
     
     // 结束计时
     let duration = start.elapsed();
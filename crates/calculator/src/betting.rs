@@ -0,0 +1,145 @@
+//! 按 Kelly 准则确定下注额
+//!
+//! 最优下注 = 资金 × Kelly 比例 × 优势 / 每局方差。优势可以直接取上桌 EV
+//! （`TableEVResult::ev`，同时使用其中的每局方差），也可以由 true count 估算（`CountAdvantage`）。
+//! 下注额向下取整到筹码单位，再限制在桌台最小注和最大注之间：
+//! 没有优势时仍下最小注（不离桌）。
+
+use crate::calculator::{create_full_deck, Calculator};
+use crate::error::{CalculatorError, Result};
+use crate::rules::GameRules;
+use crate::types::TableEVResult;
+use serde::{Deserialize, Serialize};
+
+/// 每 +1 true count 增加的优势（Hi-Lo 的常用估计值 0.5%）
+pub const ADVANTAGE_PER_TRUE_COUNT: f64 = 0.005;
+
+/// 由 true count 估算优势：完整牌靴的上桌 EV 加上每点 true count 的优势
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CountAdvantage {
+    /// 完整牌靴（true count 为 0）的上桌 EV
+    pub base_ev: f64,
+    /// 每 +1 true count 增加的优势
+    pub per_true_count: f64,
+}
+
+impl CountAdvantage {
+    /// 指定完整牌靴的上桌 EV，每点 true count 按 `ADVANTAGE_PER_TRUE_COUNT` 计
+    pub fn new(base_ev: f64) -> Self {
+        Self {
+            base_ev,
+            per_true_count: ADVANTAGE_PER_TRUE_COUNT,
+        }
+    }
+
+    /// 按规则计算完整牌靴的上桌 EV（基础策略）
+    ///
+    /// # Errors
+    /// 规则无效时返回相应错误
+    pub fn from_rules(rules: &GameRules) -> Result<Self> {
+        Self::from_calculator(&Calculator::new(rules.clone()))
+    }
+
+    /// 用已配置的计算器（策略模式、指数打法）计算完整牌靴的上桌 EV
+    ///
+    /// # Errors
+    /// 规则无效时返回相应错误
+    pub fn from_calculator(calculator: &Calculator) -> Result<Self> {
+        let deck = create_full_deck(calculator.rules().deck_count);
+        Ok(Self::new(calculator.calculate_table_ev(&deck)?.ev))
+    }
+
+    /// 指定 true count 下的估计优势
+    pub fn advantage(&self, true_count: f64) -> f64 {
+        self.base_ev + self.per_true_count * true_count
+    }
+}
+
+/// Kelly 下注设置
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct KellyBetting {
+    /// 当前资金
+    pub bankroll: f64,
+    /// Kelly 比例（1.0 为完整 Kelly，0.5 为半 Kelly）
+    pub kelly_fraction: f64,
+    /// 每局净收益的方差（以一个单位的原始投注计，常见规则约 1.3；
+    /// 按上桌 EV 下注时改用 `TableEVResult::variance`）
+    pub variance: f64,
+    /// 桌台最小注
+    pub min_bet: f64,
+    /// 桌台最大注
+    pub max_bet: f64,
+    /// 筹码单位（下注额向下取整到它的整数倍）
+    pub chip_unit: f64,
+}
+
+impl KellyBetting {
+    /// 检查设置
+    ///
+    /// # Errors
+    /// 资金为负、Kelly 比例不在 (0, 1]、方差或筹码单位不为正、
+    /// 最小注为负或大于最大注时返回 `InvalidBetting`
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |message: &str| Err(CalculatorError::InvalidBetting(message.to_string()));
+        if !self.bankroll.is_finite() || self.bankroll < 0.0 {
+            return invalid("资金必须是非负数");
+        }
+        if !(self.kelly_fraction > 0.0 && self.kelly_fraction <= 1.0) {
+            return invalid("Kelly 比例必须在 (0, 1] 之间");
+        }
+        if !self.variance.is_finite() || self.variance <= 0.0 {
+            return invalid("方差必须是正数");
+        }
+        if !self.chip_unit.is_finite() || self.chip_unit <= 0.0 {
+            return invalid("筹码单位必须是正数");
+        }
+        if !(self.min_bet >= 0.0 && self.min_bet <= self.max_bet && self.max_bet.is_finite()) {
+            return invalid("最小注必须是非负数且不超过最大注");
+        }
+        Ok(())
+    }
+
+    /// 设置资金（资金随输赢变化时更新）
+    pub fn with_bankroll(mut self, bankroll: f64) -> Self {
+        self.bankroll = bankroll;
+        self
+    }
+
+    /// 不考虑桌台限制和筹码单位的 Kelly 下注额（没有优势时为 0）
+    pub fn kelly_bet(&self, advantage: f64) -> f64 {
+        (self.bankroll * self.kelly_fraction * advantage / self.variance).max(0.0)
+    }
+
+    /// 指定优势下的下注额：Kelly 下注额向下取整到筹码单位，限制在最小注和最大注之间
+    pub fn bet_for(&self, advantage: f64) -> f64 {
+        let chips = (self.kelly_bet(advantage) / self.chip_unit).floor();
+        (chips * self.chip_unit).min(self.max_bet).max(self.min_bet)
+    }
+
+    /// 以上桌 EV 作为优势的下注额，方差取该牌组的每局方差（没有时用设置中的方差）
+    pub fn bet_for_table_ev(&self, table_ev: &TableEVResult) -> f64 {
+        if table_ev.variance > 0.0 {
+            Self { variance: table_ev.variance, ..*self }.bet_for(table_ev.ev)
+        } else {
+            self.bet_for(table_ev.ev)
+        }
+    }
+
+    /// 以 true count 估算优势的下注额
+    pub fn bet_for_true_count(&self, true_count: f64, advantage: &CountAdvantage) -> f64 {
+        self.bet_for(advantage.advantage(true_count))
+    }
+}
+
+impl Default for KellyBetting {
+    fn default() -> Self {
+        Self {
+            bankroll: 10000.0,
+            kelly_fraction: 0.5,
+            variance: 1.3,
+            min_bet: 10.0,
+            max_bet: 200.0,
+            chip_unit: 5.0,
+        }
+    }
+}
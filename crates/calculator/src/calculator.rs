@@ -1,47 +1,412 @@
 //! Blackjack 计算器核心逻辑
 
+use crate::error::{CalculatorError, Result};
 use crate::rules::GameRules;
-use crate::types::{CardCounts, TableEVResult};
+use crate::types::{
+    Action, Card, CardCounts, DealerDistribution, DecisionEV, EffectsOfRemoval, Hand, InsuranceEV,
+    InsurancePolicy, PayoutDistribution, RankEffect, StrategyComparison, StrategyMode, TableEVResult,
+};
 use crate::probability_calculator::ProbabilityCalculator;
+use crate::deviations::DeviationTable;
 use crate::ev_calculator::calculate_ev;
+use crate::strategy::{BasicStrategy, Strategy};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+#[cfg(feature = "exact")]
+use crate::exact::{ExactCalculator, ExactEVResult};
+#[cfg(feature = "exact")]
+use num_traits::ToPrimitive;
 
 /// Blackjack 计算器
+/// 
+/// 每种策略模式持有一个概率计算器，记忆化结果在多次调用之间保留：
+/// 对几乎相同的剩余牌组连续查询时，大部分庄家和玩家子状态可以直接复用
 pub struct Calculator {
     rules: GameRules,
+    mode: StrategyMode,
+    insurance: InsurancePolicy,
+    deviations: Option<DeviationTable>,
+    strategy: Arc<dyn Strategy>,
+    basic_engine: Mutex<ProbabilityCalculator>,
+    optimal_engine: Mutex<ProbabilityCalculator>,
 }
 
 impl Calculator {
-    /// 创建新的计算器实例
+    /// 创建新的计算器实例（按基础策略决策）
     pub fn new(rules: GameRules) -> Self {
-        Self { rules }
+        Self::with_strategy_mode(rules, StrategyMode::Basic)
     }
 
     /// 使用默认规则创建计算器
     pub fn with_default_rules() -> Self {
+        Self::new(GameRules::default())
+    }
+
+    /// 创建指定策略模式的计算器
+    /// 
+    /// `StrategyMode::Optimal` 下每个决策都选择当前剩余牌组下精确 EV 最高的动作
+    pub fn with_strategy_mode(rules: GameRules, mode: StrategyMode) -> Self {
+        let engine = |mode| Mutex::new(ProbabilityCalculator::with_mode(rules.clone(), mode));
         Self {
-            rules: GameRules::default(),
+            basic_engine: engine(StrategyMode::Basic),
+            optimal_engine: engine(StrategyMode::Optimal),
+            rules,
+            mode,
+            insurance: InsurancePolicy::Never,
+            deviations: None,
+            strategy: Arc::new(BasicStrategy::new()),
         }
     }
 
+    /// 设置保险策略
+    /// 
+    /// `InsurancePolicy::WhenPositive` 下，庄家明牌为A且保险 EV 为正时购买保险，
+    /// 保险的收益计入上桌 EV 的 `ev_insurance`
+    pub fn with_insurance_policy(mut self, policy: InsurancePolicy) -> Self {
+        self.insurance = policy;
+        for engine in [&mut self.basic_engine, &mut self.optimal_engine] {
+            engine
+                .get_mut()
+                .unwrap_or_else(PoisonError::into_inner)
+                .set_insurance_policy(policy);
+        }
+        self
+    }
+
+    /// 设置指数打法（如 `DeviationTable::illustrious_18()`）
+    ///
+    /// 基础策略模式下，每个决策点按当前剩余牌组的 true count 偏离基础策略；
+    /// 表中设置了保险指数时（如 Illustrious 18 的 +3），庄家明牌为A时同样按 true count
+    /// 决定是否购买保险，取代保险策略。最优策略模式不受影响
+    pub fn with_deviations(mut self, deviations: DeviationTable) -> Self {
+        self.basic_engine
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .set_deviations(Some(deviations.clone()));
+        self.deviations = Some(deviations);
+        self
+    }
+
+    /// 设置基础策略模式下使用的策略（默认为 `BasicStrategy`）
+    ///
+    /// 引擎在每个决策点把当前阶段的合法动作交给 `Strategy::decide_among`，
+    /// 指数打法在策略的动作之上偏离；最优策略模式不受影响
+    pub fn with_strategy(mut self, strategy: impl Strategy + 'static) -> Self {
+        self.strategy = Arc::new(strategy);
+        self.basic_engine
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .set_strategy(self.strategy.clone());
+        self
+    }
+
+    /// 游戏规则
+    pub fn rules(&self) -> &GameRules {
+        &self.rules
+    }
+
+    /// 当前策略模式
+    pub fn strategy_mode(&self) -> StrategyMode {
+        self.mode
+    }
+
+    /// 当前保险策略
+    pub fn insurance_policy(&self) -> InsurancePolicy {
+        self.insurance
+    }
+
+    /// 当前指数打法
+    pub fn deviations(&self) -> Option<&DeviationTable> {
+        self.deviations.as_ref()
+    }
+
+    /// 基础策略模式下使用的策略
+    pub fn strategy(&self) -> &dyn Strategy {
+        self.strategy.as_ref()
+    }
+
+    /// 缓存的子状态数量（所有策略模式之和）
+    pub fn cache_size(&self) -> usize {
+        let basic = self.engine(StrategyMode::Basic).cache_size();
+        basic + self.engine(StrategyMode::Optimal).cache_size()
+    }
+
+    /// 清空跨调用保留的缓存
+    pub fn clear_cache(&self) {
+        self.engine(StrategyMode::Basic).clear_cache();
+        self.engine(StrategyMode::Optimal).clear_cache();
+    }
+
     /// 计算上桌 EV（是否上桌的期望值）
     /// 
     /// 综合考虑所有可能的游戏情况：
     /// - 普通投注（Hit/Stand）
     /// - 加倍投注（Double Down）
     /// - 投降（Surrender）
+    /// - 分牌（Split）
+    /// - 保险（Insurance，按保险策略）
     /// 
-    /// 玩家按照基础策略进行游戏，最终返回一个综合的 EV 值。
+    /// 玩家按照当前策略模式进行游戏，最终返回一个综合的 EV 值。
     /// 
     /// # Arguments
     /// * `deck` - 当前剩余牌组信息
     /// 
     /// # Returns
     /// `TableEVResult` 包含期望值和各种概率
-    pub fn calculate_table_ev(&self, deck: &CardCounts) -> TableEVResult {
-        let mut calculator = ProbabilityCalculator::new(self.rules.clone());
-        let outcome = calculator.calculate_table_ev(deck);
+    /// 
+    /// # Errors
+    /// 规则无效、牌组不可能来自规则中的牌组数量，或剩余牌少于 4 张
+    pub fn calculate_table_ev(&self, deck: &CardCounts) -> Result<TableEVResult> {
+        self.validate(deck, &[], 4)?;
+        Ok(self.table_ev_with_mode(self.mode, deck))
+    }
+
+    /// 计算一局净收益的概率分布
+    ///
+    /// 与 `calculate_table_ev` 使用相同的策略模式和保险策略，分布的 EV 和方差
+    /// 与 `TableEVResult` 相同，另外可以得到偏度、输钱概率、分位数等风险指标。
+    /// 分牌局的分布按庄家最终结果卷积各手的收益，比上桌 EV 的计算慢（不并行）
+    ///
+    /// # Errors
+    /// 与 `calculate_table_ev` 相同
+    pub fn calculate_payout_distribution(&self, deck: &CardCounts) -> Result<PayoutDistribution> {
+        self.validate(deck, &[], 4)?;
+        Ok(self.engine(self.mode).calculate_payout_distribution(deck))
+    }
+
+    /// 同时计算基础策略和组合依赖最优策略的上桌 EV
+    /// 
+    /// 用于衡量固定策略表在当前剩余牌组下损失了多少 EV
+    pub fn compare_strategies(&self, deck: &CardCounts) -> Result<StrategyComparison> {
+        self.validate(deck, &[], 4)?;
+        let basic = self.table_ev_with_mode(StrategyMode::Basic, deck);
+        let optimal = self.table_ev_with_mode(StrategyMode::Optimal, deck);
+        let ev_gain = optimal.ev - basic.ev;
+        Ok(StrategyComparison {
+            basic,
+            optimal,
+            ev_gain,
+        })
+    }
+
+    /// 计算每种点数（A、2-9、十点牌）的移除效应（EOR）
+    ///
+    /// 从起始牌组移除一张该点数的牌后重新计算上桌 EV，与起始牌组的 EV 相减。
+    /// 十点牌优先移除 J/Q/K，没有时移除 10。
+    ///
+    /// # Errors
+    /// 规则或牌组不合法时返回相应错误；牌组少于 5 张时返回 `InsufficientCards`
+    pub fn effects_of_removal(&self, deck: &CardCounts) -> Result<EffectsOfRemoval> {
+        self.validate(deck, &[], 5)?;
+        let baseline_ev = self.table_ev_with_mode(self.mode, deck).ev;
+
+        let count_of = |card| deck.get(&card).copied().unwrap_or(0);
+        let mut ranks = Vec::with_capacity(10);
+        for rank in 1..=10 {
+            let card = if rank == 1 { Card::Ace } else { Card::Number(rank) };
+            let (count, removed) = if rank == 10 {
+                let faces = count_of(Card::Face);
+                let removed = if faces > 0 { Card::Face } else { Card::Number(10) };
+                (faces + count_of(Card::Number(10)), removed)
+            } else {
+                (count_of(card), card)
+            };
+            if count == 0 {
+                continue;
+            }
+
+            let mut reduced = deck.clone();
+            *reduced.entry(removed).or_insert(0) -= 1;
+            let ev = self.table_ev_with_mode(self.mode, &reduced).ev;
+            ranks.push(RankEffect {
+                card,
+                count,
+                ev,
+                eor: ev - baseline_ev,
+            });
+        }
+        Ok(EffectsOfRemoval { baseline_ev, ranks })
+    }
+
+    /// 计算某个决策点上每个合法动作的精确 EV
+    /// 
+    /// 合法动作由手牌和规则决定：两张牌时可加倍，规则允许时可分牌（对子）和投降；
+    /// 要牌之后只能要牌或停牌。执行动作之后的决策按当前策略模式进行。
+    /// 
+    /// # Arguments
+    /// * `player_hand` - 玩家手牌
+    /// * `dealer_up_card` - 庄家明牌
+    /// * `deck` - 未见的剩余牌组（不含玩家手牌和庄家明牌）
+    /// 
+    /// # Returns
+    /// `DecisionEV` 包含每个合法动作的 EV（庄家已偷看时为没有黑杰克的条件 EV）
+    /// 
+    /// # Errors
+    /// 规则或牌面无效、手牌和明牌加上剩余牌组超过牌组数量，或剩余牌组为空
+    pub fn calculate_action_evs(
+        &self,
+        player_hand: &Hand,
+        dealer_up_card: Card,
+        deck: &CardCounts,
+    ) -> Result<DecisionEV> {
+        let mut seen: Vec<Card> = player_hand.cards.iter().map(|card| card.card).collect();
+        seen.push(dealer_up_card);
+        self.validate(deck, &seen, 1)?;
+
+        Ok(DecisionEV {
+            actions: self
+                .engine(self.mode)
+                .calculate_action_evs(player_hand, dealer_up_card, deck),
+        })
+    }
+
+    /// 按基础策略决定实际牌局中一手牌的动作
+    ///
+    /// 与 EV 计算中的基础策略一致：分牌、投降、加倍不允许时回退，
+    /// 并按无暗牌和 Charlie 规则调整。`hand_count` 为这一局当前的总手数（1 表示还没有分牌），
+    /// `unseen` 为玩家未见的剩余牌组（不含玩家手牌和庄家明牌，庄家暗牌在其中），
+    /// 按 EV 计算的点数布局交给策略
+    ///
+    /// # Errors
+    /// 规则无效，或手牌、明牌、剩余牌组中有无效牌面，或剩余牌组超出牌组数量
+    pub fn basic_action(
+        &self,
+        player_hand: &Hand,
+        dealer_up_card: Card,
+        hand_count: u8,
+        unseen: &CardCounts,
+    ) -> Result<Action> {
+        self.validate_play(player_hand, dealer_up_card, unseen)?;
+        Ok(self
+            .engine(StrategyMode::Basic)
+            .basic_play(player_hand, dealer_up_card, hand_count, unseen, None))
+    }
+
+    /// 按基础策略和指数打法决定实际牌局中一手牌的动作
+    ///
+    /// 与 `basic_action` 相同，设置了指数打法时按玩家记录的 `true_count` 偏离基础策略
+    ///
+    /// # Errors
+    /// 规则无效，或手牌、明牌、剩余牌组中有无效牌面，或剩余牌组超出牌组数量
+    pub fn index_action(
+        &self,
+        player_hand: &Hand,
+        dealer_up_card: Card,
+        hand_count: u8,
+        unseen: &CardCounts,
+        true_count: f64,
+    ) -> Result<Action> {
+        self.validate_play(player_hand, dealer_up_card, unseen)?;
+        Ok(self
+            .engine(StrategyMode::Basic)
+            .basic_play(player_hand, dealer_up_card, hand_count, unseen, Some(true_count)))
+    }
+
+    /// 计算庄家明牌为A时保险和等额赔付的精确 EV
+    /// 
+    /// # Arguments
+    /// * `deck` - 未见的剩余牌组（不含玩家手牌和庄家明牌A）
+    /// 
+    /// # Errors
+    /// 规则无效、明牌A加上剩余牌组超过牌组数量，或剩余牌组为空
+    pub fn calculate_insurance(&self, deck: &CardCounts) -> Result<InsuranceEV> {
+        self.validate(deck, &[Card::Ace], 1)?;
+        Ok(self.engine(self.mode).calculate_insurance(deck))
+    }
+
+    /// 计算庄家最终点数分布（17-21、黑杰克、爆牌）
+    /// 
+    /// 遵循 `dealer_stands_on_soft_17` 规则。结果包含黑杰克，
+    /// 需要偷看后的分布时使用 `DealerDistribution::without_blackjack`。
+    /// 
+    /// # Arguments
+    /// * `dealer_up_card` - 庄家明牌，`None` 表示明牌未知
+    /// * `deck` - 剩余牌组（明牌已知时不含明牌）
+    /// 
+    /// # Errors
+    /// 规则或牌面无效、牌组超过牌组数量，或剩余牌不够庄家补牌
+    pub fn dealer_distribution(
+        &self,
+        dealer_up_card: Option<Card>,
+        deck: &CardCounts,
+    ) -> Result<DealerDistribution> {
+        match dealer_up_card {
+            Some(up_card) => self.validate(deck, &[up_card], 1)?,
+            None => self.validate(deck, &[], 2)?,
+        }
+        Ok(self.engine(self.mode).calculate_dealer_distribution(dealer_up_card, deck))
+    }
+
+    /// 用精确有理数重新计算上桌 EV，并给出浮点结果的偏差
+    /// 
+    /// 与 `calculate_table_ev` 使用相同的策略模式、保险策略和枚举，适用于一两副牌的小牌组
+    #[cfg(feature = "exact")]
+    pub fn calculate_exact_ev(&self, deck: &CardCounts) -> Result<ExactEVResult> {
+        let float_ev = self.calculate_table_ev(deck)?.ev;
+        let mut exact = ExactCalculator::with_mode(self.rules.clone(), self.mode)
+            .with_insurance_policy(self.insurance)
+            .with_strategy(self.strategy.clone());
+        if let Some(deviations) = &self.deviations {
+            exact = exact.with_deviations(deviations.clone());
+        }
+        let ev = exact.calculate_table_ev(deck);
+        let deviation = float_ev - ev.to_f64().unwrap_or(f64::NAN);
+        Ok(ExactEVResult {
+            ev,
+            float_ev,
+            deviation,
+        })
+    }
+
+    /// 检查规则和牌组
+    /// 
+    /// `seen` 为已知但不在 `deck` 中的牌（玩家手牌、庄家明牌），与 `deck` 合计不能超过牌组数量；
+    /// `deck` 至少要有 `required` 张牌
+    fn validate(&self, deck: &CardCounts, seen: &[Card], required: u32) -> Result<()> {
+        self.rules.validate()?;
+
+        let mut all_cards = deck.clone();
+        for &card in seen {
+            if !card.is_valid() {
+                return Err(CalculatorError::InvalidRank(card));
+            }
+            *all_cards.entry(card).or_insert(0) += 1;
+        }
+        self.rules.validate_deck(&all_cards)?;
+
+        let available = deck.values().sum();
+        if available < required {
+            return Err(CalculatorError::InsufficientCards { required, available });
+        }
+        Ok(())
+    }
+
+    /// 检查实际牌局中的规则、牌面和玩家未见的剩余牌组
+    ///
+    /// 玩家可能没有看到全部已发出的牌，剩余牌组不必和手牌一起凑成完整的牌组
+    fn validate_play(&self, player_hand: &Hand, dealer_up_card: Card, unseen: &CardCounts) -> Result<()> {
+        self.rules.validate()?;
+        let cards = player_hand.cards.iter().map(|card| card.card);
+        if let Some(card) = cards.chain([dealer_up_card]).find(|card| !card.is_valid()) {
+            return Err(CalculatorError::InvalidRank(card));
+        }
+        self.rules.validate_deck(unseen)?;
+        Ok(())
+    }
+
+    /// 按指定策略模式计算上桌 EV
+    fn table_ev_with_mode(&self, mode: StrategyMode, deck: &CardCounts) -> TableEVResult {
+        let outcome = self.engine(mode).calculate_table_ev(deck);
         calculate_ev(&outcome, &self.rules)
     }
+
+    /// 指定策略模式的概率计算器（带跨调用缓存）
+    fn engine(&self, mode: StrategyMode) -> MutexGuard<'_, ProbabilityCalculator> {
+        let engine = match mode {
+            StrategyMode::Basic => &self.basic_engine,
+            StrategyMode::Optimal => &self.optimal_engine,
+        };
+        engine.lock().unwrap_or_else(PoisonError::into_inner)
+    }
 }
 
 impl Default for Calculator {
@@ -52,10 +417,15 @@ impl Default for Calculator {
 
 /// 创建完整 8 副牌
 pub fn create_full_8_deck() -> CardCounts {
+    create_full_deck(8)
+}
+
+/// 创建指定副数的完整牌组
+pub fn create_full_deck(deck_count: u8) -> CardCounts {
     let mut deck = CardCounts::new();
     
-    // 8副牌，每副52张
-    for _ in 0..8 {
+    // 每副52张
+    for _ in 0..deck_count {
         // A
         *deck.entry(crate::types::Card::Ace).or_insert(0) += 4;
         // 2-10
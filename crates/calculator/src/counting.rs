@@ -0,0 +1,307 @@
+//! 算牌系统：计数标签、running count 和 true count
+//!
+//! `CountSystem` 为每种点数（A、2-9、十点牌）指定一个标签，内置常见系统
+//! （Hi-Lo、KO、Hi-Opt I/II、Omega II、Zen、Wong Halves），也可以自定义标签。
+//! 计数可以从剩余牌组（`CardCounts`）一次算出，也可以用 `RunningCounter` 逐张累加。
+//!
+//! 不平衡系统（如 KO）一副牌的标签之和不为 0，running count 从
+//! `initial_running_count` 开始，使其在牌靴只剩一副牌时的期望值为 0。
+
+use crate::error::{CalculatorError, Result};
+use crate::rules::GameRules;
+use crate::types::{card_counts_to_point_counts, Card, CardCounts, EffectsOfRemoval, PointCounts};
+use serde::{Deserialize, Serialize};
+
+/// 每副牌的张数
+const CARDS_PER_DECK: f64 = 52.0;
+
+/// 标签数组中的索引：0=A, 1..=8 为 2-9, 9=十点牌（10/J/Q/K）
+fn tag_index(card: Card) -> Option<usize> {
+    match card {
+        Card::Ace => Some(0),
+        Card::Number(10) | Card::Face => Some(9),
+        Card::Number(n) if (2..=9).contains(&n) => Some(n as usize - 1),
+        Card::Number(_) => None,
+    }
+}
+
+/// 剩余牌数对应的剩余副数（按每副 52 张估算）
+pub fn decks_for_cards(cards: u32) -> f64 {
+    cards as f64 / CARDS_PER_DECK
+}
+
+/// 按规则中的牌组数量和已发出的张数估算剩余副数
+pub fn decks_remaining(rules: &GameRules, cards_seen: u32) -> f64 {
+    let total = rules.deck_count as u32 * CARDS_PER_DECK as u32;
+    decks_for_cards(total.saturating_sub(cards_seen))
+}
+
+/// running count 除以剩余副数（剩余不足一张时按一张计，避免除以 0）
+fn true_count_for(running_count: f64, cards_remaining: u32) -> f64 {
+    running_count / decks_for_cards(cards_remaining.max(1))
+}
+
+/// 算牌系统
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CountSystem {
+    /// 系统名称
+    pub name: String,
+    /// 每种点数的标签，索引：0=A, 1..=8 为 2-9, 9=十点牌
+    pub tags: [f64; 10],
+}
+
+impl CountSystem {
+    /// 自定义标签的算牌系统
+    pub fn new(name: impl Into<String>, tags: [f64; 10]) -> Self {
+        Self {
+            name: name.into(),
+            tags,
+        }
+    }
+
+    /// Hi-Lo：2-6 为 +1，10/A 为 -1
+    pub fn hi_lo() -> Self {
+        Self::new("hi-lo", [-1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, -1.0])
+    }
+
+    /// KO（Knock-Out）：在 Hi-Lo 基础上 7 也为 +1，不平衡
+    pub fn ko() -> Self {
+        Self::new("ko", [-1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0, -1.0])
+    }
+
+    /// Hi-Opt I：3-6 为 +1，十点牌为 -1，A 不计
+    pub fn hi_opt_1() -> Self {
+        Self::new("hi-opt-1", [0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, -1.0])
+    }
+
+    /// Hi-Opt II：二级系统，A 不计
+    pub fn hi_opt_2() -> Self {
+        Self::new("hi-opt-2", [0.0, 1.0, 1.0, 2.0, 2.0, 1.0, 1.0, 0.0, 0.0, -2.0])
+    }
+
+    /// Omega II：二级系统，9 为 -1，A 不计
+    pub fn omega_2() -> Self {
+        Self::new("omega-2", [0.0, 1.0, 1.0, 2.0, 2.0, 2.0, 1.0, 0.0, -1.0, -2.0])
+    }
+
+    /// Zen：二级系统，A 为 -1
+    pub fn zen() -> Self {
+        Self::new("zen", [-1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 1.0, 0.0, 0.0, -2.0])
+    }
+
+    /// Wong Halves：三级系统，带半点标签
+    pub fn wong_halves() -> Self {
+        Self::new(
+            "wong-halves",
+            [-1.0, 0.5, 1.0, 1.0, 1.5, 1.0, 0.5, 0.0, -0.5, -1.0],
+        )
+    }
+
+    /// 全部内置系统
+    pub fn builtin() -> Vec<CountSystem> {
+        vec![
+            Self::hi_lo(),
+            Self::ko(),
+            Self::hi_opt_1(),
+            Self::hi_opt_2(),
+            Self::omega_2(),
+            Self::zen(),
+            Self::wong_halves(),
+        ]
+    }
+
+    /// 按名称取内置系统（不区分大小写）
+    ///
+    /// # Errors
+    /// 名称不存在时返回 `UnknownCountSystem`
+    pub fn by_name(name: &str) -> Result<CountSystem> {
+        Self::builtin()
+            .into_iter()
+            .find(|system| system.name.eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| CalculatorError::UnknownCountSystem(name.to_string()))
+    }
+
+    /// 一张牌的标签
+    ///
+    /// # Errors
+    /// 牌面不存在时返回 `InvalidRank`
+    pub fn tag(&self, card: Card) -> Result<f64> {
+        tag_index(card)
+            .map(|index| self.tags[index])
+            .ok_or(CalculatorError::InvalidRank(card))
+    }
+
+    /// 一副完整牌的标签之和（平衡系统为 0）
+    pub fn imbalance(&self) -> f64 {
+        let small: f64 = self.tags[..9].iter().sum();
+        small * 4.0 + self.tags[9] * 16.0
+    }
+
+    /// 是否为平衡系统
+    pub fn is_balanced(&self) -> bool {
+        self.imbalance().abs() < 1e-9
+    }
+
+    /// 新牌靴的初始 running count（平衡系统为 0，KO 六副牌为 -20）
+    pub fn initial_running_count(&self, deck_count: u8) -> f64 {
+        -self.imbalance() * (deck_count as f64 - 1.0)
+    }
+
+    /// 下注相关系数（betting correlation）：标签与移除效应的相关系数
+    ///
+    /// 按起始牌组中每种点数的张数加权（十点牌的权重为 10/J/Q/K 之和）。
+    /// 移除一张牌使 EV 上升的点数应当有正标签，因此越接近 1 越适合决定下注；
+    /// 标签或移除效应全部相同时返回 0
+    pub fn betting_correlation(&self, effects: &EffectsOfRemoval) -> f64 {
+        let samples: Vec<(f64, f64, f64)> = effects
+            .ranks
+            .iter()
+            .filter_map(|effect| {
+                let tag = self.tag(effect.card).ok()?;
+                Some((effect.count as f64, tag, effect.eor))
+            })
+            .collect();
+        let total: f64 = samples.iter().map(|&(weight, _, _)| weight).sum();
+        if total == 0.0 {
+            return 0.0;
+        }
+        let mean_tag = samples.iter().map(|&(w, tag, _)| w * tag).sum::<f64>() / total;
+        let mean_eor = samples.iter().map(|&(w, _, eor)| w * eor).sum::<f64>() / total;
+
+        let (mut covariance, mut tag_variance, mut eor_variance) = (0.0, 0.0, 0.0);
+        for &(weight, tag, eor) in &samples {
+            let (tag, eor) = (tag - mean_tag, eor - mean_eor);
+            covariance += weight * tag * eor;
+            tag_variance += weight * tag * tag;
+            eor_variance += weight * eor * eor;
+        }
+        let denominator = (tag_variance * eor_variance).sqrt();
+        if denominator == 0.0 {
+            0.0
+        } else {
+            covariance / denominator
+        }
+    }
+
+    /// 由剩余牌组计算 running count（初始值加上已发出牌的标签之和）
+    ///
+    /// # Errors
+    /// 剩余牌组不能来自规则中的牌组数量时返回 `InvalidRank` / `ImpossibleComposition`
+    pub fn running_count(&self, remaining: &CardCounts, rules: &GameRules) -> Result<f64> {
+        rules.validate_deck(remaining)?;
+        let counts = card_counts_to_point_counts(remaining);
+        Ok(self.running_count_of_points(&counts, rules.deck_count))
+    }
+
+    /// 由剩余点数计数计算 running count（不检查牌组，供 EV 引擎使用）
+    pub(crate) fn running_count_of_points(&self, remaining: &PointCounts, deck_count: u8) -> f64 {
+        // PointCounts 索引 9（10）和 10（J/Q/K）都是十点牌
+        let remaining_tags: f64 = remaining
+            .iter()
+            .enumerate()
+            .map(|(index, &count)| self.tags[index.min(9)] * count as f64)
+            .sum();
+        // 完整牌靴的标签之和减去剩余牌的标签之和，即已发出牌的标签之和
+        let full_shoe_tags = self.imbalance() * deck_count as f64;
+        self.initial_running_count(deck_count) + full_shoe_tags - remaining_tags
+    }
+
+    /// 由剩余点数计数计算 true count（不检查牌组，供 EV 引擎使用）
+    pub(crate) fn true_count_of_points(&self, remaining: &PointCounts, deck_count: u8) -> f64 {
+        let running_count = self.running_count_of_points(remaining, deck_count);
+        true_count_for(running_count, remaining.iter().sum())
+    }
+
+    /// 由剩余牌组计算 true count（running count / 剩余副数）
+    ///
+    /// # Errors
+    /// 同 `running_count`
+    pub fn true_count(&self, remaining: &CardCounts, rules: &GameRules) -> Result<f64> {
+        let running_count = self.running_count(remaining, rules)?;
+        let cards_remaining = remaining.values().sum();
+        Ok(true_count_for(running_count, cards_remaining))
+    }
+}
+
+impl Default for CountSystem {
+    fn default() -> Self {
+        Self::hi_lo()
+    }
+}
+
+/// 逐张累加的计数器
+///
+/// 按发牌顺序记录每张牌的标签，剩余副数由规则中的牌组数量和已发出张数估算。
+#[derive(Debug, Clone)]
+pub struct RunningCounter {
+    system: CountSystem,
+    deck_count: u8,
+    running_count: f64,
+    cards_seen: u32,
+}
+
+impl RunningCounter {
+    /// 按规则中的牌组数量创建新牌靴的计数器
+    pub fn new(system: CountSystem, rules: &GameRules) -> Self {
+        Self {
+            running_count: system.initial_running_count(rules.deck_count),
+            system,
+            deck_count: rules.deck_count,
+            cards_seen: 0,
+        }
+    }
+
+    /// 使用的算牌系统
+    pub fn system(&self) -> &CountSystem {
+        &self.system
+    }
+
+    /// 记录一张发出的牌
+    ///
+    /// # Errors
+    /// 牌面不存在时返回 `InvalidRank`，计数不变
+    pub fn count(&mut self, card: Card) -> Result<()> {
+        self.running_count += self.system.tag(card)?;
+        self.cards_seen += 1;
+        Ok(())
+    }
+
+    /// 按顺序记录多张牌
+    ///
+    /// # Errors
+    /// 遇到不存在的牌面时返回 `InvalidRank`，之前的牌已记录
+    pub fn count_all(&mut self, cards: impl IntoIterator<Item = Card>) -> Result<()> {
+        cards.into_iter().try_for_each(|card| self.count(card))
+    }
+
+    /// 当前 running count
+    pub fn running_count(&self) -> f64 {
+        self.running_count
+    }
+
+    /// 已发出的张数
+    pub fn cards_seen(&self) -> u32 {
+        self.cards_seen
+    }
+
+    /// 剩余张数（按规则中的牌组数量估算）
+    pub fn cards_remaining(&self) -> u32 {
+        (self.deck_count as u32 * CARDS_PER_DECK as u32).saturating_sub(self.cards_seen)
+    }
+
+    /// 剩余副数
+    pub fn decks_remaining(&self) -> f64 {
+        decks_for_cards(self.cards_remaining())
+    }
+
+    /// 当前 true count
+    pub fn true_count(&self) -> f64 {
+        true_count_for(self.running_count, self.cards_remaining())
+    }
+
+    /// 重新洗牌：恢复初始 running count
+    pub fn reset(&mut self) {
+        self.running_count = self.system.initial_running_count(self.deck_count);
+        self.cards_seen = 0;
+    }
+}
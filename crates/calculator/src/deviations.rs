@@ -0,0 +1,275 @@
+//! 按 true count 偏离基础策略的指数打法（Illustrious 18、Fab 4）
+//!
+//! 每条偏离以（玩家点数, 是否软点数, 庄家明牌）为键，给出 true count 阈值、方向和动作：
+//! true count 落在阈值的指定一侧时改用该动作，否则沿用基础策略。
+//!
+//! 投降先于其他动作决定：一个键有投降偏离时，是否投降完全由投降指数决定
+//! （例如 Fab 4 的硬15 对 10 在 true count 低于 0 时不投降）；
+//! 决定投降后，要牌/停牌/加倍/分牌的偏离不再改变动作；
+//! 基础策略分牌的对子（如 8-8 对 10）也不按总点数的偏离改变动作。
+//! 其余偏离按加入顺序取第一条生效且合法的。
+
+use crate::counting::CountSystem;
+use crate::error::{CalculatorError, Result};
+use crate::strategy::BasicStrategy;
+use crate::types::{Action, Card, Hand};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 偏离表的键：(玩家点数, 是否软点数, 庄家明牌点数)，A 记为 11
+type DeviationKey = (u8, bool, u8);
+
+/// 偏离生效的方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeviationDirection {
+    /// true count 大于或等于阈值时生效
+    AtOrAbove,
+    /// true count 小于阈值时生效
+    Below,
+}
+
+/// 一条指数打法
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Deviation {
+    /// 玩家点数
+    pub player_total: u8,
+    /// 是否软点数
+    pub soft: bool,
+    /// 庄家明牌点数（2-10，A 记为 11）
+    pub dealer_up: u8,
+    /// true count 阈值
+    pub threshold: f64,
+    /// 生效方向
+    pub direction: DeviationDirection,
+    /// 生效时的动作
+    pub action: Action,
+}
+
+impl Deviation {
+    /// 硬点数在 true count 达到阈值时改用 `action`
+    pub fn at_or_above(player_total: u8, dealer_up: u8, threshold: f64, action: Action) -> Self {
+        Self {
+            player_total,
+            soft: false,
+            dealer_up,
+            threshold,
+            direction: DeviationDirection::AtOrAbove,
+            action,
+        }
+    }
+
+    /// 硬点数在 true count 低于阈值时改用 `action`
+    pub fn below(player_total: u8, dealer_up: u8, threshold: f64, action: Action) -> Self {
+        Self {
+            direction: DeviationDirection::Below,
+            ..Self::at_or_above(player_total, dealer_up, threshold, action)
+        }
+    }
+
+    /// 指定 true count 下是否生效
+    pub fn applies(&self, true_count: f64) -> bool {
+        match self.direction {
+            DeviationDirection::AtOrAbove => true_count >= self.threshold,
+            DeviationDirection::Below => true_count < self.threshold,
+        }
+    }
+
+    fn key(&self) -> DeviationKey {
+        (self.player_total, self.soft, self.dealer_up)
+    }
+}
+
+/// 指数打法表
+///
+/// 阈值按 `system` 的 true count 计算；内置表均为 Hi-Lo 指数（多副牌 S17）
+#[derive(Debug, Clone)]
+pub struct DeviationTable {
+    /// 计算 true count 使用的算牌系统
+    pub system: CountSystem,
+    /// 保险指数（true count 达到时购买保险）
+    pub insurance_index: Option<f64>,
+    entries: HashMap<DeviationKey, Vec<Deviation>>,
+}
+
+impl DeviationTable {
+    /// 创建空的偏离表
+    pub fn new(system: CountSystem) -> Self {
+        Self {
+            system,
+            insurance_index: None,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Illustrious 18（Hi-Lo）：最有价值的 18 条指数打法，含保险 +3
+    pub fn illustrious_18() -> Self {
+        use Action::{Double, Hit, Split, Stand};
+        let deviations = [
+            Deviation::at_or_above(16, 10, 0.0, Stand),
+            Deviation::at_or_above(15, 10, 4.0, Stand),
+            Deviation::at_or_above(20, 5, 5.0, Split),
+            Deviation::at_or_above(20, 6, 4.0, Split),
+            Deviation::at_or_above(10, 10, 4.0, Double),
+            Deviation::at_or_above(12, 3, 2.0, Stand),
+            Deviation::at_or_above(12, 2, 3.0, Stand),
+            Deviation::at_or_above(11, 11, 1.0, Double),
+            Deviation::at_or_above(9, 2, 1.0, Double),
+            Deviation::at_or_above(10, 11, 4.0, Double),
+            Deviation::at_or_above(9, 7, 3.0, Double),
+            Deviation::at_or_above(16, 9, 5.0, Stand),
+            Deviation::below(13, 2, -1.0, Hit),
+            Deviation::below(12, 4, 0.0, Hit),
+            Deviation::below(12, 5, -2.0, Hit),
+            Deviation::below(12, 6, -1.0, Hit),
+            Deviation::below(13, 3, -2.0, Hit),
+        ];
+        let mut table = Self::new(CountSystem::hi_lo()).with_deviations(deviations);
+        table.insurance_index = Some(3.0);
+        table
+    }
+
+    /// Fab 4（Hi-Lo）：后投降的 4 条指数打法
+    ///
+    /// 基础策略对硬15 对 10 投降，true count 低于 0 时不再投降
+    pub fn fab_4() -> Self {
+        use Action::Surrender;
+        Self::new(CountSystem::hi_lo()).with_deviations([
+            Deviation::at_or_above(14, 10, 3.0, Surrender),
+            Deviation::at_or_above(15, 10, 0.0, Surrender),
+            Deviation::at_or_above(15, 9, 2.0, Surrender),
+            Deviation::at_or_above(15, 11, 1.0, Surrender),
+        ])
+    }
+
+    /// Illustrious 18 加 Fab 4
+    pub fn illustrious_18_fab_4() -> Self {
+        let mut table = Self::fab_4();
+        let illustrious_18 = Self::illustrious_18();
+        table.insurance_index = illustrious_18.insurance_index;
+        table.with_deviations(illustrious_18.deviations().copied().collect::<Vec<_>>())
+    }
+
+    /// 内置表名称（`i18`、`fab4`、`i18-fab4`）
+    pub const BUILTIN_NAMES: [&'static str; 3] = ["i18", "fab4", "i18-fab4"];
+
+    /// 按名称取内置表（不区分大小写）
+    ///
+    /// # Errors
+    /// 名称不存在时返回 `UnknownDeviationTable`
+    pub fn by_name(name: &str) -> Result<DeviationTable> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "i18" => Ok(Self::illustrious_18()),
+            "fab4" => Ok(Self::fab_4()),
+            "i18-fab4" => Ok(Self::illustrious_18_fab_4()),
+            _ => Err(CalculatorError::UnknownDeviationTable(name.to_string())),
+        }
+    }
+
+    /// 追加偏离（排在同一个键已有的偏离之后）
+    pub fn with_deviations(mut self, deviations: impl IntoIterator<Item = Deviation>) -> Self {
+        for deviation in deviations {
+            self.add(deviation);
+        }
+        self
+    }
+
+    /// 追加一条偏离
+    pub fn add(&mut self, deviation: Deviation) {
+        self.entries.entry(deviation.key()).or_default().push(deviation);
+    }
+
+    /// 全部偏离（同一个键内按加入顺序）
+    pub fn deviations(&self) -> impl Iterator<Item = &Deviation> {
+        self.entries.values().flatten()
+    }
+
+    /// 偏离条数
+    pub fn len(&self) -> usize {
+        self.entries.values().map(Vec::len).sum()
+    }
+
+    /// 是否没有任何偏离
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 按当前 true count 调整基础策略的动作
+    ///
+    /// `allowed` 判断动作在当前规则和手牌阶段下是否合法，不合法的偏离会被跳过；
+    /// 投降指数否决基础策略的投降时，改用 `without_surrender` 给出的动作
+    pub fn apply(
+        &self,
+        basic_action: Action,
+        player_hand: &Hand,
+        dealer_up_card: Card,
+        true_count: f64,
+        allowed: impl Fn(Action) -> bool,
+        without_surrender: impl FnOnce() -> Action,
+    ) -> Action {
+        let key = (
+            player_hand.value(),
+            player_hand.is_soft(),
+            BasicStrategy::card_to_value(dealer_up_card),
+        );
+        let Some(deviations) = self.entries.get(&key) else {
+            return basic_action;
+        };
+
+        let mut action = basic_action;
+        let mut surrender_indices = deviations
+            .iter()
+            .filter(|deviation| deviation.action == Action::Surrender)
+            .peekable();
+        if surrender_indices.peek().is_some() && allowed(Action::Surrender) {
+            if surrender_indices.any(|deviation| deviation.applies(true_count)) {
+                return Action::Surrender;
+            }
+            if action == Action::Surrender {
+                action = without_surrender();
+            }
+        }
+        if matches!(action, Action::Surrender | Action::Split) {
+            return action;
+        }
+
+        deviations
+            .iter()
+            .find(|deviation| {
+                deviation.action != Action::Surrender
+                    && deviation.applies(true_count)
+                    && allowed(deviation.action)
+            })
+            .map_or(action, |deviation| deviation.action)
+    }
+
+    /// 在基础策略之上按 true count 查询动作（不做规则检查）
+    ///
+    /// 分牌只用于对子，加倍和投降只用于两张牌；没有生效的偏离时返回基础策略的动作
+    pub fn get_action(
+        &self,
+        strategy: &BasicStrategy,
+        player_hand: &Hand,
+        dealer_up_card: Card,
+        true_count: f64,
+    ) -> Action {
+        let two_cards = player_hand.card_count() == 2;
+        let allowed = |action| match action {
+            Action::Split => player_hand.can_split(),
+            Action::Double | Action::Surrender => two_cards,
+            Action::Hit | Action::Stand => true,
+        };
+        self.apply(
+            strategy.get_action(player_hand, dealer_up_card),
+            player_hand,
+            dealer_up_card,
+            true_count,
+            allowed,
+            || strategy.get_action_without_split_surrender(player_hand, dealer_up_card),
+        )
+    }
+
+    /// 指定 true count 下是否购买保险
+    pub fn takes_insurance(&self, true_count: f64) -> bool {
+        self.insurance_index.is_some_and(|index| true_count >= index)
+    }
+}
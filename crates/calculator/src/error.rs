@@ -0,0 +1,59 @@
+//! 计算器错误类型
+
+use crate::types::Card;
+use thiserror::Error;
+
+/// 计算器错误
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum CalculatorError {
+    /// 牌面不存在（例如 `Card::Number(1)` 或 `Card::Number(11)`）
+    #[error("无效的牌面: {0:?}（点数牌只能是 2-10）")]
+    InvalidRank(Card),
+    /// 某种牌的张数超过规则中牌组数量的上限
+    #[error("{card:?} 共 {count} 张，超过 {deck_count} 副牌的上限 {max} 张")]
+    ImpossibleComposition {
+        card: Card,
+        count: u32,
+        deck_count: u8,
+        max: u32,
+    },
+    /// 牌组数量为 0
+    #[error("无效的牌组数量: {0}")]
+    InvalidDeckCount(u8),
+    /// 允许分牌时最多手数小于 2
+    #[error("无效的分牌最多手数: {0}（允许分牌时至少为 2）")]
+    InvalidMaxSplitHands(u8),
+    /// Charlie 规则的牌数少于 3
+    #[error("无效的 Charlie 牌数: {0}（至少为 3）")]
+    InvalidCharlieCards(u8),
+    /// 黑杰克或 Charlie 赔率不是正的有限数
+    #[error("无效的赔率: {0}")]
+    InvalidPayout(f64),
+    /// 剩余牌不足以完成计算
+    #[error("剩余牌不足：至少需要 {required} 张，实际 {available} 张")]
+    InsufficientCards { required: u32, available: u32 },
+    /// 规则预设名称不存在
+    #[error("未知的规则预设: {0}")]
+    UnknownPreset(String),
+    /// 算牌系统名称不存在
+    #[error("未知的算牌系统: {0}")]
+    UnknownCountSystem(String),
+    /// 指数打法表名称不存在
+    #[error("未知的指数打法表: {0}")]
+    UnknownDeviationTable(String),
+    /// 规则文件无法读取或格式错误
+    #[error("无效的规则文件: {0}")]
+    InvalidRulesFile(String),
+    /// 策略表缺少单元格、代码无效或单元格互相矛盾
+    #[error("无效的策略表: {0}")]
+    InvalidStrategyChart(String),
+    /// Kelly 下注设置无效
+    #[error("无效的下注设置: {0}")]
+    InvalidBetting(String),
+    /// 要发出的牌已不在剩余牌组中
+    #[error("剩余牌组中已没有 {0:?}")]
+    CardNotInShoe(Card),
+}
+
+/// 计算器结果类型
+pub type Result<T> = std::result::Result<T, CalculatorError>;
@@ -4,15 +4,18 @@
 //! - 普通投注（Hit/Stand）
 //! - 加倍投注（Double Down）
 //! - 投降（Surrender）
+//! - 分牌（Split）
+//! - 保险（Insurance）
 //! 
-//! 最终返回一个综合的 EV 值，表示玩家按照基础策略上桌后的期望收益。
+//! 最终返回一个综合的 EV 值，表示玩家按照基础策略上桌后的期望收益，
+//! 以及每局净收益的方差（由同一组概率计算净收益平方的期望）。
 
 use crate::types::{GameOutcome, TableEVResult};
 use crate::rules::GameRules;
 
 /// 根据游戏结果概率计算期望值
 /// 
-/// 计算"上桌 EV"，包含所有可能的游戏情况（普通投注、加倍、投降）
+/// 计算"上桌 EV"，包含所有可能的游戏情况（普通投注、加倍、投降、分牌、保险）
 pub fn calculate_ev(
     outcome: &GameOutcome,
     rules: &GameRules,
@@ -21,8 +24,12 @@ pub fn calculate_ev(
     // EV_normal = P(玩家胜) × 1.0 + P(玩家黑杰克) × 1.5 - P(庄家胜) × 1.0 - P(庄家黑杰克) × 1.0
     // 平局退还本金，所以 EV = 0
     
+    // Charlie 自动获胜按 Charlie 赔率结算
+    let charlie_payout = rules.charlie.map_or(1.0, |charlie| charlie.payout);
+
     let ev_normal: f64 = outcome.player_win_prob * 1.0
         + outcome.player_blackjack_prob * rules.blackjack_payout
+        + outcome.player_charlie_prob * charlie_payout
         - outcome.dealer_win_prob * 1.0
         - outcome.dealer_blackjack_prob * 1.0;
     
@@ -31,16 +38,26 @@ pub fn calculate_ev(
     // 注意：加倍时通常不会有黑杰克（因为只能在前两张牌时加倍）
     let ev_double = outcome.player_win_prob_double * 2.0
         + outcome.player_blackjack_prob_double * (rules.blackjack_payout * 2.0)
+        + outcome.player_charlie_prob_double * (charlie_payout * 2.0)
         - outcome.dealer_win_prob_double * 2.0
         - outcome.dealer_blackjack_prob_double * 2.0;
     
     // 投降的 EV（损失0.5倍投注）
     let ev_surrender = outcome.surrender_prob * (-0.5);
     
-    // 总 EV = 普通投注 EV + 加倍投注 EV + 投降 EV
+    // 分牌的 EV（各分牌手已在概率计算中结算，这里直接使用加权收益）
+    let ev_split = outcome.split_ev;
+
+    // 保险的 EV（保险注独立结算，同样直接使用加权收益）
+    let ev_insurance = outcome.insurance_ev;
+    
+    // 总 EV = 普通投注 EV + 加倍投注 EV + 投降 EV + 分牌 EV + 保险 EV
     // 这是"上桌 EV"，综合考虑了所有可能的游戏情况
-    let ev = ev_normal + ev_double + ev_surrender;
+    let ev = ev_normal + ev_double + ev_surrender + ev_split + ev_insurance;
     
+    // 方差 = E[净收益²] - EV²（舍入误差可能使结果略小于 0）
+    let variance = (second_moment(outcome, rules) - ev * ev).max(0.0);
+
     // 合并概率（用于显示）
     let total_player_win_prob = outcome.player_win_prob + outcome.player_win_prob_double;
     let total_dealer_win_prob = outcome.dealer_win_prob + outcome.dealer_win_prob_double;
@@ -54,12 +71,45 @@ pub fn calculate_ev(
         ev_normal,
         ev_double,
         ev_surrender,
+        ev_split,
+        ev_insurance,
         player_win_prob: total_player_win_prob,
         dealer_win_prob: total_dealer_win_prob,
         push_prob: total_push_prob,
         player_blackjack_prob: total_player_blackjack_prob,
         dealer_blackjack_prob: total_dealer_blackjack_prob,
+        charlie_prob: outcome.player_charlie_prob + outcome.player_charlie_prob_double,
         surrender_prob: outcome.surrender_prob,
+        split_prob: outcome.split_prob,
+        insurance_prob: outcome.insurance_prob,
+        variance,
+        std_dev: variance.sqrt(),
     }
 }
 
+/// 每局净收益平方的期望（以原始投注为单位的平方）
+///
+/// 普通投注的收益为 ±1、黑杰克赔率或 Charlie 赔率，加倍投注翻倍（平方为 4 倍），
+/// 投降为 -0.5；分牌局和保险注的平方项已在概率计算中累加
+fn second_moment(outcome: &GameOutcome, rules: &GameRules) -> f64 {
+    let blackjack_payout = rules.blackjack_payout;
+    let charlie_payout = rules.charlie.map_or(1.0, |charlie| charlie.payout);
+
+    let normal = outcome.player_win_prob
+        + outcome.player_blackjack_prob * blackjack_payout * blackjack_payout
+        + outcome.player_charlie_prob * charlie_payout * charlie_payout
+        + outcome.dealer_win_prob
+        + outcome.dealer_blackjack_prob;
+    let double = outcome.player_win_prob_double
+        + outcome.player_blackjack_prob_double * blackjack_payout * blackjack_payout
+        + outcome.player_charlie_prob_double * charlie_payout * charlie_payout
+        + outcome.dealer_win_prob_double
+        + outcome.dealer_blackjack_prob_double;
+
+    normal
+        + double * 4.0
+        + outcome.surrender_prob * 0.25
+        + outcome.split_second_moment
+        + outcome.insurance_second_moment
+}
+
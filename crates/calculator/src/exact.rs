@@ -0,0 +1,526 @@
+//! 精确有理数验证模式（`exact` 特性）
+//!
+//! `ProbabilityCalculator` 在深层递归中累加 `f64` 乘积，舍入误差无法直接观察。
+//! `ExactCalculator` 使用与它完全相同的枚举：相同的初始发牌、相同的决策
+//! （基础策略表查询和规则回退直接调用概率计算器）、相同的庄家黑杰克结算方式，
+//! 只是把概率和收益换成 `BigRational`，得到上桌 EV 的精确分数。
+//!
+//! 有理数的分子分母随牌组增大迅速变长，只适用于一两副牌的小牌组。
+//! 最优策略模式下按精确 EV 选择动作，与浮点计算只在 EV 几乎相等的动作之间可能选择不同。
+
+use crate::probability_calculator::{
+    DealerMemoKey, HandStage, PlayerMemoKey, ProbabilityCalculator, DEALER_BLACKJACK, DEALER_BUST,
+};
+use crate::deviations::DeviationTable;
+use crate::rules::{DealerRules, GameRules, HoleCardRule};
+use crate::strategy::Strategy;
+use crate::types::{
+    Action, Card, CardCounts, Hand, InsurancePolicy, PlayingCard, PointCounts, StrategyMode, Suit,
+    point_index_to_card,
+};
+use num_bigint::BigInt;
+use num_rational::{BigRational, Ratio};
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 庄家结果的精确概率（索引同 `DealerProbs`：0-4 = 17-21 点，5 = 黑杰克，6 = 爆牌）
+type ExactDealerProbs = [BigRational; 7];
+
+/// 精确 EV 与浮点 EV 的对比
+#[derive(Debug, Clone)]
+pub struct ExactEVResult {
+    /// 精确的上桌 EV（以原始投注为单位）
+    pub ev: BigRational,
+    /// 浮点引擎计算的上桌 EV
+    pub float_ev: f64,
+    /// 浮点结果与精确结果之差（`float_ev - ev`）
+    pub deviation: f64,
+}
+
+impl ExactEVResult {
+    /// 精确 EV 转换为最接近的 `f64`
+    pub fn ev_f64(&self) -> f64 {
+        self.ev.to_f64().unwrap_or(f64::NAN)
+    }
+}
+
+/// 精确有理数计算器
+pub struct ExactCalculator {
+    /// 提供规则、决策和初始发牌枚举的浮点引擎（不使用它的缓存）
+    engine: ProbabilityCalculator,
+    /// 黑杰克赔率的精确分数
+    blackjack_payout: BigRational,
+    /// Charlie 赔率的精确分数
+    charlie_payout: BigRational,
+    dealer_memo: HashMap<DealerMemoKey, ExactDealerProbs>,
+    player_memo: HashMap<PlayerMemoKey, BigRational>,
+}
+
+impl ExactCalculator {
+    /// 创建精确计算器（按基础策略决策）
+    pub fn new(rules: GameRules) -> Self {
+        Self::with_mode(rules, StrategyMode::Basic)
+    }
+
+    /// 创建指定策略模式的精确计算器
+    pub fn with_mode(rules: GameRules, mode: StrategyMode) -> Self {
+        let charlie_payout = rules.charlie.map_or(1.0, |charlie| charlie.payout);
+        Self {
+            blackjack_payout: Self::payout_ratio(rules.blackjack_payout),
+            charlie_payout: Self::payout_ratio(charlie_payout),
+            engine: ProbabilityCalculator::with_mode(rules, mode),
+            dealer_memo: HashMap::new(),
+            player_memo: HashMap::new(),
+        }
+    }
+
+    /// 赔率按最接近的简单分数处理（1.5 → 3/2，1.2 → 6/5）
+    fn payout_ratio(payout: f64) -> BigRational {
+        let payout = Ratio::<i64>::approximate_float(payout).unwrap_or_else(|| Ratio::from_integer(1));
+        BigRational::new(BigInt::from(*payout.numer()), BigInt::from(*payout.denom()))
+    }
+
+    /// 设置保险策略
+    pub fn with_insurance_policy(mut self, policy: InsurancePolicy) -> Self {
+        self.engine.set_insurance_policy(policy);
+        self
+    }
+
+    /// 设置基础策略模式下使用的策略（与浮点引擎使用同一套决策）
+    pub fn with_strategy(mut self, strategy: impl Strategy + 'static) -> Self {
+        self.engine.set_strategy(Arc::new(strategy));
+        self
+    }
+
+    /// 设置指数打法（与浮点引擎使用同一套决策）
+    pub fn with_deviations(mut self, deviations: DeviationTable) -> Self {
+        self.engine.set_deviations(Some(deviations));
+        self
+    }
+
+    /// 计算精确的上桌 EV
+    pub fn calculate_table_ev(&mut self, deck: &CardCounts) -> BigRational {
+        let counts = ProbabilityCalculator::merged_point_counts(deck);
+        let total_cards: u64 = counts.iter().map(|&count| count as u64).sum();
+
+        if total_cards < 4 {
+            return BigRational::zero();
+        }
+
+        let deal_count = total_cards * (total_cards - 1) * (total_cards - 2);
+        let mut ev = BigRational::zero();
+        for deal in self.engine.initial_deals(&counts, total_cards as u32) {
+            let deal_ev = self.game_ev(&deal.player_hand, deal.dealer_up_idx, &deal.counts);
+            ev += Self::ratio(deal.ways, deal_count) * deal_ev;
+        }
+        ev
+    }
+
+    /// 整数比值
+    fn ratio(numer: u64, denom: u64) -> BigRational {
+        BigRational::new(BigInt::from(numer), BigInt::from(denom))
+    }
+
+    /// 抽到指定点数的精确概率
+    fn draw_prob(counts: &PointCounts, point_idx: usize) -> BigRational {
+        let total_cards: u64 = counts.iter().map(|&count| count as u64).sum();
+        Self::ratio(counts[point_idx] as u64, total_cards)
+    }
+
+    /// 一局的 EV（庄家暗牌尚未抽取）
+    fn game_ev(&mut self, player_hand: &Hand, dealer_up_idx: usize, counts: &PointCounts) -> BigRational {
+        let dealer_blackjack_prob = self.dealer_blackjack_prob(dealer_up_idx, counts);
+        let mut ev = self.insurance_ev(dealer_up_idx, counts, &dealer_blackjack_prob);
+
+        // 玩家黑杰克：庄家也是黑杰克则平局
+        if player_hand.is_blackjack() {
+            let no_blackjack = BigRational::one() - &dealer_blackjack_prob;
+            return ev + &self.blackjack_payout * no_blackjack;
+        }
+
+        // 庄家偷看到黑杰克：玩家只输原始投注（早投降时改在决策点结算）
+        if self.engine.rules().hole_card == HoleCardRule::Peek
+            && !self.engine.early_surrender(dealer_up_idx)
+        {
+            ev -= &dealer_blackjack_prob;
+        }
+
+        ev + self.play_ev(player_hand, dealer_up_idx, counts, HandStage::Initial)
+    }
+
+    /// 按保险策略（或指数打法的保险指数）购买保险的 EV
+    fn insurance_ev(
+        &self,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+        dealer_blackjack_prob: &BigRational,
+    ) -> BigRational {
+        // 0.5 × (3 × P(黑杰克) - 1)
+        let insurance_ev = (dealer_blackjack_prob * BigInt::from(3) - BigRational::one())
+            / BigInt::from(2);
+        if self.engine.takes_insurance(dealer_up_idx, counts, insurance_ev.is_positive()) {
+            insurance_ev
+        } else {
+            BigRational::zero()
+        }
+    }
+
+    /// 玩家在当前阶段行动的 EV
+    fn play_ev(
+        &mut self,
+        player_hand: &Hand,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+        stage: HandStage,
+    ) -> BigRational {
+        match self.engine.mode() {
+            StrategyMode::Basic => {
+                let action = self.engine.basic_action(player_hand, dealer_up_idx, stage, counts);
+                self.decision_ev(action, player_hand, dealer_up_idx, counts, stage)
+            }
+            StrategyMode::Optimal => {
+                let mut best: Option<BigRational> = None;
+                for action in self.engine.legal_actions(player_hand, stage) {
+                    let ev = self.decision_ev(action, player_hand, dealer_up_idx, counts, stage);
+                    if best.as_ref().is_none_or(|best_ev| ev > *best_ev) {
+                        best = Some(ev);
+                    }
+                }
+                best.unwrap_or_else(BigRational::zero)
+            }
+        }
+    }
+
+    /// 决策点执行动作的 EV（初始决策点计入之后才结算的庄家黑杰克）
+    fn decision_ev(
+        &mut self,
+        action: Action,
+        player_hand: &Hand,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+        stage: HandStage,
+    ) -> BigRational {
+        let mut ev = self.action_ev(action, player_hand, dealer_up_idx, counts, stage);
+        if stage == HandStage::Initial && self.engine.loses_original_bet(action, stage, dealer_up_idx) {
+            ev -= self.dealer_blackjack_prob(dealer_up_idx, counts);
+        }
+        ev
+    }
+
+    /// 执行指定动作的 EV
+    fn action_ev(
+        &mut self,
+        action: Action,
+        player_hand: &Hand,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+        stage: HandStage,
+    ) -> BigRational {
+        match action {
+            Action::Stand => self.stand_ev(player_hand.value(), dealer_up_idx, counts),
+            Action::Hit => self.hit_ev(player_hand, dealer_up_idx, counts, stage),
+            Action::Double => self.double_ev(player_hand, dealer_up_idx, counts),
+            Action::Split => {
+                let pair_card = player_hand.cards[0].card;
+                let max_hands = self.engine.rules().max_split_hands;
+                self.split_positions_ev(pair_card, dealer_up_idx, counts, max_hands)
+            }
+            Action::Surrender => {
+                let surrender_prob = if self.engine.early_surrender(dealer_up_idx) {
+                    BigRational::one()
+                } else {
+                    BigRational::one() - self.dealer_blackjack_prob(dealer_up_idx, counts)
+                };
+                -surrender_prob / BigInt::from(2)
+            }
+        }
+    }
+
+    /// 要牌的 EV
+    fn hit_ev(
+        &mut self,
+        player_hand: &Hand,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+        stage: HandStage,
+    ) -> BigRational {
+        let drawn_stage = self.engine.drawn_stage(stage);
+        let mut ev = BigRational::zero();
+        for point_idx in 0..=10 {
+            if counts[point_idx] == 0 {
+                continue;
+            }
+            let prob = Self::draw_prob(counts, point_idx);
+            let new_hand = Self::with_card(player_hand, point_idx);
+            let mut new_counts = *counts;
+            new_counts[point_idx] -= 1;
+
+            let sub_ev = if new_hand.is_busted() {
+                self.bust_ev(dealer_up_idx, &new_counts)
+            } else if self.engine.rules().is_charlie(&new_hand) {
+                self.charlie_ev(dealer_up_idx, &new_counts)
+            } else {
+                self.drawn_ev(&new_hand, dealer_up_idx, &new_counts, drawn_stage)
+            };
+            ev += prob * sub_ev;
+        }
+        ev
+    }
+
+    /// 要牌后继续行动的 EV（带缓存，键与浮点引擎相同）
+    fn drawn_ev(
+        &mut self,
+        player_hand: &Hand,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+        stage: HandStage,
+    ) -> BigRational {
+        let card_count = self.engine.memo_card_count(player_hand);
+        let memo_key = (
+            player_hand.value(),
+            player_hand.is_soft(),
+            card_count,
+            stage,
+            dealer_up_idx,
+            ProbabilityCalculator::deck_key(counts),
+        );
+        if let Some(cached) = self.player_memo.get(&memo_key) {
+            return cached.clone();
+        }
+
+        let ev = self.play_ev(player_hand, dealer_up_idx, counts, stage);
+        self.player_memo.insert(memo_key, ev.clone());
+        ev
+    }
+
+    /// 加倍的 EV（投注翻倍，只再要一张牌）
+    fn double_ev(&mut self, player_hand: &Hand, dealer_up_idx: usize, counts: &PointCounts) -> BigRational {
+        let mut ev = BigRational::zero();
+        for point_idx in 0..=10 {
+            if counts[point_idx] == 0 {
+                continue;
+            }
+            let prob = Self::draw_prob(counts, point_idx);
+            let new_hand = Self::with_card(player_hand, point_idx);
+            let mut new_counts = *counts;
+            new_counts[point_idx] -= 1;
+
+            let sub_ev = if new_hand.is_busted() {
+                self.bust_ev(dealer_up_idx, &new_counts)
+            } else if self.engine.rules().is_charlie(&new_hand) {
+                self.charlie_ev(dealer_up_idx, &new_counts)
+            } else {
+                self.stand_ev(new_hand.value(), dealer_up_idx, &new_counts)
+            };
+            ev += prob * sub_ev;
+        }
+        ev * BigInt::from(2)
+    }
+
+    /// 一个分牌位置分成两手的 EV（与浮点引擎相同：两手平分手数上限 `max_hands`）
+    fn split_positions_ev(
+        &mut self,
+        pair_card: Card,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+        max_hands: u8,
+    ) -> BigRational {
+        let left = self.split_hand_ev(pair_card, dealer_up_idx, counts, max_hands.div_ceil(2));
+        if max_hands.is_multiple_of(2) || !self.engine.rules().allow_resplit {
+            return left * BigInt::from(2);
+        }
+        left + self.split_hand_ev(pair_card, dealer_up_idx, counts, max_hands / 2)
+    }
+
+    /// 单手分牌的 EV（与浮点引擎相同：每手以共享的剩余牌组独立计算）
+    fn split_hand_ev(
+        &mut self,
+        pair_card: Card,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+        max_hands: u8,
+    ) -> BigRational {
+        let total_cards: u32 = counts.iter().sum();
+        if total_cards == 0 {
+            return BigRational::zero();
+        }
+
+        let rules = self.engine.rules();
+        let can_resplit =
+            rules.allow_resplit && pair_card != Card::Ace && max_hands > 1;
+
+        let mut ev = BigRational::zero();
+        for point_idx in 0..=10 {
+            if counts[point_idx] == 0 {
+                continue;
+            }
+            let prob = Self::draw_prob(counts, point_idx);
+
+            let mut split_hand = Hand::new();
+            split_hand.add_card(PlayingCard {
+                card: pair_card,
+                suit: Suit::Spades,
+            });
+            let split_hand = Self::with_card(&split_hand, point_idx);
+            let mut new_counts = *counts;
+            new_counts[point_idx] -= 1;
+
+            let hand_ev = if pair_card == Card::Ace {
+                // 分A后每手只发一张牌，直接停牌
+                self.stand_ev(split_hand.value(), dealer_up_idx, &new_counts)
+            } else if can_resplit && split_hand.can_split() {
+                let resplit_ev = self.split_positions_ev(pair_card, dealer_up_idx, &new_counts, max_hands);
+                match self.engine.mode() {
+                    StrategyMode::Basic => {
+                        if self.engine.should_resplit(&split_hand, dealer_up_idx, &new_counts) {
+                            resplit_ev
+                        } else {
+                            self.play_ev(&split_hand, dealer_up_idx, &new_counts, HandStage::Split)
+                        }
+                    }
+                    StrategyMode::Optimal => {
+                        let play_ev =
+                            self.play_ev(&split_hand, dealer_up_idx, &new_counts, HandStage::Split);
+                        resplit_ev.max(play_ev)
+                    }
+                }
+            } else {
+                self.play_ev(&split_hand, dealer_up_idx, &new_counts, HandStage::Split)
+            };
+
+            ev += prob * hand_ev;
+        }
+        ev
+    }
+
+    /// 玩家爆牌的 EV
+    fn bust_ev(&self, dealer_up_idx: usize, counts: &PointCounts) -> BigRational {
+        -self.play_path_prob(dealer_up_idx, counts)
+    }
+
+    /// 玩家达到 Charlie 的 EV（庄家黑杰克仍然优先）
+    fn charlie_ev(&self, dealer_up_idx: usize, counts: &PointCounts) -> BigRational {
+        let blackjack_prob = self.dealer_blackjack_prob(dealer_up_idx, counts);
+        let charlie_prob = BigRational::one() - &blackjack_prob;
+        let blackjack_loss = self.play_path_prob(dealer_up_idx, counts) - &charlie_prob;
+        charlie_prob * &self.charlie_payout - blackjack_loss
+    }
+
+    /// 玩家停牌后与庄家比较的 EV
+    fn stand_ev(&mut self, player_value: u8, dealer_up_idx: usize, counts: &PointCounts) -> BigRational {
+        let (up_value, up_soft) = ProbabilityCalculator::add_card_to_total(0, false, dealer_up_idx);
+        let dealer = self.dealer_probs(up_value, up_soft, true, counts);
+
+        let mut ev = &dealer[DEALER_BUST] - &dealer[DEALER_BLACKJACK];
+        for (i, prob) in dealer[..DEALER_BLACKJACK].iter().enumerate() {
+            let dealer_value = 17 + i as u8;
+            if player_value > dealer_value {
+                ev += prob;
+            } else if player_value < dealer_value {
+                ev -= prob;
+            }
+        }
+        ev
+    }
+
+    /// 庄家最终点数的精确分布（黑杰克路径的处理与浮点引擎相同）
+    fn dealer_probs(
+        &mut self,
+        dealer_value: u8,
+        dealer_is_soft: bool,
+        up_card_only: bool,
+        counts: &PointCounts,
+    ) -> ExactDealerProbs {
+        let memo_key = (
+            dealer_value,
+            dealer_is_soft,
+            up_card_only,
+            ProbabilityCalculator::deck_key(counts),
+        );
+        if let Some(cached) = self.dealer_memo.get(&memo_key) {
+            return cached.clone();
+        }
+
+        let mut outcome: ExactDealerProbs = std::array::from_fn(|_| BigRational::zero());
+        let rules = self.engine.rules();
+
+        if !up_card_only
+            && !DealerRules::should_hit_value(dealer_value, dealer_is_soft, rules.dealer_stands_on_soft_17)
+        {
+            outcome[(dealer_value - 17) as usize] = BigRational::one();
+            self.dealer_memo.insert(memo_key, outcome.clone());
+            return outcome;
+        }
+
+        let total_cards: u32 = counts.iter().sum();
+        if total_cards == 0 {
+            return outcome;
+        }
+
+        let no_hole_card = rules.hole_card == HoleCardRule::NoHoleCard;
+        for point_idx in 0..=10 {
+            if counts[point_idx] == 0 {
+                continue;
+            }
+            let prob = Self::draw_prob(counts, point_idx);
+            let (new_value, new_is_soft) =
+                ProbabilityCalculator::add_card_to_total(dealer_value, dealer_is_soft, point_idx);
+
+            if up_card_only && new_value == 21 {
+                if no_hole_card {
+                    outcome[DEALER_BLACKJACK] += prob;
+                }
+                continue;
+            }
+
+            if new_value > 21 {
+                outcome[DEALER_BUST] += prob;
+                continue;
+            }
+
+            let mut new_counts = *counts;
+            new_counts[point_idx] -= 1;
+            let sub_outcome = self.dealer_probs(new_value, new_is_soft, false, &new_counts);
+            for (total, sub) in outcome.iter_mut().zip(sub_outcome) {
+                *total += sub * &prob;
+            }
+        }
+
+        self.dealer_memo.insert(memo_key, outcome.clone());
+        outcome
+    }
+
+    /// 庄家暗牌组成黑杰克的精确概率
+    fn dealer_blackjack_prob(&self, dealer_up_idx: usize, counts: &PointCounts) -> BigRational {
+        let total_cards: u64 = counts.iter().map(|&count| count as u64).sum();
+        if total_cards == 0 {
+            return BigRational::zero();
+        }
+        let blackjack_cards = match dealer_up_idx {
+            0 => counts[9] + counts[10],
+            9 | 10 => counts[0],
+            _ => 0,
+        };
+        Self::ratio(blackjack_cards as u64, total_cards)
+    }
+
+    /// 玩家行动路径的精确概率质量
+    fn play_path_prob(&self, dealer_up_idx: usize, counts: &PointCounts) -> BigRational {
+        if self.engine.rules().hole_card == HoleCardRule::NoHoleCard {
+            BigRational::one()
+        } else {
+            BigRational::one() - self.dealer_blackjack_prob(dealer_up_idx, counts)
+        }
+    }
+
+    /// 手牌加一张指定点数的牌
+    fn with_card(hand: &Hand, point_idx: usize) -> Hand {
+        let mut new_hand = hand.clone();
+        new_hand.add_card(PlayingCard {
+            card: point_index_to_card(point_idx),
+            suit: Suit::Spades,
+        });
+        new_hand
+    }
+}
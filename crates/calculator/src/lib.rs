@@ -2,14 +2,38 @@
 //!
 //! 提供 Blackjack 游戏的概率计算、期望值计算等功能
 
+pub mod error;
 pub mod types;
 pub mod rules;
+pub mod presets;
 pub mod strategy;
+pub mod strategy_generator;
+pub mod strategy_chart;
 pub mod probability_calculator;
 pub mod ev_calculator;
 pub mod calculator;
+pub mod shoe;
+pub mod tracker;
+pub mod counting;
+pub mod deviations;
+pub mod betting;
+#[cfg(feature = "exact")]
+pub mod exact;
 
-pub use calculator::{Calculator, create_full_8_deck};
+pub use calculator::{Calculator, create_full_8_deck, create_full_deck};
+pub use error::CalculatorError;
 pub use types::*;
-pub use rules::GameRules;
+pub use rules::{CharlieRule, DoubleRule, GameRules, HoleCardRule, SurrenderRule};
+pub use presets::RulePreset;
+pub use strategy::{BasicStrategy, Strategy};
+pub use strategy_chart::{ChartRow, StrategyChart};
+pub use shoe::Shoe;
+pub use tracker::{BetRamp, ShoeTracker, TrackerUpdate};
+pub use counting::{CountSystem, RunningCounter};
+pub use deviations::{Deviation, DeviationDirection, DeviationTable};
+pub use betting::{CountAdvantage, KellyBetting};
+#[cfg(feature = "exact")]
+pub use exact::{ExactCalculator, ExactEVResult};
+#[cfg(feature = "exact")]
+pub use num_rational::BigRational;
 
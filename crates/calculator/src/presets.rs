@@ -0,0 +1,281 @@
+//! 命名规则预设与规则文件加载
+//!
+//! 常见赌场规则以名称登记（如 `strip-s17`），规则文件可以直接引用预设名称，
+//! 也可以在预设或默认规则的基础上覆盖部分字段：
+//!
+//! ```toml
+//! rules = "strip-s17"
+//! ```
+//!
+//! ```toml
+//! [rules]
+//! preset = "atlantic-city"
+//! blackjack_payout = 1.2
+//! ```
+//!
+//! 没有 `rules` 键时整个文件视为规则表。JSON 文件结构相同。
+
+use crate::error::{CalculatorError, Result};
+use crate::rules::{DoubleRule, GameRules, HoleCardRule, SurrenderRule};
+use serde_json::{Map, Value};
+use std::path::Path;
+
+/// 一个命名规则预设
+#[derive(Debug, Clone, Copy)]
+pub struct RulePreset {
+    /// 预设名称（规则文件和命令行中使用）
+    pub name: &'static str,
+    /// 简短说明
+    pub description: &'static str,
+    build: fn() -> GameRules,
+}
+
+impl RulePreset {
+    /// 预设对应的规则
+    pub fn rules(&self) -> GameRules {
+        (self.build)()
+    }
+}
+
+/// 全部预设（按名称查找时使用）
+const PRESETS: &[RulePreset] = &[
+    RulePreset {
+        name: "default",
+        description: "默认规则：8副牌，H17，后投降，可分牌（不再分、不能分牌后加倍）",
+        build: GameRules::default,
+    },
+    RulePreset {
+        name: "strip-s17",
+        description: "拉斯维加斯大道：4副牌，S17，DAS，再分至4手，后投降",
+        build: strip_s17,
+    },
+    RulePreset {
+        name: "downtown-h17",
+        description: "拉斯维加斯市中心：2副牌，H17，DAS，不投降",
+        build: downtown_h17,
+    },
+    RulePreset {
+        name: "atlantic-city",
+        description: "大西洋城：8副牌，S17，DAS，再分至4手，后投降",
+        build: atlantic_city,
+    },
+    RulePreset {
+        name: "reno-h17",
+        description: "里诺：6副牌，H17，只限硬10-11加倍，DAS，不投降",
+        build: reno_h17,
+    },
+    RulePreset {
+        name: "macau-enhc",
+        description: "澳门：8副牌，S17，无暗牌（庄家黑杰克收走全部投注），不投降",
+        build: macau_enhc,
+    },
+    RulePreset {
+        name: "european-enhc",
+        description: "欧式无暗牌：6副牌，S17，DAS，不投降",
+        build: european_enhc,
+    },
+    RulePreset {
+        name: "european-obo",
+        description: "欧式无暗牌，庄家黑杰克只收原始投注：6副牌，S17，DAS，不投降",
+        build: european_obo,
+    },
+    RulePreset {
+        name: "single-deck-6-5",
+        description: "单副牌黑杰克 6:5 赔付：H17，不能分牌后加倍，不投降",
+        build: single_deck_6_5,
+    },
+];
+
+fn strip_s17() -> GameRules {
+    GameRules {
+        deck_count: 4,
+        surrender: SurrenderRule::Late,
+        allow_split: true,
+        dealer_stands_on_soft_17: true,
+        allow_resplit: true,
+        max_split_hands: 4,
+        allow_double_after_split: true,
+        blackjack_payout: 1.5,
+        hole_card: HoleCardRule::Peek,
+        double: DoubleRule::AnyTwoCards,
+        charlie: None,
+    }
+}
+
+fn downtown_h17() -> GameRules {
+    GameRules {
+        deck_count: 2,
+        surrender: SurrenderRule::None,
+        dealer_stands_on_soft_17: false,
+        ..strip_s17()
+    }
+}
+
+fn atlantic_city() -> GameRules {
+    GameRules {
+        deck_count: 8,
+        ..strip_s17()
+    }
+}
+
+fn reno_h17() -> GameRules {
+    GameRules {
+        deck_count: 6,
+        surrender: SurrenderRule::None,
+        dealer_stands_on_soft_17: false,
+        double: DoubleRule::TenToEleven,
+        ..strip_s17()
+    }
+}
+
+fn macau_enhc() -> GameRules {
+    GameRules {
+        deck_count: 8,
+        surrender: SurrenderRule::None,
+        hole_card: HoleCardRule::NoHoleCard,
+        ..strip_s17()
+    }
+}
+
+fn european_enhc() -> GameRules {
+    GameRules {
+        deck_count: 6,
+        ..macau_enhc()
+    }
+}
+
+fn european_obo() -> GameRules {
+    GameRules {
+        hole_card: HoleCardRule::NoHoleCardOriginalBetsOnly,
+        ..european_enhc()
+    }
+}
+
+fn single_deck_6_5() -> GameRules {
+    GameRules {
+        deck_count: 1,
+        surrender: SurrenderRule::None,
+        dealer_stands_on_soft_17: false,
+        allow_double_after_split: false,
+        blackjack_payout: 1.2,
+        ..strip_s17()
+    }
+}
+
+impl GameRules {
+    /// 全部命名预设
+    pub fn presets() -> &'static [RulePreset] {
+        PRESETS
+    }
+
+    /// 按名称取预设规则（不区分大小写）
+    ///
+    /// # Errors
+    /// 名称不存在时返回 `UnknownPreset`
+    pub fn preset(name: &str) -> Result<GameRules> {
+        PRESETS
+            .iter()
+            .find(|preset| preset.name.eq_ignore_ascii_case(name.trim()))
+            .map(RulePreset::rules)
+            .ok_or_else(|| CalculatorError::UnknownPreset(name.to_string()))
+    }
+
+    /// 从 TOML 文本加载规则
+    pub fn from_toml_str(text: &str) -> Result<GameRules> {
+        let value: Value = toml::from_str(text)
+            .map_err(|err| CalculatorError::InvalidRulesFile(err.to_string()))?;
+        rules_from_value(value)
+    }
+
+    /// 从 JSON 文本加载规则
+    pub fn from_json_str(text: &str) -> Result<GameRules> {
+        let value: Value = serde_json::from_str(text)
+            .map_err(|err| CalculatorError::InvalidRulesFile(err.to_string()))?;
+        rules_from_value(value)
+    }
+
+    /// 从规则文件加载，按扩展名（`.toml` / `.json`）选择格式
+    pub fn from_file(path: impl AsRef<Path>) -> Result<GameRules> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|err| {
+            CalculatorError::InvalidRulesFile(format!("{}: {err}", path.display()))
+        })?;
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+        let result = match extension.as_deref() {
+            Some("toml") => GameRules::from_toml_str(&text),
+            Some("json") => GameRules::from_json_str(&text),
+            _ => Err(CalculatorError::InvalidRulesFile(
+                "只支持 .toml 和 .json 文件".to_string(),
+            )),
+        };
+        result.map_err(|err| match err {
+            CalculatorError::InvalidRulesFile(message) => {
+                CalculatorError::InvalidRulesFile(format!("{}: {message}", path.display()))
+            }
+            other => other,
+        })
+    }
+
+    /// 预设名称或规则文件路径（命令行参数使用）
+    ///
+    /// 能匹配预设名称时使用预设，否则按文件路径加载
+    pub fn from_spec(spec: &str) -> Result<GameRules> {
+        match GameRules::preset(spec) {
+            Err(CalculatorError::UnknownPreset(_)) if Path::new(spec).is_file() => {
+                GameRules::from_file(spec)
+            }
+            result => result,
+        }
+    }
+}
+
+/// 解析规则文档：预设名称、带 `preset` 基础的覆盖表，或完整规则表
+fn rules_from_value(value: Value) -> Result<GameRules> {
+    let spec = match value {
+        Value::Object(mut document) if document.contains_key("rules") => {
+            document.remove("rules").unwrap_or(Value::Null)
+        }
+        other => other,
+    };
+    let rules = match spec {
+        Value::String(name) => GameRules::preset(&name)?,
+        Value::Object(overrides) => apply_overrides(overrides)?,
+        other => {
+            return Err(CalculatorError::InvalidRulesFile(format!(
+                "rules 必须是预设名称或规则表，实际为 {other}"
+            )))
+        }
+    };
+    rules.validate()?;
+    Ok(rules)
+}
+
+/// 在基础规则（`preset` 或默认规则）上覆盖字段，未知字段视为错误
+fn apply_overrides(mut overrides: Map<String, Value>) -> Result<GameRules> {
+    let base = match overrides.remove("preset") {
+        Some(Value::String(name)) => GameRules::preset(&name)?,
+        Some(other) => {
+            return Err(CalculatorError::InvalidRulesFile(format!(
+                "preset 必须是字符串，实际为 {other}"
+            )))
+        }
+        None => GameRules::default(),
+    };
+    let mut merged = match serde_json::to_value(base) {
+        Ok(Value::Object(fields)) => fields,
+        _ => unreachable!("GameRules 总是序列化为对象"),
+    };
+    for (key, value) in overrides {
+        // 旧版字段名覆盖到新字段上，避免与基础规则中的 surrender 重复
+        let key = if key == "allow_surrender" { "surrender".to_string() } else { key };
+        if !merged.contains_key(&key) {
+            return Err(CalculatorError::InvalidRulesFile(format!("未知的规则字段: {key}")));
+        }
+        merged.insert(key, value);
+    }
+    serde_json::from_value(Value::Object(merged))
+        .map_err(|err| CalculatorError::InvalidRulesFile(err.to_string()))
+}
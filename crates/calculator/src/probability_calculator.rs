@@ -1,58 +1,682 @@
 //! 概率计算核心（优化版：分层计算 + 组合数学）
+//!
+//! 玩家只看到自己的手牌和庄家明牌进行决策，庄家暗牌在玩家行动结束后
+//! 才从剩余牌组中抽取。美式规则下庄家先偷看暗牌，因此所有玩家行动的结果
+//! 都只包含"庄家没有黑杰克"的路径，其概率质量在递归中精确保留，不做局部归一化。
+//! 欧式无暗牌规则下庄家黑杰克路径留在递归中，与玩家的全部投注比较；
+//! OBO 规则下庄家黑杰克只输原始投注，仍在递归之外按原始投注单独结算。
+//! 早投降在庄家检查黑杰克之前决定，此时庄家黑杰克改在初始决策点按动作结算。
 
 use crate::types::{
-    Card, CardCounts, GameOutcome, Hand, PlayingCard, PointCounts, Suit,
-    card_counts_to_point_counts, point_index_to_value,
+    Action, ActionEV, Card, CardCounts, DealerDistribution, GameOutcome, Hand, InsuranceEV,
+    InsurancePolicy, PayoutDistribution, PlayingCard, PointCounts, StrategyMode, Suit, card_counts_to_point_counts, card_to_point_index, point_index_to_card,
+    point_index_to_value,
 };
-use crate::rules::{DealerRules, GameRules};
-use crate::strategy::BasicStrategy;
+use crate::rules::{DealerRules, DoubleRule, GameRules, HoleCardRule};
+use crate::strategy::{BasicStrategy, Strategy};
+use crate::deviations::DeviationTable;
+use crate::ev_calculator::calculate_ev;
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hasher};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::sync::Arc;
+#[cfg(feature = "parallel")]
+use std::sync::{Mutex, PoisonError};
+
+/// 庄家最终结果概率（未归一化）
+/// 索引：0-4 = 停在 17-21 点，5 = 黑杰克，6 = 爆牌
+type DealerProbs = [f64; 7];
+
+/// 庄家黑杰克在 `DealerProbs` 中的索引
+pub(crate) const DEALER_BLACKJACK: usize = 5;
+/// 庄家爆牌在 `DealerProbs` 中的索引
+pub(crate) const DEALER_BUST: usize = 6;
+
+/// 剩余牌组的精确编码：合并十点牌后的 10 种点数各占 12 位
+///
+/// 每种点数最多 4095 张（足够 255 副牌），在此范围内不同的牌组编码一定不同
+pub(crate) type DeckKey = u128;
+
+/// `DeckKey` 中每种点数占用的位数
+const DECK_KEY_BITS: u32 = 12;
+
+/// 庄家结果缓存的键：(庄家点数, 是否软点数, 是否只有明牌, 剩余牌组)
+pub(crate) type DealerMemoKey = (u8, bool, bool, DeckKey);
+
+/// 玩家要牌后结果缓存的键：(玩家点数, 是否软点数, 玩家牌数, 手牌阶段, 庄家明牌索引, 剩余牌组)
+pub(crate) type PlayerMemoKey = (u8, bool, usize, HandStage, usize, DeckKey);
+
+/// 缓存使用的哈希表（键是精确编码，不需要抗碰撞攻击，用乘法哈希代替默认的 SipHash）
+type MemoMap<K, V> = HashMap<K, V, BuildHasherDefault<MemoHasher>>;
+
+/// 乘法哈希：逐字混合方式与 FxHash 相同，结束时再做一次 fmix64 扩散
+#[derive(Default)]
+struct MemoHasher(u64);
+
+impl MemoHasher {
+    fn add(&mut self, value: u64) {
+        self.0 = (self.0.rotate_left(5) ^ value).wrapping_mul(0x517c_c1b7_2722_0a95);
+    }
+}
+
+impl Hasher for MemoHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.add(byte as u64);
+        }
+    }
+
+    fn write_u8(&mut self, value: u8) {
+        self.add(value as u64);
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.add(value);
+    }
+
+    fn write_u128(&mut self, value: u128) {
+        self.add(value as u64);
+        self.add((value >> 64) as u64);
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.add(value as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        // 最终混合，让低位也充分依赖所有输入位（哈希表用低位选桶）
+        let mut hash = self.0;
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+        hash ^= hash >> 33;
+        hash
+    }
+}
+
+/// 缓存条目上限，超过后在下一次计算开始前清空，避免跨调用复用时内存无限增长
+const MAX_CACHE_ENTRIES: usize = 2_000_000;
+
+/// 玩家手牌所处阶段（决定可选动作）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum HandStage {
+    /// 初始两张牌：可加倍、分牌、投降
+    Initial,
+    /// 分牌后的两张牌：加倍取决于是否允许分牌后加倍，不能投降
+    Split,
+    /// 要牌之后：要牌或停牌，任意张数加倍规则下还可加倍
+    Drawn,
+    /// 不允许分牌后加倍时分牌手牌要牌之后：只能要牌或停牌
+    /// （只在任意张数加倍规则下与 `Drawn` 区分，其他规则共用 `Drawn` 的缓存）
+    SplitDrawn,
+}
+
+/// 一手牌的收益按庄家最终结果分解：E[收益 × 庄家结果为 d]，索引同 `DealerProbs`
+type DealerPayoffs = [f64; 7];
+
+/// 一个分牌位置（一手牌，或再分牌后的多手牌）的收益，已按概率加权
+///
+/// 分牌递归同时用于 EV 和方差（`SplitHandMoments`）以及净收益分布（`SplitHandDistribution`）
+trait SplitPayoffs: Clone + Default {
+    /// 收益只取决于庄家最终结果的一手牌，`payoff(d)` 为庄家结果为 d 时的收益
+    fn settled(dealer: &DealerProbs, payoff: impl Fn(usize) -> f64) -> Self;
+
+    /// 投注乘以 `bet`（加倍为 2）
+    fn scaled(&self, bet: f64) -> Self;
+
+    /// 按权重累加
+    fn add(&mut self, other: &Self, weight: f64);
+
+    /// 两个分牌位置之和
+    ///
+    /// 两手面对同一个庄家：以庄家最终结果为条件时视为相互独立（忽略两手之间的牌张消耗），
+    /// `dealer` 为分牌时庄家最终结果的分布
+    fn paired(&self, other: &Self, dealer: &DealerProbs) -> Self;
+
+    /// 期望收益
+    fn ev(&self) -> f64;
+
+    /// 要牌后手牌的缓存
+    fn memo(calculator: &mut ProbabilityCalculator) -> &mut MemoMap<PlayerMemoKey, Self>;
+}
+
+/// 分牌位置的收益矩
+#[derive(Debug, Clone, Copy, Default)]
+struct SplitHandMoments {
+    /// 期望收益
+    ev: f64,
+    /// 收益平方的期望
+    second_moment: f64,
+    /// 按庄家最终结果分解的期望收益
+    by_dealer: DealerPayoffs,
+}
+
+impl SplitPayoffs for SplitHandMoments {
+    fn settled(dealer: &DealerProbs, payoff: impl Fn(usize) -> f64) -> Self {
+        let mut moments = Self::default();
+        for (i, &prob) in dealer.iter().enumerate() {
+            let payoff = payoff(i);
+            moments.ev += prob * payoff;
+            moments.second_moment += prob * payoff * payoff;
+            moments.by_dealer[i] = prob * payoff;
+        }
+        moments
+    }
+
+    fn scaled(&self, bet: f64) -> Self {
+        Self {
+            ev: self.ev * bet,
+            second_moment: self.second_moment * bet * bet,
+            by_dealer: self.by_dealer.map(|payoff| payoff * bet),
+        }
+    }
+
+    fn add(&mut self, other: &Self, weight: f64) {
+        self.ev += other.ev * weight;
+        self.second_moment += other.second_moment * weight;
+        for (total, payoff) in self.by_dealer.iter_mut().zip(other.by_dealer) {
+            *total += payoff * weight;
+        }
+    }
+
+    /// E[X₁·X₂] = Σ_d P(d)·E[X₁ | d]·E[X₂ | d] = Σ_d by_dealer₁[d]·by_dealer₂[d] / P(d)
+    fn paired(&self, other: &Self, dealer: &DealerProbs) -> Self {
+        let cross: f64 = self
+            .by_dealer
+            .iter()
+            .zip(&other.by_dealer)
+            .zip(dealer)
+            .filter(|&(_, &prob)| prob > 0.0)
+            .map(|((&first, &second), &prob)| first * second / prob)
+            .sum();
+        let mut by_dealer = self.by_dealer;
+        for (total, payoff) in by_dealer.iter_mut().zip(other.by_dealer) {
+            *total += payoff;
+        }
+        Self {
+            ev: self.ev + other.ev,
+            second_moment: self.second_moment + other.second_moment + 2.0 * cross,
+            by_dealer,
+        }
+    }
+
+    fn ev(&self) -> f64 {
+        self.ev
+    }
+
+    fn memo(calculator: &mut ProbabilityCalculator) -> &mut MemoMap<PlayerMemoKey, Self> {
+        &mut calculator.moment_memo
+    }
+}
+
+/// 分牌位置的净收益分布，按庄家最终结果分解：P(收益 = x 且庄家结果为 d)，索引同 `DealerProbs`
+#[derive(Debug, Clone, Default)]
+struct SplitHandDistribution {
+    by_dealer: [PayoutDistribution; 7],
+}
+
+impl SplitPayoffs for SplitHandDistribution {
+    fn settled(dealer: &DealerProbs, payoff: impl Fn(usize) -> f64) -> Self {
+        let mut distribution = Self::default();
+        for (i, &prob) in dealer.iter().enumerate() {
+            distribution.by_dealer[i].add(payoff(i), prob);
+        }
+        distribution
+    }
+
+    fn scaled(&self, bet: f64) -> Self {
+        Self {
+            by_dealer: self.by_dealer.each_ref().map(|payouts| payouts.scaled(bet)),
+        }
+    }
+
+    fn add(&mut self, other: &Self, weight: f64) {
+        for (total, payouts) in self.by_dealer.iter_mut().zip(&other.by_dealer) {
+            total.merge(payouts, weight);
+        }
+    }
+
+    /// P(X₁ + X₂ = s, d) = Σ P(X₁ = x, d)·P(X₂ = s - x, d) / P(d)
+    fn paired(&self, other: &Self, dealer: &DealerProbs) -> Self {
+        let mut distribution = Self::default();
+        let positions = self.by_dealer.iter().zip(&other.by_dealer);
+        for ((total, (first, second)), &prob) in distribution.by_dealer.iter_mut().zip(positions).zip(dealer) {
+            if prob > 0.0 {
+                total.merge(&first.convolve(second), 1.0 / prob);
+            }
+        }
+        distribution
+    }
+
+    fn ev(&self) -> f64 {
+        self.by_dealer.iter().map(PayoutDistribution::ev).sum()
+    }
+
+    fn memo(calculator: &mut ProbabilityCalculator) -> &mut MemoMap<PlayerMemoKey, Self> {
+        &mut calculator.distribution_memo
+    }
+}
 
 /// 概率计算器
 pub struct ProbabilityCalculator {
     rules: GameRules,
-    strategy: BasicStrategy,
-    memo: std::collections::HashMap<(u8, bool, usize, u8, u64), GameOutcome>,
+    /// 基础策略模式下查询玩家动作的策略
+    strategy: Arc<dyn Strategy>,
+    /// 基础策略模式下按剩余牌组的 true count 偏离基础策略
+    deviations: Option<DeviationTable>,
+    mode: StrategyMode,
+    insurance: InsurancePolicy,
+    /// 庄家结果缓存（以完整的剩余牌组为键，不会冲突）
+    ///
+    /// 结果只取决于规则和剩余牌组，跨调用保留，牌组相近的连续查询可以直接复用
+    memo: MemoMap<DealerMemoKey, DealerProbs>,
+    /// 玩家要牌后手牌的结果缓存
+    /// 最优策略模式下决策与牌数无关，牌数固定记为 0 以共享结果
+    player_memo: MemoMap<PlayerMemoKey, GameOutcome>,
+    /// 分牌手牌要牌后的收益矩缓存（键与 `player_memo` 相同）
+    moment_memo: MemoMap<PlayerMemoKey, SplitHandMoments>,
+    /// 分牌手牌要牌后的净收益分布缓存（只在计算净收益分布时使用）
+    distribution_memo: MemoMap<PlayerMemoKey, SplitHandDistribution>,
+    /// 并行计算的工作计算器（每个线程一个，各自持有跨调用保留的缓存）
+    #[cfg(feature = "parallel")]
+    workers: Vec<Mutex<ProbabilityCalculator>>,
+}
+
+/// 一种初始发牌：玩家两张牌和庄家明牌（庄家暗牌留在剩余牌组中）
+pub(crate) struct InitialDeal {
+    pub(crate) player_hand: Hand,
+    pub(crate) dealer_up_idx: usize,
+    /// 发牌后的剩余牌组
+    pub(crate) counts: PointCounts,
+    /// 这种发牌的概率
+    pub(crate) prob: f64,
+    /// 这种发牌的方式数（三张牌剩余张数之积），概率 = 方式数 / (n × (n-1) × (n-2))
+    #[cfg(feature = "exact")]
+    pub(crate) ways: u64,
 }
 
 impl ProbabilityCalculator {
-    /// 创建新的概率计算器
+    /// 创建新的概率计算器（按基础策略决策）
     pub fn new(rules: GameRules) -> Self {
+        Self::with_mode(rules, StrategyMode::Basic)
+    }
+
+    /// 创建指定策略模式的概率计算器
+    pub fn with_mode(rules: GameRules, mode: StrategyMode) -> Self {
         Self {
             rules,
-            strategy: BasicStrategy::new(),
-            memo: std::collections::HashMap::new(),
+            strategy: Arc::new(BasicStrategy::new()),
+            deviations: None,
+            mode,
+            insurance: InsurancePolicy::Never,
+            memo: MemoMap::default(),
+            player_memo: MemoMap::default(),
+            moment_memo: MemoMap::default(),
+            distribution_memo: MemoMap::default(),
+            #[cfg(feature = "parallel")]
+            workers: Vec::new(),
+        }
+    }
+
+    /// 设置保险策略（影响 `calculate_table_ev`）
+    pub fn with_insurance_policy(mut self, policy: InsurancePolicy) -> Self {
+        self.set_insurance_policy(policy);
+        self
+    }
+
+    /// 设置保险策略（保险在缓存之外结算，不影响已有缓存）
+    pub fn set_insurance_policy(&mut self, policy: InsurancePolicy) {
+        self.insurance = policy;
+    }
+
+    /// 设置基础策略模式下使用的策略（清空玩家结果缓存）
+    pub fn set_strategy(&mut self, strategy: Arc<dyn Strategy>) {
+        self.strategy = strategy;
+        self.player_memo.clear();
+        self.moment_memo.clear();
+        self.distribution_memo.clear();
+        #[cfg(feature = "parallel")]
+        self.workers.clear();
+    }
+
+    /// 设置指数打法（只影响基础策略模式，清空玩家结果缓存）
+    pub fn set_deviations(&mut self, deviations: Option<DeviationTable>) {
+        self.deviations = deviations;
+        self.player_memo.clear();
+        self.moment_memo.clear();
+        self.distribution_memo.clear();
+        #[cfg(feature = "parallel")]
+        self.workers.clear();
+    }
+
+    /// 游戏规则
+    #[cfg(feature = "exact")]
+    pub(crate) fn rules(&self) -> &GameRules {
+        &self.rules
+    }
+
+    /// 策略模式
+    #[cfg(feature = "exact")]
+    pub(crate) fn mode(&self) -> StrategyMode {
+        self.mode
+    }
+
+    /// 缓存条目数（庄家结果 + 玩家结果，包括并行工作计算器的缓存）
+    pub fn cache_size(&self) -> usize {
+        let size = self.memo.len() + self.player_memo.len()
+            + self.moment_memo.len()
+            + self.distribution_memo.len();
+        #[cfg(feature = "parallel")]
+        let size = size
+            + self
+                .workers
+                .iter()
+                .map(|worker| worker.lock().unwrap_or_else(PoisonError::into_inner).cache_size())
+                .sum::<usize>();
+        size
+    }
+
+    /// 清空缓存
+    pub fn clear_cache(&mut self) {
+        self.memo.clear();
+        self.player_memo.clear();
+        self.moment_memo.clear();
+        self.distribution_memo.clear();
+        #[cfg(feature = "parallel")]
+        for worker in &mut self.workers {
+            worker.get_mut().unwrap_or_else(PoisonError::into_inner).clear_cache();
+        }
+    }
+
+    /// 缓存过大时清空
+    fn limit_cache_size(&mut self) {
+        if self.cache_size() > MAX_CACHE_ENTRIES {
+            self.clear_cache();
         }
     }
 
     /// 计算上桌 EV（主入口）
     pub fn calculate_table_ev(&mut self, deck: &CardCounts) -> GameOutcome {
-        self.memo.clear();
-        
-        let point_counts = card_counts_to_point_counts(deck);
+        self.limit_cache_size();
+
+        let point_counts = Self::merged_point_counts(deck);
         let total_cards: u32 = point_counts.iter().sum();
-        
+
         if total_cards < 4 {
             return GameOutcome::zero();
         }
 
         let mut total_outcome = GameOutcome::zero();
-        
+
         // 优化策略：分层计算
         // 1. 遍历所有可能的玩家初始手牌组合（按点数分组）
         // 2. 对每种玩家手牌，遍历所有可能的庄家明牌
-        // 3. 对每种组合，计算所有可能的庄家暗牌
+        // 3. 庄家暗牌在玩家行动结束后再抽取
         self.calculate_layered(&point_counts, total_cards, &mut total_outcome);
-        
+
         total_outcome
     }
 
-    /// 分层计算：先玩家手牌，再庄家明牌，最后庄家暗牌
+    /// 计算一局净收益的概率分布
+    ///
+    /// 与 `calculate_table_ev` 使用相同的枚举和决策，分布的 EV 与上桌 EV 相同
+    pub fn calculate_payout_distribution(&mut self, deck: &CardCounts) -> PayoutDistribution {
+        self.limit_cache_size();
+
+        let point_counts = Self::merged_point_counts(deck);
+        let total_cards: u32 = point_counts.iter().sum();
+
+        let mut distribution = PayoutDistribution::new();
+        if total_cards < 4 {
+            return distribution;
+        }
+
+        for deal in self.initial_deals(&point_counts, total_cards) {
+            let sub_distribution =
+                self.game_distribution(&deal.player_hand, deal.dealer_up_idx, &deal.counts);
+            distribution.merge(&sub_distribution, deal.prob);
+        }
+        distribution
+    }
+
+    /// 计算某个决策点上每个合法动作的 EV
+    ///
+    /// `deck` 为未见的剩余牌（不含玩家手牌和庄家明牌，庄家暗牌在其中）。
+    /// 庄家已偷看时返回庄家没有黑杰克的条件 EV；无暗牌规则或早投降的初始决策点
+    /// 玩家不知道庄家是否黑杰克，返回包含庄家黑杰克的 EV。后续决策按当前策略模式进行。
+    pub fn calculate_action_evs(
+        &mut self,
+        player_hand: &Hand,
+        dealer_up_card: Card,
+        deck: &CardCounts,
+    ) -> Vec<ActionEV> {
+        self.limit_cache_size();
+
+        let counts = Self::merged_point_counts(deck);
+        let dealer_up_idx = Self::merged_point_index(dealer_up_card);
+
+        let stage = if player_hand.card_count() == 2 {
+            HandStage::Initial
+        } else {
+            HandStage::Drawn
+        };
+
+        // 庄家偷看后必然是黑杰克时，玩家没有可行动的决策点
+        let no_blackjack_prob = self.no_dealer_blackjack_prob(dealer_up_idx, &counts);
+        let peeked = self.rules.hole_card == HoleCardRule::Peek
+            && (stage != HandStage::Initial || !self.early_surrender(dealer_up_idx));
+        if peeked && no_blackjack_prob <= 0.0 {
+            return Vec::new();
+        }
+        let condition_prob = if peeked { no_blackjack_prob } else { 1.0 };
+
+        // 黑杰克直接按赔率结算（无暗牌时庄家黑杰克为平局）
+        if player_hand.is_blackjack() {
+            return vec![ActionEV {
+                action: Action::Stand,
+                ev: self.rules.blackjack_payout * no_blackjack_prob / condition_prob,
+            }];
+        }
+
+        self.legal_actions(player_hand, stage)
+            .into_iter()
+            .map(|action| {
+                let mut outcome =
+                    self.action_outcome(action, player_hand, dealer_up_idx, &counts, stage);
+                outcome.dealer_blackjack_prob +=
+                    self.pending_blackjack_loss(action, stage, dealer_up_idx, &counts);
+                ActionEV {
+                    action,
+                    ev: self.outcome_ev(&outcome) / condition_prob,
+                }
+            })
+            .collect()
+    }
+
+    /// 计算庄家明牌为A时保险和等额赔付的 EV
+    ///
+    /// `deck` 为未见的剩余牌（不含玩家手牌和庄家明牌A，庄家暗牌在其中）
+    pub fn calculate_insurance(&self, deck: &CardCounts) -> InsuranceEV {
+        let counts = Self::merged_point_counts(deck);
+        let dealer_blackjack_prob = self.dealer_blackjack_prob(0, &counts);
+
+        InsuranceEV {
+            dealer_blackjack_prob,
+            insurance: Self::insurance_ev(dealer_blackjack_prob),
+            even_money: 1.0,
+            decline_even_money: self.rules.blackjack_payout * (1.0 - dealer_blackjack_prob),
+        }
+    }
+
+    /// 计算庄家最终点数分布
+    ///
+    /// `dealer_up_card` 为 None 时明牌未知，从 `deck` 中抽取；
+    /// 否则 `deck` 为不含明牌的剩余牌组。结果包含黑杰克，概率总和为1。
+    pub fn calculate_dealer_distribution(
+        &mut self,
+        dealer_up_card: Option<Card>,
+        deck: &CardCounts,
+    ) -> DealerDistribution {
+        self.limit_cache_size();
+
+        let counts = Self::merged_point_counts(deck);
+
+        let Some(up_card) = dealer_up_card else {
+            // 明牌未知：按明牌概率加权
+            let total_cards: u32 = counts.iter().sum();
+            let mut probs: DealerProbs = [0.0; 7];
+            for up_idx in 0..=10 {
+                if counts[up_idx] == 0 {
+                    continue;
+                }
+                let prob = counts[up_idx] as f64 / total_cards as f64;
+                let mut new_counts = counts;
+                new_counts[up_idx] -= 1;
+                let sub_probs = self.dealer_probs_with_blackjack(up_idx, &new_counts);
+                for (total, sub) in probs.iter_mut().zip(sub_probs) {
+                    *total += sub * prob;
+                }
+            }
+            return Self::to_dealer_distribution(&probs);
+        };
+
+        let up_idx = Self::merged_point_index(up_card);
+        let probs = self.dealer_probs_with_blackjack(up_idx, &counts);
+        Self::to_dealer_distribution(&probs)
+    }
+
+    /// 给定明牌时庄家的完整分布（补回偷看时排除的黑杰克路径）
+    fn dealer_probs_with_blackjack(
+        &mut self,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+    ) -> DealerProbs {
+        let (up_value, up_soft) = Self::add_card_to_total(0, false, dealer_up_idx);
+        let mut probs = self.dealer_play_outcome(up_value, up_soft, true, counts);
+        if self.rules.hole_card != HoleCardRule::NoHoleCard {
+            probs[DEALER_BLACKJACK] += self.dealer_blackjack_prob(dealer_up_idx, counts);
+        }
+        probs
+    }
+
+    /// 将内部的庄家概率数组转换为公开的分布结构
+    fn to_dealer_distribution(probs: &DealerProbs) -> DealerDistribution {
+        DealerDistribution {
+            total_17: probs[0],
+            total_18: probs[1],
+            total_19: probs[2],
+            total_20: probs[3],
+            total_21: probs[4],
+            blackjack: probs[DEALER_BLACKJACK],
+            bust: probs[DEALER_BUST],
+        }
+    }
+
+    /// 将牌组转换为计算用的点数统计（10 和 J/Q/K 点数相同，合并后减少一半的十点牌分支）
+    pub(crate) fn merged_point_counts(deck: &CardCounts) -> PointCounts {
+        let mut point_counts = card_counts_to_point_counts(deck);
+        point_counts[9] += point_counts[10];
+        point_counts[10] = 0;
+        assert!(
+            point_counts.iter().all(|&count| count < 1 << DECK_KEY_BITS),
+            "每种点数最多支持 {} 张牌",
+            (1 << DECK_KEY_BITS) - 1
+        );
+        point_counts
+    }
+
+    /// 剩余牌组的精确缓存键（十点牌已合并到索引 9）
+    pub(crate) fn deck_key(counts: &PointCounts) -> DeckKey {
+        counts[..10]
+            .iter()
+            .fold(0, |key, &count| (key << DECK_KEY_BITS) | count as DeckKey)
+    }
+
+    /// 牌在合并十点牌后的点数索引
+    ///
+    /// # Panics
+    /// 牌面无效（`Calculator` 在调用引擎之前已经检查）
+    fn merged_point_index(card: Card) -> usize {
+        let (index, _) = card_to_point_index(card).expect("牌面应为 A、2-10 或 J/Q/K");
+        index.min(9)
+    }
+
+    /// 分层计算（串行）：逐个初始发牌计算游戏结果
+    #[cfg(not(feature = "parallel"))]
+    fn calculate_layered(
+        &mut self,
+        counts: &PointCounts,
+        total_cards: u32,
+        outcome: &mut GameOutcome,
+    ) {
+        for deal in self.initial_deals(counts, total_cards) {
+            let sub_outcome =
+                self.calculate_game_outcome(&deal.player_hand, deal.dealer_up_idx, &deal.counts);
+            outcome.add(&sub_outcome, deal.prob);
+        }
+    }
+
+    /// 分层计算（并行）：初始发牌分配到 rayon 线程池
+    ///
+    /// 每个线程使用自己的工作计算器和缓存，结果按初始发牌的固定顺序累加，
+    /// 与串行计算的结果完全相同
+    #[cfg(feature = "parallel")]
     fn calculate_layered(
         &mut self,
         counts: &PointCounts,
         total_cards: u32,
         outcome: &mut GameOutcome,
     ) {
+        let deals = self.initial_deals(counts, total_cards);
+        self.prepare_workers();
+
+        let workers = &self.workers;
+        let sub_outcomes: Vec<GameOutcome> = deals
+            .par_iter()
+            .map(|deal| {
+                let index = rayon::current_thread_index().unwrap_or(0) % workers.len();
+                let mut worker = workers[index].lock().unwrap_or_else(PoisonError::into_inner);
+                worker.calculate_game_outcome(&deal.player_hand, deal.dealer_up_idx, &deal.counts)
+            })
+            .collect();
+
+        for (deal, sub_outcome) in deals.iter().zip(&sub_outcomes) {
+            outcome.add(sub_outcome, deal.prob);
+        }
+    }
+
+    /// 按线程池大小准备工作计算器，并同步保险策略
+    #[cfg(feature = "parallel")]
+    fn prepare_workers(&mut self) {
+        let threads = rayon::current_num_threads().max(1);
+        while self.workers.len() < threads {
+            let worker = Self {
+                rules: self.rules.clone(),
+                strategy: self.strategy.clone(),
+                deviations: self.deviations.clone(),
+                mode: self.mode,
+                insurance: self.insurance,
+                memo: MemoMap::default(),
+                player_memo: MemoMap::default(),
+                moment_memo: MemoMap::default(),
+                distribution_memo: MemoMap::default(),
+                workers: Vec::new(),
+            };
+            self.workers.push(Mutex::new(worker));
+        }
+        for worker in &mut self.workers {
+            worker.get_mut().unwrap_or_else(PoisonError::into_inner).insurance = self.insurance;
+        }
+    }
+
+    /// 所有初始发牌及其概率
+    ///
+    /// 1. 玩家第一张牌
+    /// 2. 庄家明牌
+    /// 3. 玩家第二张牌（庄家暗牌留在剩余牌组中）
+    pub(crate) fn initial_deals(&self, counts: &PointCounts, total_cards: u32) -> Vec<InitialDeal> {
+        let mut deals = Vec::new();
+
         // 第一层：玩家第一张牌
         for p1_idx in 0..=10 {
             if counts[p1_idx] == 0 {
@@ -61,31 +685,23 @@ impl ProbabilityCalculator {
             let p1_count = counts[p1_idx];
             let p1_value = point_index_to_value(p1_idx);
             let prob1 = p1_count as f64 / total_cards as f64;
-            
+
             let mut counts_after_p1 = *counts;
             counts_after_p1[p1_idx] -= 1;
             let remaining_after_p1 = total_cards - 1;
-            
+
             // 第二层：庄家明牌
             for d_up_idx in 0..=10 {
                 if counts_after_p1[d_up_idx] == 0 {
                     continue;
                 }
                 let d_up_count = counts_after_p1[d_up_idx];
-                let d_up_value = point_index_to_value(d_up_idx);
-                let dealer_up_card = if d_up_idx == 0 {
-                    Card::Ace
-                } else if d_up_idx == 10 {
-                    Card::Face
-                } else {
-                    Card::Number(d_up_idx as u8)
-                };
                 let prob2 = d_up_count as f64 / remaining_after_p1 as f64;
-                
+
                 let mut counts_after_d_up = counts_after_p1;
                 counts_after_d_up[d_up_idx] -= 1;
                 let remaining_after_d_up = remaining_after_p1 - 1;
-                
+
                 // 第三层：玩家第二张牌
                 for p2_idx in 0..=10 {
                     if counts_after_d_up[p2_idx] == 0 {
@@ -94,412 +710,1114 @@ impl ProbabilityCalculator {
                     let p2_count = counts_after_d_up[p2_idx];
                     let p2_value = point_index_to_value(p2_idx);
                     let prob3 = p2_count as f64 / remaining_after_d_up as f64;
-                    
-                    let mut counts_after_p2 = counts_after_d_up;
-                    counts_after_p2[p2_idx] -= 1;
-                    let remaining_after_p2 = remaining_after_d_up - 1;
-                    
-                    // 第四层：庄家暗牌
-                    for d_hidden_idx in 0..=10 {
-                        if counts_after_p2[d_hidden_idx] == 0 {
-                            continue;
-                        }
-                        let d_hidden_count = counts_after_p2[d_hidden_idx];
-                        let d_hidden_value = point_index_to_value(d_hidden_idx);
-                        let prob4 = d_hidden_count as f64 / remaining_after_p2 as f64;
-                        
-                        let initial_prob = prob1 * prob2 * prob3 * prob4;
-                        
-                        // 创建剩余牌组
-                        let mut new_counts = counts_after_p2;
-                        new_counts[d_hidden_idx] -= 1;
-                        
+
+                    // 庄家暗牌留在剩余牌组中
+                    let mut new_counts = counts_after_d_up;
+                    new_counts[p2_idx] -= 1;
+
+                    deals.push(InitialDeal {
                         // 创建实际手牌对象（用于策略查询）
-                        let player_hand = self.create_hand_from_values(p1_value, p2_value);
-                        let dealer_hand = self.create_hand_from_values(d_up_value, d_hidden_value);
-                        
-                        // 计算该初始组合的游戏结果
-                        let sub_outcome = self.calculate_game_outcome(
-                            &player_hand,
-                            &dealer_hand,
-                            dealer_up_card,
-                            &new_counts,
-                        );
-                        
-                        outcome.add(&sub_outcome, initial_prob);
-                    }
+                        player_hand: self.create_hand_from_values(p1_value, p2_value),
+                        dealer_up_idx: d_up_idx,
+                        counts: new_counts,
+                        prob: prob1 * prob2 * prob3,
+                        #[cfg(feature = "exact")]
+                        ways: p1_count as u64 * d_up_count as u64 * p2_count as u64,
+                    });
+                }
+            }
+        }
+
+        deals
+    }
+
+    /// 从两张牌的值创建手牌对象
+    fn create_hand_from_values(&self, card1: u8, card2: u8) -> Hand {
+        let mut hand = Hand::new();
+        hand.add_card(PlayingCard {
+            card: if card1 == 1 {
+                Card::Ace
+            } else if card1 == 10 {
+                Card::Face
+            } else {
+                Card::Number(card1)
+            },
+            suit: Suit::Spades,
+        });
+        hand.add_card(PlayingCard {
+            card: if card2 == 1 {
+                Card::Ace
+            } else if card2 == 10 {
+                Card::Face
+            } else {
+                Card::Number(card2)
+            },
+            suit: Suit::Spades,
+        });
+        hand
+    }
+
+    /// 计算一局的游戏结果（庄家暗牌尚未抽取）
+    fn calculate_game_outcome(
+        &mut self,
+        player_hand: &Hand,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+    ) -> GameOutcome {
+        let dealer_blackjack_prob = self.dealer_blackjack_prob(dealer_up_idx, counts);
+        let mut outcome = GameOutcome::zero();
+
+        // 检查玩家黑杰克：庄家也是黑杰克则平局
+        if player_hand.is_blackjack() {
+            outcome.push_prob = dealer_blackjack_prob;
+            outcome.player_blackjack_prob = 1.0 - dealer_blackjack_prob;
+        } else {
+            // 庄家偷看到黑杰克：玩家只输原始投注（早投降时改在决策点结算）
+            if self.rules.hole_card == HoleCardRule::Peek && !self.early_surrender(dealer_up_idx) {
+                outcome.dealer_blackjack_prob = dealer_blackjack_prob;
+            }
+
+            // 玩家行动（偷看规则下结果已包含庄家没有黑杰克的概率）
+            let play_outcome =
+                self.play_hand(player_hand, dealer_up_idx, counts, HandStage::Initial);
+            outcome.add(&play_outcome, 1.0);
+        }
+
+        // 庄家黑杰克时主注的收益：玩家黑杰克平局，否则输掉原始投注
+        let blackjack_loss = if player_hand.is_blackjack() {
+            0.0
+        } else {
+            -dealer_blackjack_prob
+        };
+        let insurance = self.insurance_outcome(
+            dealer_up_idx,
+            counts,
+            dealer_blackjack_prob,
+            self.outcome_ev(&outcome),
+            blackjack_loss,
+        );
+        outcome.add(&insurance, 1.0);
+        outcome
+    }
+
+    /// 一局净收益的分布（庄家暗牌尚未抽取，与 `calculate_game_outcome` 的结果相同）
+    ///
+    /// 普通投注、加倍和投降的收益由 `GameOutcome` 的各项概率给出，分牌局由分牌递归
+    /// 按庄家最终结果卷积各手的收益。购买保险时庄家黑杰克的结果加 1、其余结果减 0.5
+    /// （无暗牌规则下玩家爆牌后庄家的黑杰克不单独区分，与保险的方差项相同）
+    fn game_distribution(
+        &mut self,
+        player_hand: &Hand,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+    ) -> PayoutDistribution {
+        let outcome = self.calculate_game_outcome(player_hand, dealer_up_idx, counts);
+        let blackjack_payout = self.rules.blackjack_payout;
+        let charlie_payout = self.rules.charlie.map_or(1.0, |charlie| charlie.payout);
+
+        // 庄家黑杰克时的结果（玩家黑杰克只在庄家也是黑杰克时平局）
+        let mut blackjack = PayoutDistribution::new();
+        blackjack.add(-1.0, outcome.dealer_blackjack_prob);
+        blackjack.add(-2.0, outcome.dealer_blackjack_prob_double);
+        // 其余结果
+        let mut other = PayoutDistribution::new();
+        if player_hand.is_blackjack() {
+            blackjack.add(0.0, outcome.push_prob);
+        } else {
+            other.add(0.0, outcome.push_prob);
+        }
+        for (payout, prob) in [
+            (1.0, outcome.player_win_prob),
+            (blackjack_payout, outcome.player_blackjack_prob),
+            (charlie_payout, outcome.player_charlie_prob),
+            (-1.0, outcome.dealer_win_prob),
+            (2.0, outcome.player_win_prob_double),
+            (2.0 * blackjack_payout, outcome.player_blackjack_prob_double),
+            (2.0 * charlie_payout, outcome.player_charlie_prob_double),
+            (0.0, outcome.push_prob_double),
+            (-2.0, outcome.dealer_win_prob_double),
+            (-0.5, outcome.surrender_prob),
+        ] {
+            other.add(payout, prob);
+        }
+
+        if outcome.split_prob > 0.0 {
+            let hands: SplitHandDistribution =
+                self.split_payoffs(player_hand, dealer_up_idx, counts);
+            for (i, payouts) in hands.by_dealer.iter().enumerate() {
+                let part = if i == DEALER_BLACKJACK { &mut blackjack } else { &mut other };
+                part.merge(payouts, 1.0);
+            }
+        }
+
+        if outcome.insurance_prob > 0.0 {
+            blackjack = blackjack.shifted(1.0);
+            other = other.shifted(-0.5);
+        }
+        blackjack.merge(&other, 1.0);
+        blackjack
+    }
+
+    /// 按保险策略决定是否购买保险的结果
+    ///
+    /// 保险注独立于主注结算，玩家黑杰克时购买保险与选择等额赔付等价。
+    /// 保险注在庄家黑杰克时赢 1、否则输 0.5，与主注同局结算，因此净收益平方的期望
+    /// 包含交叉项 2·E[主注 × 保险注]：`main_ev` 为主注的 EV，`blackjack_loss` 为
+    /// E[主注 × 庄家黑杰克]（无暗牌规则下加倍和分牌的追加投注不计入）
+    fn insurance_outcome(
+        &self,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+        dealer_blackjack_prob: f64,
+        main_ev: f64,
+        blackjack_loss: f64,
+    ) -> GameOutcome {
+        let insurance_ev = Self::insurance_ev(dealer_blackjack_prob);
+        if !self.takes_insurance(dealer_up_idx, counts, insurance_ev > 0.0) {
+            return GameOutcome::zero();
+        }
+
+        // 保险注 = -0.5 + 1.5 × [庄家黑杰克]
+        let insurance_square = dealer_blackjack_prob + 0.25 * (1.0 - dealer_blackjack_prob);
+        let cross = -0.5 * main_ev + 1.5 * blackjack_loss;
+        GameOutcome {
+            insurance_prob: 1.0,
+            insurance_ev,
+            insurance_second_moment: insurance_square + 2.0 * cross,
+            ..GameOutcome::zero()
+        }
+    }
+
+    /// 庄家明牌为A时是否购买保险
+    ///
+    /// 基础策略模式下指数打法表带保险指数时，按剩余牌组的 true count 决定；
+    /// 否则按保险策略（`insurance_positive` 为保险 EV 是否为正）
+    pub(crate) fn takes_insurance(
+        &self,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+        insurance_positive: bool,
+    ) -> bool {
+        if dealer_up_idx != 0 {
+            return false;
+        }
+        match &self.deviations {
+            Some(table) if self.mode == StrategyMode::Basic && table.insurance_index.is_some() => {
+                let true_count = table.system.true_count_of_points(counts, self.rules.deck_count);
+                table.takes_insurance(true_count)
+            }
+            _ => self.insurance == InsurancePolicy::WhenPositive && insurance_positive,
+        }
+    }
+
+    /// 保险注的期望收益（以原始投注为单位）
+    ///
+    /// 保险注为原始投注的一半，庄家黑杰克时按 2:1 赔付：
+    /// EV = 0.5 × (2 × P(黑杰克) - (1 - P(黑杰克)))
+    fn insurance_ev(dealer_blackjack_prob: f64) -> f64 {
+        0.5 * (3.0 * dealer_blackjack_prob - 1.0)
+    }
+
+    /// 玩家在当前阶段行动的结果
+    fn play_hand(
+        &mut self,
+        player_hand: &Hand,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+        stage: HandStage,
+    ) -> GameOutcome {
+        match self.mode {
+            StrategyMode::Basic => {
+                let action = self.basic_action(player_hand, dealer_up_idx, stage, counts);
+                self.decision_outcome(action, player_hand, dealer_up_idx, counts, stage)
+            }
+            StrategyMode::Optimal => self
+                .best_action(player_hand, dealer_up_idx, counts, stage)
+                .map(|(_, outcome)| outcome)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// 当前阶段 EV 最高的合法动作及其结果（EV 相同时取先列出的动作）
+    fn best_action(
+        &mut self,
+        player_hand: &Hand,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+        stage: HandStage,
+    ) -> Option<(Action, GameOutcome)> {
+        let mut best: Option<(f64, Action, GameOutcome)> = None;
+        for action in self.legal_actions(player_hand, stage) {
+            let outcome = self.decision_outcome(action, player_hand, dealer_up_idx, counts, stage);
+            let ev = self.outcome_ev(&outcome);
+            if best.as_ref().is_none_or(|(best_ev, _, _)| ev > *best_ev) {
+                best = Some((ev, action, outcome));
+            }
+        }
+        best.map(|(_, action, outcome)| (action, outcome))
+    }
+
+    /// 决策点执行动作的结果
+    ///
+    /// 庄家黑杰克在初始决策之后才结算时，输掉的原始投注在初始决策点统一计入
+    fn decision_outcome(
+        &mut self,
+        action: Action,
+        player_hand: &Hand,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+        stage: HandStage,
+    ) -> GameOutcome {
+        let mut outcome = self.action_outcome(action, player_hand, dealer_up_idx, counts, stage);
+        if stage == HandStage::Initial {
+            outcome.dealer_blackjack_prob +=
+                self.pending_blackjack_loss(action, stage, dealer_up_idx, counts);
+        }
+        outcome
+    }
+
+    /// 庄家黑杰克在决策之后才结算、且只输原始投注的概率
+    ///
+    /// - 偷看 + 早投降：初始决策点除投降外的动作都输原始投注
+    /// - 无暗牌：加倍/分牌的追加投注已在递归中结算，只有后投降无效时输原始投注
+    /// - OBO：除早投降外的动作都输原始投注
+    fn pending_blackjack_loss(
+        &self,
+        action: Action,
+        stage: HandStage,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+    ) -> f64 {
+        if self.loses_original_bet(action, stage, dealer_up_idx) {
+            self.dealer_blackjack_prob(dealer_up_idx, counts)
+        } else {
+            0.0
+        }
+    }
+
+    /// 庄家黑杰克是否在决策之后才结算、且只输原始投注（见 `pending_blackjack_loss`）
+    pub(crate) fn loses_original_bet(
+        &self,
+        action: Action,
+        stage: HandStage,
+        dealer_up_idx: usize,
+    ) -> bool {
+        let early_surrender = self.early_surrender(dealer_up_idx);
+        match self.rules.hole_card {
+            HoleCardRule::Peek => {
+                stage == HandStage::Initial && early_surrender && action != Action::Surrender
+            }
+            HoleCardRule::NoHoleCard => action == Action::Surrender && !early_surrender,
+            HoleCardRule::NoHoleCardOriginalBetsOnly => {
+                action != Action::Surrender || !early_surrender
+            }
+        }
+    }
+
+    /// 对当前庄家明牌是否为早投降
+    pub(crate) fn early_surrender(&self, dealer_up_idx: usize) -> bool {
+        self.rules
+            .surrender
+            .is_early_against(point_index_to_card(dealer_up_idx))
+    }
+
+    /// 实际牌局中一手牌的策略动作（含规则回退，供逐手游戏的模拟使用）
+    ///
+    /// `hand_count` 为这一局当前的总手数（1 表示还没有分牌），
+    /// 分牌后的对子按规则和策略决定是否再分牌；
+    /// 设置了指数打法时按 `true_count` 偏离基础策略。
+    /// 策略收到的是玩家未见的剩余牌组 `unseen`，与 EV 计算相同合并十点牌
+    pub fn basic_play(
+        &self,
+        player_hand: &Hand,
+        dealer_up_card: Card,
+        hand_count: u8,
+        unseen: &CardCounts,
+        true_count: Option<f64>,
+    ) -> Action {
+        let dealer_up_idx = Self::merged_point_index(dealer_up_card);
+        let split = hand_count > 1;
+        let two_cards = player_hand.card_count() == 2;
+        let shoe = Self::merged_point_counts(unseen);
+
+        if split && two_cards && player_hand.can_split() {
+            let can_resplit = self.rules.allow_resplit
+                && player_hand.cards[0].card != Card::Ace
+                && hand_count < self.rules.max_split_hands;
+            if can_resplit && self.should_resplit(player_hand, dealer_up_idx, &shoe) {
+                return Action::Split;
+            }
+        }
+
+        let stage = match (two_cards, split) {
+            (true, false) => HandStage::Initial,
+            (true, true) => HandStage::Split,
+            (false, false) => HandStage::Drawn,
+            (false, true) => self.drawn_stage(HandStage::Split),
+        };
+        self.strategy_action(player_hand, dealer_up_idx, stage, &shoe, true_count)
+    }
+
+    /// 根据策略决定玩家动作（含规则回退）
+    ///
+    /// 设置了指数打法时，true count 由当前剩余牌组计算
+    /// （已发出的牌包括这一局的玩家手牌和庄家明牌）
+    pub(crate) fn basic_action(
+        &self,
+        player_hand: &Hand,
+        dealer_up_idx: usize,
+        stage: HandStage,
+        counts: &PointCounts,
+    ) -> Action {
+        let true_count = self
+            .deviations
+            .as_ref()
+            .map(|table| table.system.true_count_of_points(counts, self.rules.deck_count));
+        self.strategy_action(player_hand, dealer_up_idx, stage, counts, true_count)
+    }
+
+    /// 分牌后的对子是否再分牌（按策略，不使用指数打法）
+    pub(crate) fn should_resplit(
+        &self,
+        player_hand: &Hand,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+    ) -> bool {
+        let mut allowed = self.legal_actions(player_hand, HandStage::Split);
+        allowed.push(Action::Split);
+        let dealer_up_card = point_index_to_card(dealer_up_idx);
+        self.strategy
+            .decide_among(player_hand, dealer_up_card, counts, &self.rules, &allowed)
+            == Action::Split
+    }
+
+    /// 根据策略和指数打法决定玩家动作
+    ///
+    /// 策略在当前阶段的合法动作中选择；仍然返回不合法的动作时按停牌处理
+    fn strategy_action(
+        &self,
+        player_hand: &Hand,
+        dealer_up_idx: usize,
+        stage: HandStage,
+        counts: &PointCounts,
+        true_count: Option<f64>,
+    ) -> Action {
+        let dealer_up_card = point_index_to_card(dealer_up_idx);
+        let allowed = self.legal_actions(player_hand, stage);
+        let mut action =
+            self.strategy
+                .decide_among(player_hand, dealer_up_card, counts, &self.rules, &allowed);
+        if !allowed.contains(&action) {
+            action = Action::Stand;
+        }
+
+        match (&self.deviations, true_count) {
+            (Some(table), Some(true_count)) => table.apply(
+                action,
+                player_hand,
+                dealer_up_card,
+                true_count,
+                |deviation| self.deviation_allowed(deviation, player_hand, stage),
+                || {
+                    let without_surrender: Vec<Action> = allowed
+                        .iter()
+                        .copied()
+                        .filter(|action| !matches!(action, Action::Split | Action::Surrender))
+                        .collect();
+                    self.strategy.decide_among(
+                        player_hand,
+                        dealer_up_card,
+                        counts,
+                        &self.rules,
+                        &without_surrender,
+                    )
+                },
+            ),
+            _ => action,
+        }
+    }
+
+    /// 指数打法的动作在当前规则和手牌阶段下是否合法
+    fn deviation_allowed(&self, action: Action, player_hand: &Hand, stage: HandStage) -> bool {
+        match action {
+            Action::Hit | Action::Stand => true,
+            Action::Double => self.can_double(player_hand, stage),
+            Action::Split => {
+                stage == HandStage::Initial && self.rules.allow_split && player_hand.can_split()
+            }
+            Action::Surrender => stage == HandStage::Initial && self.rules.surrender.allowed(),
+        }
+    }
+
+    /// 当前阶段规则是否允许这手牌加倍
+    pub(crate) fn can_double(&self, player_hand: &Hand, stage: HandStage) -> bool {
+        let stage_allows = match stage {
+            HandStage::Initial => true,
+            HandStage::Split => self.rules.allow_double_after_split,
+            HandStage::Drawn => self.rules.double == DoubleRule::AnyNumberOfCards,
+            HandStage::SplitDrawn => false,
+        };
+        stage_allows && self.rules.double.allows(player_hand)
+    }
+
+    /// 要牌之后的手牌阶段
+    ///
+    /// 任意张数加倍规则下，不允许分牌后加倍的分牌手牌要牌后仍不能加倍
+    pub(crate) fn drawn_stage(&self, stage: HandStage) -> HandStage {
+        let split_without_double = matches!(stage, HandStage::Split | HandStage::SplitDrawn)
+            && !self.rules.allow_double_after_split
+            && self.rules.double == DoubleRule::AnyNumberOfCards;
+        if split_without_double {
+            HandStage::SplitDrawn
+        } else {
+            HandStage::Drawn
+        }
+    }
+
+    /// 当前阶段的合法动作
+    pub(crate) fn legal_actions(&self, player_hand: &Hand, stage: HandStage) -> Vec<Action> {
+        let mut actions = vec![Action::Stand, Action::Hit];
+
+        if self.can_double(player_hand, stage) {
+            actions.push(Action::Double);
+        }
+
+        if stage == HandStage::Initial {
+            if self.rules.allow_split && player_hand.can_split() {
+                actions.push(Action::Split);
+            }
+            if self.rules.surrender.allowed() {
+                actions.push(Action::Surrender);
+            }
+        }
+
+        actions
+    }
+
+    /// 执行指定动作的结果
+    fn action_outcome(
+        &mut self,
+        action: Action,
+        player_hand: &Hand,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+        stage: HandStage,
+    ) -> GameOutcome {
+        match action {
+            Action::Stand => {
+                self.player_stand_outcome(player_hand.value(), dealer_up_idx, counts)
+            }
+            Action::Hit => {
+                self.player_hit_outcome(player_hand, dealer_up_idx, counts, stage)
+            }
+            Action::Double => {
+                // 加倍：投注翻倍，所以结果需要标记为加倍
+                let double_outcome = self.player_double_outcome(player_hand, dealer_up_idx, counts);
+                Self::into_double_outcome(double_outcome)
+            }
+            Action::Split => {
+                self.player_split_outcome(player_hand, dealer_up_idx, counts)
+            }
+            Action::Surrender => {
+                // 投降：损失0.5倍投注
+                // 早投降不受庄家黑杰克影响；后投降只在庄家没有黑杰克时成立
+                let surrender_prob = if self.early_surrender(dealer_up_idx) {
+                    1.0
+                } else {
+                    self.no_dealer_blackjack_prob(dealer_up_idx, counts)
+                };
+                GameOutcome {
+                    surrender_prob,
+                    ..GameOutcome::zero()
                 }
             }
         }
     }
 
-    /// 从两张牌的值创建手牌对象
-    fn create_hand_from_values(&self, card1: u8, card2: u8) -> Hand {
-        let mut hand = Hand::new();
-        hand.add_card(PlayingCard {
-            card: if card1 == 1 {
-                Card::Ace
-            } else if card1 == 10 {
-                Card::Face
-            } else {
-                Card::Number(card1)
-            },
-            suit: Suit::Spades,
-        });
-        hand.add_card(PlayingCard {
-            card: if card2 == 1 {
-                Card::Ace
-            } else if card2 == 10 {
-                Card::Face
-            } else {
-                Card::Number(card2)
-            },
-            suit: Suit::Spades,
-        });
-        hand
+    /// 将普通投注的概率转移到加倍投注
+    fn into_double_outcome(mut outcome: GameOutcome) -> GameOutcome {
+        outcome.player_win_prob_double = outcome.player_win_prob;
+        outcome.dealer_win_prob_double = outcome.dealer_win_prob;
+        outcome.push_prob_double = outcome.push_prob;
+        outcome.player_blackjack_prob_double = outcome.player_blackjack_prob;
+        outcome.dealer_blackjack_prob_double = outcome.dealer_blackjack_prob;
+        outcome.player_charlie_prob_double = outcome.player_charlie_prob;
+        // 清空普通投注的概率（因为已经加倍）
+        outcome.player_win_prob = 0.0;
+        outcome.dealer_win_prob = 0.0;
+        outcome.push_prob = 0.0;
+        outcome.player_blackjack_prob = 0.0;
+        outcome.dealer_blackjack_prob = 0.0;
+        outcome.player_charlie_prob = 0.0;
+        outcome
     }
 
-
-    /// 递归计算游戏结果（返回条件概率）
-    fn calculate_game_outcome(
+    /// 玩家分牌后的结果
+    ///
+    /// 每手分牌都以共享的剩余牌组独立计算（忽略两手之间的牌张消耗），
+    /// 分牌局的收益为两手分牌收益之和，记录在 `split_ev` 中；
+    /// 净收益平方的期望按庄家最终结果计入两手之间的相关性（见 `SplitPayoffs::paired`）
+    fn player_split_outcome(
         &mut self,
         player_hand: &Hand,
-        dealer_hand: &Hand,
-        dealer_up_card: Card,
+        dealer_up_idx: usize,
         counts: &PointCounts,
     ) -> GameOutcome {
-        // 检查玩家黑杰克
-        if player_hand.is_blackjack() {
-            if dealer_hand.is_blackjack() {
-                return GameOutcome {
-                    push_prob: 1.0,
-                    ..GameOutcome::zero()
-                };
-            } else {
-                return GameOutcome {
-                    player_blackjack_prob: 1.0,
-                    ..GameOutcome::zero()
-                };
-            }
+        let hands: SplitHandMoments = self.split_payoffs(player_hand, dealer_up_idx, counts);
+
+        GameOutcome {
+            split_prob: self.play_path_prob(dealer_up_idx, counts),
+            split_ev: hands.ev,
+            split_second_moment: hands.second_moment,
+            ..GameOutcome::zero()
         }
-        
-        // 检查庄家黑杰克
-        if dealer_hand.is_blackjack() {
-            return GameOutcome {
-                dealer_blackjack_prob: 1.0,
-                ..GameOutcome::zero()
-            };
+    }
+
+    /// 分牌局（两个分牌位置之和）的收益
+    fn split_payoffs<P: SplitPayoffs>(
+        &mut self,
+        player_hand: &Hand,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+    ) -> P {
+        let pair_card = player_hand.cards[0].card;
+        self.split_positions_ev::<P>(pair_card, dealer_up_idx, counts, self.rules.max_split_hands)
+    }
+
+    /// 把一个分牌位置分成两手的收益
+    ///
+    /// 各位置独立计算，手数上限 `max_hands` 由两手平分（奇数时左手多一手），
+    /// 保证整局的总手数不超过 `max_split_hands`
+    fn split_positions_ev<P: SplitPayoffs>(
+        &mut self,
+        pair_card: Card,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+        max_hands: u8,
+    ) -> P {
+        let dealer = self.dealer_up_card_outcome(dealer_up_idx, counts);
+        let left = self.split_hand_ev::<P>(pair_card, dealer_up_idx, counts, max_hands.div_ceil(2));
+        if max_hands.is_multiple_of(2) || !self.rules.allow_resplit {
+            return left.paired(&left, &dealer);
         }
-        
-        // 检查玩家是否爆牌
-        if player_hand.is_busted() {
-            return GameOutcome {
-                dealer_win_prob: 1.0,
-                ..GameOutcome::zero()
-            };
+        let right = self.split_hand_ev::<P>(pair_card, dealer_up_idx, counts, max_hands / 2);
+        left.paired(&right, &dealer)
+    }
+
+    /// 单个分牌位置的收益（以单手原始投注为单位）
+    ///
+    /// `max_hands` 为这一位置最多可以再分成的手数，大于 1 时才能再分牌
+    fn split_hand_ev<P: SplitPayoffs>(
+        &mut self,
+        pair_card: Card,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+        max_hands: u8,
+    ) -> P {
+        let total_cards: u32 = counts.iter().sum();
+
+        if total_cards == 0 {
+            return P::default();
         }
-        
-        // 根据基础策略决定玩家动作
-        // 注意：上桌EV计算不考虑分牌，即使规则允许
-        let mut action = self.strategy.get_action(player_hand, dealer_up_card);
-        
-        // 强制忽略分牌（上桌EV计算不需要考虑分牌）
-        if action == crate::types::Action::Split {
-            action = self.strategy.get_action_without_split_surrender(player_hand, dealer_up_card);
-        }
-        
-        // 如果策略是投降，但规则不允许，则回退到其他策略
-        if action == crate::types::Action::Surrender && (!self.rules.allow_surrender || !player_hand.can_double()) {
-            // 不允许投降，查询其他策略（Hit/Stand/Double）
-            action = self.strategy.get_action_without_split_surrender(player_hand, dealer_up_card);
-        }
-        
-        match action {
-            crate::types::Action::Stand => {
-                self.dealer_play_outcome(dealer_hand, player_hand, counts)
-            }
-            crate::types::Action::Hit => {
-                self.player_hit_outcome(player_hand, dealer_hand, dealer_up_card, counts)
+
+        let can_resplit = self.rules.allow_resplit && pair_card != Card::Ace && max_hands > 1;
+
+        let mut payoffs = P::default();
+        for point_idx in 0..=10 {
+            if counts[point_idx] == 0 {
+                continue;
             }
-            crate::types::Action::Double => {
-                // 加倍：投注翻倍，所以结果需要标记为加倍
-                let mut double_outcome = self.player_double_outcome(player_hand, dealer_hand, counts);
-                // 将普通投注的概率转移到加倍投注
-                double_outcome.player_win_prob_double = double_outcome.player_win_prob;
-                double_outcome.dealer_win_prob_double = double_outcome.dealer_win_prob;
-                double_outcome.push_prob_double = double_outcome.push_prob;
-                double_outcome.player_blackjack_prob_double = double_outcome.player_blackjack_prob;
-                double_outcome.dealer_blackjack_prob_double = double_outcome.dealer_blackjack_prob;
-                // 清空普通投注的概率（因为已经加倍）
-                double_outcome.player_win_prob = 0.0;
-                double_outcome.dealer_win_prob = 0.0;
-                double_outcome.push_prob = 0.0;
-                double_outcome.player_blackjack_prob = 0.0;
-                double_outcome.dealer_blackjack_prob = 0.0;
-                double_outcome
-            }
-            crate::types::Action::Split => {
-                // 分牌：上桌EV计算不考虑分牌，这里不应该到达
-                // 如果到达这里，说明逻辑有误，回退到普通策略
-                self.dealer_play_outcome(dealer_hand, player_hand, counts)
-            }
-            crate::types::Action::Surrender => {
-                // 投降：损失0.5倍投注
-                GameOutcome {
-                    surrender_prob: 1.0,
-                    ..GameOutcome::zero()
+
+            let prob = counts[point_idx] as f64 / total_cards as f64;
+
+            let mut split_hand = Hand::new();
+            split_hand.add_card(PlayingCard {
+                card: pair_card,
+                suit: Suit::Spades,
+            });
+            split_hand.add_card(PlayingCard {
+                card: point_index_to_card(point_idx),
+                suit: Suit::Spades,
+            });
+
+            let mut new_counts = *counts;
+            new_counts[point_idx] -= 1;
+
+            let hand = if pair_card == Card::Ace {
+                // 分A后每手只发一张牌，直接停牌（A+10 只算21点，不算黑杰克）
+                self.stand_payoffs::<P>(split_hand.value(), dealer_up_idx, &new_counts)
+            } else if can_resplit && split_hand.can_split() {
+                // 再分牌：这一手变成两手
+                let resplit =
+                    self.split_positions_ev::<P>(pair_card, dealer_up_idx, &new_counts, max_hands);
+                match self.mode {
+                    StrategyMode::Basic => {
+                        if self.should_resplit(&split_hand, dealer_up_idx, &new_counts) {
+                            resplit
+                        } else {
+                            self.split_hand_play_ev(&split_hand, dealer_up_idx, &new_counts)
+                        }
+                    }
+                    StrategyMode::Optimal => {
+                        let play: P = self.split_hand_play_ev(&split_hand, dealer_up_idx, &new_counts);
+                        if resplit.ev() > play.ev() { resplit } else { play }
+                    }
                 }
+            } else {
+                self.split_hand_play_ev(&split_hand, dealer_up_idx, &new_counts)
+            };
+
+            payoffs.add(&hand, prob);
+        }
+
+        payoffs
+    }
+
+    /// 分牌后不再分牌时，按分牌手牌规则行动的收益
+    fn split_hand_play_ev<P: SplitPayoffs>(
+        &mut self,
+        split_hand: &Hand,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+    ) -> P {
+        self.hand_payoffs(split_hand, dealer_up_idx, counts, HandStage::Split)
+    }
+
+    /// 按当前策略模式行动的一手牌的收益（与 `play_hand` 的决策相同）
+    fn hand_payoffs<P: SplitPayoffs>(
+        &mut self,
+        player_hand: &Hand,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+        stage: HandStage,
+    ) -> P {
+        let action = match self.mode {
+            StrategyMode::Basic => self.basic_action(player_hand, dealer_up_idx, stage, counts),
+            StrategyMode::Optimal => self
+                .best_action(player_hand, dealer_up_idx, counts, stage)
+                .map_or(Action::Stand, |(action, _)| action),
+        };
+        // 分牌手牌只会要牌、停牌或加倍
+        let (bet, drawn_stage) = match action {
+            Action::Hit => (1.0, Some(self.drawn_stage(stage))),
+            Action::Double => (2.0, None),
+            _ => return self.stand_payoffs(player_hand.value(), dealer_up_idx, counts),
+        };
+
+        let mut payoffs = P::default();
+        let total_cards: u32 = counts.iter().sum();
+        if total_cards == 0 {
+            return payoffs;
+        }
+        for point_idx in 0..=10 {
+            if counts[point_idx] == 0 {
+                continue;
             }
+            let prob = counts[point_idx] as f64 / total_cards as f64;
+
+            let mut new_player_hand = player_hand.clone();
+            new_player_hand.add_card(PlayingCard {
+                card: point_index_to_card(point_idx),
+                suit: Suit::Spades,
+            });
+            let mut new_counts = *counts;
+            new_counts[point_idx] -= 1;
+
+            let sub: P = if new_player_hand.is_busted() {
+                let dealer = self.dealer_up_card_outcome(dealer_up_idx, &new_counts);
+                P::settled(&dealer, |_| -1.0)
+            } else if self.rules.is_charlie(&new_player_hand) {
+                self.charlie_payoffs(dealer_up_idx, &new_counts)
+            } else if let Some(drawn_stage) = drawn_stage {
+                self.drawn_hand_payoffs(&new_player_hand, dealer_up_idx, &new_counts, drawn_stage)
+            } else {
+                self.stand_payoffs(new_player_hand.value(), dealer_up_idx, &new_counts)
+            };
+            payoffs.add(&sub.scaled(bet), prob);
+        }
+        payoffs
+    }
+
+    /// 要牌后继续行动的收益（带缓存，与 `play_drawn_hand` 相同）
+    fn drawn_hand_payoffs<P: SplitPayoffs>(
+        &mut self,
+        player_hand: &Hand,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+        stage: HandStage,
+    ) -> P {
+        let memo_key = (
+            player_hand.value(),
+            player_hand.is_soft(),
+            self.memo_card_count(player_hand),
+            stage,
+            dealer_up_idx,
+            Self::deck_key(counts),
+        );
+        if let Some(cached) = P::memo(self).get(&memo_key) {
+            return cached.clone();
         }
+
+        let payoffs: P = self.hand_payoffs(player_hand, dealer_up_idx, counts, stage);
+        P::memo(self).insert(memo_key, payoffs.clone());
+        payoffs
+    }
+
+    /// 停牌的收益（与 `player_stand_outcome` 相同）
+    fn stand_payoffs<P: SplitPayoffs>(
+        &mut self,
+        player_value: u8,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+    ) -> P {
+        let dealer = self.dealer_up_card_outcome(dealer_up_idx, counts);
+        P::settled(&dealer, |i| match i {
+            DEALER_BUST => 1.0,
+            DEALER_BLACKJACK => -1.0,
+            _ => match player_value.cmp(&(17 + i as u8)) {
+                std::cmp::Ordering::Greater => 1.0,
+                std::cmp::Ordering::Less => -1.0,
+                std::cmp::Ordering::Equal => 0.0,
+            },
+        })
+    }
+
+    /// 达到 Charlie 的收益（庄家黑杰克仍然优先）
+    fn charlie_payoffs<P: SplitPayoffs>(&mut self, dealer_up_idx: usize, counts: &PointCounts) -> P {
+        let charlie_payout = self.rules.charlie.map_or(1.0, |charlie| charlie.payout);
+        let dealer = self.dealer_up_card_outcome(dealer_up_idx, counts);
+        P::settled(&dealer, |i| if i == DEALER_BLACKJACK { -1.0 } else { charlie_payout })
+    }
+
+    /// 只有明牌时庄家最终结果的分布（庄家黑杰克单独结算时不含黑杰克）
+    fn dealer_up_card_outcome(&mut self, dealer_up_idx: usize, counts: &PointCounts) -> DealerProbs {
+        let (up_value, up_soft) = Self::add_card_to_total(0, false, dealer_up_idx);
+        self.dealer_play_outcome(up_value, up_soft, true, counts)
+    }
+
+    /// 将结果概率换算为期望收益
+    fn outcome_ev(&self, outcome: &GameOutcome) -> f64 {
+        calculate_ev(outcome, &self.rules).ev
     }
 
     /// 玩家要牌后的结果
     fn player_hit_outcome(
         &mut self,
         player_hand: &Hand,
-        dealer_hand: &Hand,
-        dealer_up_card: Card,
+        dealer_up_idx: usize,
         counts: &PointCounts,
+        stage: HandStage,
     ) -> GameOutcome {
+        let drawn_stage = self.drawn_stage(stage);
         let mut outcome = GameOutcome::zero();
         let total_cards: u32 = counts.iter().sum();
-        
+
         if total_cards == 0 {
             return outcome;
         }
-        
+
         for point_idx in 0..=10 {
             if counts[point_idx] == 0 {
                 continue;
             }
-            
+
             let prob = counts[point_idx] as f64 / total_cards as f64;
-            
-            let card = if point_idx == 0 {
-                Card::Ace
-            } else if point_idx == 10 {
-                Card::Face
-            } else {
-                Card::Number(point_idx as u8)
-            };
-            
+
             let mut new_player_hand = player_hand.clone();
             new_player_hand.add_card(PlayingCard {
-                card,
+                card: point_index_to_card(point_idx),
                 suit: Suit::Spades,
             });
-            
+
+            let mut new_counts = *counts;
+            new_counts[point_idx] -= 1;
+
             // 检查玩家是否爆牌
             if new_player_hand.is_busted() {
-                outcome.add(
-                    &GameOutcome {
-                        dealer_win_prob: 1.0,
-                        ..GameOutcome::zero()
-                    },
-                    prob,
-                );
+                outcome.add(&self.player_bust_outcome(dealer_up_idx, &new_counts), prob);
                 continue;
             }
-            
-            let mut new_counts = *counts;
-            new_counts[point_idx] -= 1;
-            
+
+            // 达到 Charlie 自动获胜
+            if self.rules.is_charlie(&new_player_hand) {
+                outcome.add(&self.player_charlie_outcome(dealer_up_idx, &new_counts), prob);
+                continue;
+            }
+
             // 递归计算
-            let sub_outcome = self.calculate_game_outcome(
-                &new_player_hand,
-                dealer_hand,
-                dealer_up_card,
-                &new_counts,
-            );
-            
+            let sub_outcome =
+                self.play_drawn_hand(&new_player_hand, dealer_up_idx, &new_counts, drawn_stage);
+
             outcome.add(&sub_outcome, prob);
         }
-        
+
+        outcome
+    }
+
+    /// 要牌后继续行动的结果（带缓存）
+    fn play_drawn_hand(
+        &mut self,
+        player_hand: &Hand,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+        stage: HandStage,
+    ) -> GameOutcome {
+        let card_count = self.memo_card_count(player_hand);
+        let memo_key = (
+            player_hand.value(),
+            player_hand.is_soft(),
+            card_count,
+            stage,
+            dealer_up_idx,
+            Self::deck_key(counts),
+        );
+
+        if let Some(cached) = self.player_memo.get(&memo_key) {
+            return cached.clone();
+        }
+
+        let outcome = self.play_hand(player_hand, dealer_up_idx, counts, stage);
+        self.player_memo.insert(memo_key, outcome.clone());
         outcome
     }
 
+    /// 玩家要牌后结果缓存键中的牌数
+    ///
+    /// 最优策略模式下决策与牌数无关，记为 0 以共享结果；Charlie 规则下结果取决于牌数
+    pub(crate) fn memo_card_count(&self, player_hand: &Hand) -> usize {
+        match self.mode {
+            StrategyMode::Optimal if self.rules.charlie.is_none() => 0,
+            _ => player_hand.card_count(),
+        }
+    }
+
     /// 玩家加倍后的结果
     fn player_double_outcome(
         &mut self,
         player_hand: &Hand,
-        dealer_hand: &Hand,
+        dealer_up_idx: usize,
         counts: &PointCounts,
     ) -> GameOutcome {
         let mut outcome = GameOutcome::zero();
         let total_cards: u32 = counts.iter().sum();
-        
+
         if total_cards == 0 {
             return outcome;
         }
-        
+
         for point_idx in 0..=10 {
             if counts[point_idx] == 0 {
                 continue;
             }
-            
+
             let prob = counts[point_idx] as f64 / total_cards as f64;
-            
-            let card = if point_idx == 0 {
-                Card::Ace
-            } else if point_idx == 10 {
-                Card::Face
-            } else {
-                Card::Number(point_idx as u8)
-            };
-            
+
             let mut new_player_hand = player_hand.clone();
             new_player_hand.add_card(PlayingCard {
-                card,
+                card: point_index_to_card(point_idx),
                 suit: Suit::Spades,
             });
-            
+
+            let mut new_counts = *counts;
+            new_counts[point_idx] -= 1;
+
             // 检查玩家是否爆牌
             if new_player_hand.is_busted() {
-                outcome.add(
-                    &GameOutcome {
-                        dealer_win_prob: 1.0,
-                        ..GameOutcome::zero()
-                    },
-                    prob,
-                );
+                outcome.add(&self.player_bust_outcome(dealer_up_idx, &new_counts), prob);
                 continue;
             }
-            
-            let mut new_counts = *counts;
-            new_counts[point_idx] -= 1;
-            
+
+            // 达到 Charlie 自动获胜
+            if self.rules.is_charlie(&new_player_hand) {
+                outcome.add(&self.player_charlie_outcome(dealer_up_idx, &new_counts), prob);
+                continue;
+            }
+
             // 玩家停牌，庄家回合
-            let dealer_outcome = self.dealer_play_outcome(
-                dealer_hand,
-                &new_player_hand,
+            let dealer_outcome = self.player_stand_outcome(
+                new_player_hand.value(),
+                dealer_up_idx,
                 &new_counts,
             );
-            
+
             outcome.add(&dealer_outcome, prob);
         }
-        
+
+        outcome
+    }
+
+    /// 玩家爆牌的结果（庄家黑杰克单独结算时只计入庄家没有黑杰克的概率）
+    fn player_bust_outcome(&self, dealer_up_idx: usize, counts: &PointCounts) -> GameOutcome {
+        GameOutcome {
+            dealer_win_prob: self.play_path_prob(dealer_up_idx, counts),
+            ..GameOutcome::zero()
+        }
+    }
+
+    /// 玩家达到 Charlie 的结果（庄家黑杰克仍然优先）
+    fn player_charlie_outcome(&self, dealer_up_idx: usize, counts: &PointCounts) -> GameOutcome {
+        let path_prob = self.play_path_prob(dealer_up_idx, counts);
+        let charlie_prob = self.no_dealer_blackjack_prob(dealer_up_idx, counts);
+        GameOutcome {
+            player_charlie_prob: charlie_prob,
+            dealer_blackjack_prob: path_prob - charlie_prob,
+            ..GameOutcome::zero()
+        }
+    }
+
+    /// 玩家停牌后与庄家比较的结果
+    fn player_stand_outcome(
+        &mut self,
+        player_value: u8,
+        dealer_up_idx: usize,
+        counts: &PointCounts,
+    ) -> GameOutcome {
+        let dealer = self.dealer_up_card_outcome(dealer_up_idx, counts);
+
+        let mut outcome = GameOutcome {
+            player_win_prob: dealer[DEALER_BUST],
+            dealer_blackjack_prob: dealer[DEALER_BLACKJACK],
+            ..GameOutcome::zero()
+        };
+        for (i, &prob) in dealer[..DEALER_BLACKJACK].iter().enumerate() {
+            let dealer_value = 17 + i as u8;
+            if player_value > dealer_value {
+                outcome.player_win_prob += prob;
+            } else if player_value < dealer_value {
+                outcome.dealer_win_prob += prob;
+            } else {
+                outcome.push_prob += prob;
+            }
+        }
         outcome
     }
 
-    /// 庄家回合的结果
+    /// 庄家回合的结果（庄家最终点数分布）
+    ///
+    /// `up_card_only` 为 true 时庄家只有明牌，下一张为暗牌：
+    /// 庄家黑杰克单独结算时（偷看或 OBO）组成黑杰克的暗牌路径不计入，
+    /// 无暗牌且输掉全部投注时计入黑杰克
     fn dealer_play_outcome(
         &mut self,
-        dealer_hand: &Hand,
-        player_hand: &Hand,
+        dealer_value: u8,
+        dealer_is_soft: bool,
+        up_card_only: bool,
         counts: &PointCounts,
-    ) -> GameOutcome {
+    ) -> DealerProbs {
         // 检查记忆化
-        let player_value = player_hand.value();
-        let player_is_soft = player_hand.is_soft();
-        let player_card_count = player_hand.card_count();
-        let dealer_value = dealer_hand.value();
-        let deck_sig = self.calculate_deck_signature(counts);
-        
-        let memo_key = (
-            player_value,
-            player_is_soft,
-            player_card_count,
-            dealer_value,
-            deck_sig,
-        );
-        
+        let memo_key = (dealer_value, dealer_is_soft, up_card_only, Self::deck_key(counts));
+
         if let Some(cached) = self.memo.get(&memo_key) {
-            return cached.clone();
+            return *cached;
         }
-        
-        // 判断庄家是否需要要牌
-        if !DealerRules::should_hit(dealer_hand, self.rules.dealer_stands_on_soft_17) {
-            let result = self.compare_hands(player_hand, dealer_hand);
-            self.memo.insert(memo_key, result.clone());
-            return result;
+
+        let mut outcome: DealerProbs = [0.0; 7];
+
+        // 判断庄家是否需要要牌（只有明牌时必须补暗牌）
+        if !up_card_only
+            && !DealerRules::should_hit_value(dealer_value, dealer_is_soft, self.rules.dealer_stands_on_soft_17)
+        {
+            outcome[(dealer_value - 17) as usize] = 1.0;
+            self.memo.insert(memo_key, outcome);
+            return outcome;
         }
-        
+
         // 庄家要牌
-        let mut outcome = GameOutcome::zero();
         let total_cards: u32 = counts.iter().sum();
-        
+
         if total_cards == 0 {
             return outcome;
         }
-        
+
         for point_idx in 0..=10 {
             if counts[point_idx] == 0 {
                 continue;
             }
-            
+
             let prob = counts[point_idx] as f64 / total_cards as f64;
-            
-            let card = if point_idx == 0 {
-                Card::Ace
-            } else if point_idx == 10 {
-                Card::Face
-            } else {
-                Card::Number(point_idx as u8)
-            };
-            
-            let mut new_dealer_hand = dealer_hand.clone();
-            new_dealer_hand.add_card(PlayingCard {
-                card,
-                suit: Suit::Spades,
-            });
-            
+            let (new_value, new_is_soft) =
+                Self::add_card_to_total(dealer_value, dealer_is_soft, point_idx);
+
+            // 庄家黑杰克：单独结算时不计入
+            if up_card_only && new_value == 21 {
+                if self.rules.hole_card == HoleCardRule::NoHoleCard {
+                    outcome[DEALER_BLACKJACK] += prob;
+                }
+                continue;
+            }
+
             // 检查庄家是否爆牌
-            if new_dealer_hand.is_busted() {
-                outcome.add(
-                    &GameOutcome {
-                        player_win_prob: 1.0,
-                        ..GameOutcome::zero()
-                    },
-                    prob,
-                );
+            if new_value > 21 {
+                outcome[DEALER_BUST] += prob;
                 continue;
             }
-            
+
             let mut new_counts = *counts;
             new_counts[point_idx] -= 1;
-            
+
             let sub_outcome = self.dealer_play_outcome(
-                &new_dealer_hand,
-                player_hand,
+                new_value,
+                new_is_soft,
+                false,
                 &new_counts,
             );
-            
-            outcome.add(&sub_outcome, prob);
+
+            for (total, sub) in outcome.iter_mut().zip(sub_outcome.iter()) {
+                *total += sub * prob;
+            }
         }
-        
-        self.memo.insert(memo_key, outcome.clone());
+
+        self.memo.insert(memo_key, outcome);
         outcome
     }
 
-    /// 比较玩家和庄家手牌，返回结果
-    fn compare_hands(&self, player_hand: &Hand, dealer_hand: &Hand) -> GameOutcome {
-        let player_value = player_hand.value();
-        let dealer_value = dealer_hand.value();
-        
-        if player_value > dealer_value {
-            GameOutcome {
-                player_win_prob: 1.0,
-                ..GameOutcome::zero()
-            }
-        } else if player_value < dealer_value {
-            GameOutcome {
-                dealer_win_prob: 1.0,
-                ..GameOutcome::zero()
-            }
+    /// 在点数和软硬状态上加一张牌，返回新的 (点数, 是否软点数)
+    pub(crate) fn add_card_to_total(value: u8, is_soft: bool, point_idx: usize) -> (u8, bool) {
+        let (mut value, mut is_soft) = if point_idx == 0 && !is_soft && value + 11 <= 21 {
+            // A 当作 11 点
+            (value + 11, true)
         } else {
-            GameOutcome {
-                push_prob: 1.0,
-                ..GameOutcome::zero()
-            }
+            (value + point_index_to_value(point_idx), is_soft)
+        };
+
+        if value > 21 && is_soft {
+            value -= 10;
+            is_soft = false;
+        }
+
+        (value, is_soft)
+    }
+
+    /// 庄家暗牌组成黑杰克的概率
+    fn dealer_blackjack_prob(&self, dealer_up_idx: usize, counts: &PointCounts) -> f64 {
+        let total_cards: u32 = counts.iter().sum();
+
+        if total_cards == 0 {
+            return 0.0;
         }
+
+        let blackjack_cards = match dealer_up_idx {
+            0 => counts[9] + counts[10],
+            9 | 10 => counts[0],
+            _ => 0,
+        };
+        blackjack_cards as f64 / total_cards as f64
     }
 
-    /// 计算牌组签名（用于记忆化）
-    fn calculate_deck_signature(&self, counts: &PointCounts) -> u64 {
-        let mut hash: u64 = 0;
-        for &count in counts.iter() {
-            hash = hash.wrapping_mul(31).wrapping_add(count as u64);
+    /// 庄家暗牌不组成黑杰克的概率
+    fn no_dealer_blackjack_prob(&self, dealer_up_idx: usize, counts: &PointCounts) -> f64 {
+        1.0 - self.dealer_blackjack_prob(dealer_up_idx, counts)
+    }
+
+    /// 玩家行动路径的概率质量
+    ///
+    /// 庄家黑杰克单独结算时（偷看或 OBO）只包含庄家没有黑杰克的路径，
+    /// 无暗牌且输掉全部投注时庄家黑杰克也在行动路径中
+    fn play_path_prob(&self, dealer_up_idx: usize, counts: &PointCounts) -> f64 {
+        if self.rules.hole_card == HoleCardRule::NoHoleCard {
+            1.0
+        } else {
+            self.no_dealer_blackjack_prob(dealer_up_idx, counts)
         }
-        hash
     }
 }
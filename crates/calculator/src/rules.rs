@@ -1,6 +1,7 @@
 //! Blackjack 游戏规则定义
 
-use crate::types::Hand;
+use crate::error::{CalculatorError, Result};
+use crate::types::{Card, CardCounts, Hand};
 use serde::{Deserialize, Serialize};
 
 /// 游戏规则配置
@@ -8,30 +9,240 @@ use serde::{Deserialize, Serialize};
 pub struct GameRules {
     /// 牌组数量
     pub deck_count: u8,
-    /// 是否允许投降
-    pub allow_surrender: bool,
+    /// 投降规则（不允许 / 后投降 / 早投降）
+    ///
+    /// 兼容旧版的 `allow_surrender` 布尔字段：`true` 为后投降，`false` 为不允许投降
+    #[serde(default, alias = "allow_surrender", deserialize_with = "deserialize_surrender")]
+    pub surrender: SurrenderRule,
     /// 是否允许分牌
     pub allow_split: bool,
     /// 庄家是否在软17停牌
     pub dealer_stands_on_soft_17: bool,
     /// 分牌后是否可以再次分牌
     pub allow_resplit: bool,
+    /// 分牌后最多允许的手牌数（含再分牌，通常为 4）
+    /// 注意：分A后每手只发一张牌，且不能再分
+    ///
+    /// 旧版规则没有这个字段，缺少时取 4
+    #[serde(default = "default_max_split_hands")]
+    pub max_split_hands: u8,
     /// 分牌后是否可以加倍
     pub allow_double_after_split: bool,
     /// 黑杰克赔率 (通常是 3:2 或 6:5)
     pub blackjack_payout: f64,
+    /// 庄家暗牌规则（美式偷看 / 欧式无暗牌）
+    #[serde(default)]
+    pub hole_card: HoleCardRule,
+    /// 加倍限制（任意两张 / 只限 9-11 / 只限 10-11 / 任意张数）
+    #[serde(default)]
+    pub double: DoubleRule,
+    /// Charlie 规则：达到指定张数且未爆牌的手牌自动获胜（None 表示不使用）
+    #[serde(default)]
+    pub charlie: Option<CharlieRule>,
+}
+
+/// 投降规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SurrenderRule {
+    /// 不允许投降
+    None,
+    /// 后投降：庄家确认没有黑杰克后才结算，庄家黑杰克时投降无效、输掉原始投注
+    #[default]
+    Late,
+    /// 早投降：对明牌10点和A都在庄家检查黑杰克之前投降，只输一半投注
+    Early,
+    /// 只对明牌10点早投降，对A后投降
+    EarlyVsTen,
+    /// 只对明牌A早投降，对10点后投降
+    EarlyVsAce,
+}
+
+fn default_max_split_hands() -> u8 {
+    4
+}
+
+/// 投降规则字段的两种写法：规则名称或旧版布尔值
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SurrenderField {
+    Legacy(bool),
+    Rule(SurrenderRule),
+}
+
+fn deserialize_surrender<'de, D>(deserializer: D) -> std::result::Result<SurrenderRule, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(match SurrenderField::deserialize(deserializer)? {
+        SurrenderField::Legacy(true) => SurrenderRule::Late,
+        SurrenderField::Legacy(false) => SurrenderRule::None,
+        SurrenderField::Rule(rule) => rule,
+    })
+}
+
+impl SurrenderRule {
+    /// 是否允许投降
+    pub fn allowed(&self) -> bool {
+        *self != SurrenderRule::None
+    }
+
+    /// 对指定庄家明牌是否为早投降
+    pub fn is_early_against(&self, dealer_up_card: Card) -> bool {
+        match self {
+            SurrenderRule::Early => {
+                matches!(dealer_up_card, Card::Ace | Card::Face | Card::Number(10))
+            }
+            SurrenderRule::EarlyVsTen => matches!(dealer_up_card, Card::Face | Card::Number(10)),
+            SurrenderRule::EarlyVsAce => dealer_up_card == Card::Ace,
+            SurrenderRule::None | SurrenderRule::Late => false,
+        }
+    }
+}
+
+/// 庄家暗牌规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum HoleCardRule {
+    /// 美式：庄家明牌为A或10点时先偷看暗牌，黑杰克在玩家行动前结算，玩家只输原始投注
+    #[default]
+    Peek,
+    /// 欧式无暗牌（ENHC）：庄家在玩家行动后才补第二张牌，
+    /// 庄家黑杰克收走全部投注（含加倍和分牌的追加投注）
+    NoHoleCard,
+    /// 欧式无暗牌，但庄家黑杰克只收原始投注（OBO），加倍和分牌的追加投注退还
+    NoHoleCardOriginalBetsOnly,
+}
+
+/// 加倍规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DoubleRule {
+    /// 任意两张牌都可以加倍
+    #[default]
+    AnyTwoCards,
+    /// 只有硬 9-11 的两张牌可以加倍（软牌不能加倍）
+    NineToEleven,
+    /// 只有硬 10-11 的两张牌可以加倍
+    TenToEleven,
+    /// 任意张数、任意点数都可以加倍（要牌后仍可加倍）
+    AnyNumberOfCards,
+}
+
+impl DoubleRule {
+    /// 手牌按点数和牌数是否允许加倍（分牌后加倍由 `allow_double_after_split` 另行决定）
+    pub fn allows(&self, hand: &Hand) -> bool {
+        let two_cards = hand.card_count() == 2;
+        match self {
+            DoubleRule::AnyTwoCards => two_cards,
+            DoubleRule::NineToEleven => {
+                two_cards && !hand.is_soft() && (9..=11).contains(&hand.value())
+            }
+            DoubleRule::TenToEleven => {
+                two_cards && !hand.is_soft() && (10..=11).contains(&hand.value())
+            }
+            DoubleRule::AnyNumberOfCards => hand.card_count() >= 2 && !hand.is_busted(),
+        }
+    }
+}
+
+/// Charlie 规则：玩家手牌达到指定张数且没有爆牌时自动获胜
+///
+/// 在庄家补牌之前结算；庄家黑杰克仍然优先（偷看规则下已先结算，
+/// 无暗牌规则下庄家黑杰克照常收走这手投注）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CharlieRule {
+    /// 自动获胜所需的牌数（通常为 5 或 6）
+    pub cards: u8,
+    /// 自动获胜的赔率（通常为 1:1）
+    pub payout: f64,
+}
+
+impl CharlieRule {
+    /// 五张 Charlie，赔 1:1
+    pub fn five_card() -> Self {
+        Self { cards: 5, payout: 1.0 }
+    }
+
+    /// 六张 Charlie，赔 1:1
+    pub fn six_card() -> Self {
+        Self { cards: 6, payout: 1.0 }
+    }
+
+    /// 手牌是否达到 Charlie
+    pub fn is_charlie(&self, hand: &Hand) -> bool {
+        hand.card_count() >= self.cards as usize && !hand.is_busted()
+    }
+
+    /// 距离 Charlie 还差几张牌
+    pub fn cards_needed(&self, hand: &Hand) -> usize {
+        (self.cards as usize).saturating_sub(hand.card_count())
+    }
+}
+
+impl GameRules {
+    /// 手牌是否按 Charlie 规则自动获胜
+    pub fn is_charlie(&self, hand: &Hand) -> bool {
+        self.charlie.is_some_and(|charlie| charlie.is_charlie(hand))
+    }
+
+    /// 检查规则本身是否有效（牌组数量、分牌手数、黑杰克和 Charlie 赔率）
+    pub fn validate(&self) -> Result<()> {
+        if self.deck_count == 0 {
+            return Err(CalculatorError::InvalidDeckCount(self.deck_count));
+        }
+        if self.allow_split && self.max_split_hands < 2 {
+            return Err(CalculatorError::InvalidMaxSplitHands(self.max_split_hands));
+        }
+        if !self.blackjack_payout.is_finite() || self.blackjack_payout <= 0.0 {
+            return Err(CalculatorError::InvalidPayout(self.blackjack_payout));
+        }
+        if let Some(charlie) = self.charlie {
+            if charlie.cards < 3 {
+                return Err(CalculatorError::InvalidCharlieCards(charlie.cards));
+            }
+            if !charlie.payout.is_finite() || charlie.payout <= 0.0 {
+                return Err(CalculatorError::InvalidPayout(charlie.payout));
+            }
+        }
+        Ok(())
+    }
+
+    /// 检查剩余牌组能否来自规则中的牌组数量，返回总张数
+    ///
+    /// 牌面必须有效，每种牌不能超过 `deck_count` 副牌的张数
+    pub fn validate_deck(&self, deck: &CardCounts) -> Result<u32> {
+        let mut total = 0;
+        for (&card, &count) in deck {
+            if !card.is_valid() {
+                return Err(CalculatorError::InvalidRank(card));
+            }
+            let max = card.copies_per_deck() * self.deck_count as u32;
+            if count > max {
+                return Err(CalculatorError::ImpossibleComposition {
+                    card,
+                    count,
+                    deck_count: self.deck_count,
+                    max,
+                });
+            }
+            total += count;
+        }
+        Ok(total)
+    }
 }
 
 impl Default for GameRules {
     fn default() -> Self {
         Self {
             deck_count: 8,
-            allow_surrender: true,  // 支持投降
-            allow_split: false,     // 暂时禁用分牌（性能问题，待优化）
+            surrender: SurrenderRule::Late, // 支持后投降
+            allow_split: true,
             dealer_stands_on_soft_17: false, // 默认硬17停牌
             allow_resplit: false,
+            max_split_hands: default_max_split_hands(),
             allow_double_after_split: false,
             blackjack_payout: 1.5, // 3:2
+            hole_card: HoleCardRule::Peek,
+            double: DoubleRule::AnyTwoCards,
+            charlie: None,
         }
     }
 }
@@ -49,8 +260,11 @@ impl DealerRules {
     /// # Returns
     /// `true` 表示需要要牌，`false` 表示停牌
     pub fn should_hit(dealer_hand: &Hand, stands_on_soft_17: bool) -> bool {
-        let value = dealer_hand.value();
-        
+        Self::should_hit_value(dealer_hand.value(), dealer_hand.is_soft(), stands_on_soft_17)
+    }
+
+    /// 根据点数和软硬判断庄家是否需要继续要牌（用于不构造手牌的递归计算）
+    pub fn should_hit_value(value: u8, is_soft: bool, stands_on_soft_17: bool) -> bool {
         // 如果已经爆牌，不需要要牌
         if value > 21 {
             return false;
@@ -73,7 +287,7 @@ impl DealerRules {
                 return false;
             } else {
                 // 硬17停牌：如果是软17，继续要牌；如果是硬17，停牌
-                return dealer_hand.is_soft();
+                return is_soft;
             }
         }
         
@@ -0,0 +1,163 @@
+//! 区分点数和花色的牌靴
+//!
+//! `CardCounts` / `PointCounts` 只记录点数，无法计算与花色或具体牌面有关的副注
+//! （同花黑杰克奖励、完美对子等）。`Shoe` 按 13 种牌面 × 4 种花色记录剩余牌数，
+//! 需要主注 EV 时再投影为 `CardCounts` / `PointCounts` 交给现有引擎。
+
+use crate::rules::GameRules;
+use crate::types::{Card, CardCounts, PointCounts, Rank, Suit, card_counts_to_point_counts};
+use serde::{Deserialize, Serialize};
+
+/// 区分牌面和花色的牌靴
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shoe {
+    /// 剩余牌数，索引为 [牌面][花色]
+    counts: [[u32; 4]; 13],
+}
+
+impl Shoe {
+    /// 创建空牌靴
+    pub fn empty() -> Self {
+        Self {
+            counts: [[0; 4]; 13],
+        }
+    }
+
+    /// 创建指定副数的完整牌靴（每副 13 × 4 张）
+    pub fn new(deck_count: u8) -> Self {
+        Self {
+            counts: [[deck_count as u32; 4]; 13],
+        }
+    }
+
+    /// 按规则中的牌组数量创建完整牌靴
+    pub fn from_rules(rules: &GameRules) -> Self {
+        Self::new(rules.deck_count)
+    }
+
+    /// 指定牌面和花色的剩余张数
+    pub fn count(&self, rank: Rank, suit: Suit) -> u32 {
+        self.counts[rank.index()][suit.index()]
+    }
+
+    /// 指定牌面（不分花色）的剩余张数
+    pub fn rank_count(&self, rank: Rank) -> u32 {
+        self.counts[rank.index()].iter().sum()
+    }
+
+    /// 指定花色（不分牌面）的剩余张数
+    pub fn suit_count(&self, suit: Suit) -> u32 {
+        self.counts.iter().map(|row| row[suit.index()]).sum()
+    }
+
+    /// 剩余总张数
+    pub fn total(&self) -> u32 {
+        self.counts.iter().flatten().sum()
+    }
+
+    /// 是否已经没有牌
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+
+    /// 放入若干张指定的牌
+    pub fn add(&mut self, rank: Rank, suit: Suit, count: u32) {
+        self.counts[rank.index()][suit.index()] += count;
+    }
+
+    /// 移除一张指定的牌（例如已发出的牌），牌靴中没有这张牌时返回 false
+    pub fn remove(&mut self, rank: Rank, suit: Suit) -> bool {
+        let count = &mut self.counts[rank.index()][suit.index()];
+        if *count == 0 {
+            return false;
+        }
+        *count -= 1;
+        true
+    }
+
+    /// 下一张牌为指定牌面和花色的概率
+    pub fn probability(&self, rank: Rank, suit: Suit) -> f64 {
+        let total = self.total();
+        if total == 0 {
+            return 0.0;
+        }
+        self.count(rank, suit) as f64 / total as f64
+    }
+
+    /// 前两张牌满足条件的精确概率（不放回，按发牌顺序枚举）
+    ///
+    /// 用于计算依赖前两张牌牌面和花色的副注
+    pub fn two_card_probability<F>(&self, predicate: F) -> f64
+    where
+        F: Fn((Rank, Suit), (Rank, Suit)) -> bool,
+    {
+        let total = self.total();
+        if total < 2 {
+            return 0.0;
+        }
+
+        let mut prob = 0.0;
+        for rank1 in Rank::ALL {
+            for suit1 in Suit::ALL {
+                let count1 = self.count(rank1, suit1);
+                if count1 == 0 {
+                    continue;
+                }
+                for rank2 in Rank::ALL {
+                    for suit2 in Suit::ALL {
+                        let mut count2 = self.count(rank2, suit2);
+                        if (rank1, suit1) == (rank2, suit2) {
+                            count2 -= 1;
+                        }
+                        if count2 == 0 || !predicate((rank1, suit1), (rank2, suit2)) {
+                            continue;
+                        }
+                        prob += count1 as f64 / total as f64 * count2 as f64 / (total - 1) as f64;
+                    }
+                }
+            }
+        }
+        prob
+    }
+
+    /// 前两张牌组成同花黑杰克（A 与同花色10点牌）的概率
+    pub fn suited_blackjack_prob(&self) -> f64 {
+        self.two_card_probability(|(rank1, suit1), (rank2, suit2)| {
+            suit1 == suit2 && Self::is_blackjack_pair(rank1, rank2)
+        })
+    }
+
+    /// 前两张牌组成黑杰克的概率（不分花色）
+    pub fn blackjack_prob(&self) -> f64 {
+        self.two_card_probability(|(rank1, _), (rank2, _)| Self::is_blackjack_pair(rank1, rank2))
+    }
+
+    /// 投影为按牌面值统计的牌组（J/Q/K 合并为 `Card::Face`），用于现有 EV 引擎
+    pub fn to_card_counts(&self) -> CardCounts {
+        let mut deck = CardCounts::new();
+        for rank in Rank::ALL {
+            let count = self.rank_count(rank);
+            if count > 0 {
+                *deck.entry(rank.to_card()).or_insert(0) += count;
+            }
+        }
+        deck
+    }
+
+    /// 投影为按点数统计的牌组
+    pub fn to_point_counts(&self) -> PointCounts {
+        card_counts_to_point_counts(&self.to_card_counts())
+    }
+
+    /// 两张牌是否组成黑杰克
+    fn is_blackjack_pair(rank1: Rank, rank2: Rank) -> bool {
+        let is_ten = |rank: Rank| rank.to_card() == Card::Face || rank == Rank::Ten;
+        (rank1 == Rank::Ace && is_ten(rank2)) || (rank2 == Rank::Ace && is_ten(rank1))
+    }
+}
+
+impl Default for Shoe {
+    fn default() -> Self {
+        Self::from_rules(&GameRules::default())
+    }
+}
@@ -1,7 +1,80 @@
-//! Blackjack 基础策略表
-
-use crate::types::{Action, Hand, Card};
+//! Blackjack 玩家策略和基础策略表
+//!
+//! `Strategy` 是 EV 引擎查询玩家动作的接口：给定手牌、庄家明牌、剩余牌组和规则返回动作，
+//! 引擎按手牌阶段给出合法动作，不合法的动作由策略自己回退（`decide_among`）。
+//! `BasicStrategy` 是内置的基础策略表实现，其他图表、按牌组调整的策略或玩家自己的
+//! （有错误的）打法都可以实现该接口，再用 `Calculator::with_strategy` 计算 EV。
+
+use crate::calculator::create_full_deck;
+use crate::error::Result;
+use crate::rules::{DoubleRule, GameRules, HoleCardRule};
+use crate::strategy_chart::{self, StrategyChart};
+use crate::strategy_generator;
+use crate::types::{Action, Hand, Card, CardCounts, PointCounts};
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+/// 玩家策略
+///
+/// `shoe` 为玩家决策时未见的剩余牌组（不含玩家手牌和庄家明牌，庄家暗牌在其中），
+/// 按 `PointCounts` 的点数计数：索引 0 为 A，索引 1-8 为 2-9，
+/// 索引 9 为全部十点牌（10 和 J/Q/K 合并），索引 10 总为 0。
+/// EV 计算中为当前分支的剩余牌组，逐手游戏（`Calculator::basic_action`）中为调用者传入的未见牌组
+pub trait Strategy: Send + Sync {
+    /// 决定一手牌的动作
+    ///
+    /// 两张牌时可以选择规则允许的任何动作，要牌之后只有要牌、停牌和规则允许的加倍
+    fn decide(&self, hand: &Hand, dealer_up_card: Card, shoe: &PointCounts, rules: &GameRules) -> Action;
+
+    /// 只能在 `allowed` 中选择时的动作（分牌后的手牌、规则不允许 `decide` 的动作等）
+    ///
+    /// 默认：`decide` 的动作合法时使用它；不能加倍时软18及以上停牌、其余要牌；
+    /// 不能分牌或投降时硬17及以上、软19及以上停牌，其余要牌
+    fn decide_among(
+        &self,
+        hand: &Hand,
+        dealer_up_card: Card,
+        shoe: &PointCounts,
+        rules: &GameRules,
+        allowed: &[Action],
+    ) -> Action {
+        let action = self.decide(hand, dealer_up_card, shoe, rules);
+        if allowed.contains(&action) {
+            return action;
+        }
+        let stand = match action {
+            Action::Double => hand.is_soft() && hand.value() >= 18,
+            _ if hand.is_soft() => hand.value() >= 19,
+            _ => hand.value() >= 17,
+        };
+        if stand {
+            Action::Stand
+        } else {
+            Action::Hit
+        }
+    }
+}
+
+impl<S: Strategy + ?Sized> Strategy for Arc<S> {
+    fn decide(&self, hand: &Hand, dealer_up_card: Card, shoe: &PointCounts, rules: &GameRules) -> Action {
+        (**self).decide(hand, dealer_up_card, shoe, rules)
+    }
+
+    fn decide_among(
+        &self,
+        hand: &Hand,
+        dealer_up_card: Card,
+        shoe: &PointCounts,
+        rules: &GameRules,
+        allowed: &[Action],
+    ) -> Action {
+        (**self).decide_among(hand, dealer_up_card, shoe, rules, allowed)
+    }
+}
+
+/// 策略表中要牌后手牌的最大牌数
+pub(crate) const MAX_TABLE_CARDS: usize = 10;
 
 /// 基础策略表
 /// Key: (玩家点数, 是否软点数, 玩家牌数, 庄家明牌点数)
@@ -9,7 +82,7 @@ use std::collections::HashMap;
 type StrategyTable = HashMap<(u8, bool, usize, u8), Action>;
 
 /// 分牌策略表
-/// Key: (玩家点数, 庄家明牌点数)
+/// Key: (对子单张点数, 庄家明牌点数)，A 记为 11
 /// Value: 是否分牌
 type SplitTable = HashMap<(u8, u8), Action>;
 
@@ -18,11 +91,23 @@ type SplitTable = HashMap<(u8, u8), Action>;
 /// Value: 是否投降
 type SurrenderTable = HashMap<(u8, u8), Action>;
 
+/// 不能加倍时的回退表
+/// Key: (玩家点数, 是否软点数, 庄家明牌点数)
+/// Value: 要牌或停牌（没有时软18及以上停牌、其余要牌）
+type DoubleFallbackTable = HashMap<(u8, bool, u8), Action>;
+
 /// 基础策略
+#[derive(Debug, Clone)]
 pub struct BasicStrategy {
     table: StrategyTable,
     split_table: SplitTable,
+    /// 只在允许分牌后加倍时分牌的对子
+    double_after_split_table: SplitTable,
     surrender_table: SurrenderTable,
+    early_surrender_table: SurrenderTable,
+    double_fallback_table: DoubleFallbackTable,
+    /// 无暗牌规则下是否按 `adjust_for_no_hole_card` 调整（按规则生成的表已经考虑，不再调整）
+    adjusts_for_no_hole_card: bool,
 }
 
 impl BasicStrategy {
@@ -31,16 +116,132 @@ impl BasicStrategy {
         let mut table = StrategyTable::new();
         let mut split_table = SplitTable::new();
         let mut surrender_table = SurrenderTable::new();
+        let mut early_surrender_table = SurrenderTable::new();
         
         // 初始化基础策略表
         Self::init_strategy_table(&mut table);
         Self::init_split_table(&mut split_table);
         Self::init_surrender_table(&mut surrender_table);
+        Self::init_early_surrender_table(&mut early_surrender_table);
         
         Self { 
             table,
             split_table,
             surrender_table,
+            early_surrender_table,
+            adjusts_for_no_hole_card: true,
+            ..Self::empty()
+        }
+    }
+
+    /// 按规则为完整牌靴生成基础策略（见 `strategy_generator`）
+    ///
+    /// # Errors
+    /// 规则无效时返回相应错误
+    pub fn for_rules(rules: &GameRules) -> Result<Self> {
+        Self::generate(rules, &create_full_deck(rules.deck_count))
+    }
+
+    /// 按规则和起始牌组生成基础策略
+    ///
+    /// 需要对每种组合计算最优策略 EV，多副牌需要数秒（`parallel` 特性下按庄家明牌并行）；
+    /// 结果只取决于规则和牌组，可以生成一次后重复使用
+    ///
+    /// # Errors
+    /// 规则无效或牌组超过规则中的牌组数量时返回相应错误
+    pub fn generate(rules: &GameRules, deck: &CardCounts) -> Result<Self> {
+        strategy_generator::generate(rules, deck)
+    }
+
+    /// 从图表文件（`.csv` / `.json`）加载，见 `StrategyChart::from_file` 和 `StrategyChart::to_strategy`
+    ///
+    /// # Errors
+    /// 文件无法读取、扩展名不支持、格式错误或单元格无效时返回 `InvalidStrategyChart`，错误信息带文件路径
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        strategy_chart::with_path(path, StrategyChart::from_file(path)?.to_strategy())
+    }
+
+    /// 导出为 CSV 图表
+    pub fn to_csv(&self) -> String {
+        StrategyChart::from(self).to_csv()
+    }
+
+    /// 导出为 JSON 图表
+    pub fn to_json(&self) -> String {
+        StrategyChart::from(self).to_json()
+    }
+
+    /// 没有任何单元格的策略（用于从图表导入和按规则生成）
+    pub(crate) fn empty() -> Self {
+        Self {
+            table: StrategyTable::new(),
+            split_table: SplitTable::new(),
+            double_after_split_table: SplitTable::new(),
+            surrender_table: SurrenderTable::new(),
+            early_surrender_table: SurrenderTable::new(),
+            double_fallback_table: DoubleFallbackTable::new(),
+            adjusts_for_no_hole_card: false,
+        }
+    }
+
+    /// 要牌/停牌/加倍单元格
+    pub(crate) fn table_action(&self, player_value: u8, soft: bool, card_count: usize, dealer_value: u8) -> Option<Action> {
+        self.table.get(&(player_value, soft, card_count, dealer_value)).copied()
+    }
+
+    /// 对子是否总是分牌
+    pub(crate) fn splits(&self, pair_value: u8, dealer_value: u8) -> bool {
+        self.split_table.contains_key(&(pair_value, dealer_value))
+    }
+
+    /// 对子是否只在允许分牌后加倍时分牌
+    pub(crate) fn splits_with_double_after_split(&self, pair_value: u8, dealer_value: u8) -> bool {
+        self.double_after_split_table.contains_key(&(pair_value, dealer_value))
+    }
+
+    /// 硬点数两张牌是否投降
+    pub(crate) fn surrenders(&self, player_value: u8, dealer_value: u8) -> bool {
+        self.surrender_table.contains_key(&(player_value, dealer_value))
+    }
+
+    /// 设置一个要牌/停牌/加倍单元格
+    pub(crate) fn set_action(&mut self, player_value: u8, soft: bool, card_count: usize, dealer_value: u8, action: Action) {
+        self.table.insert((player_value, soft, card_count, dealer_value), action);
+    }
+
+    /// 设置对子是否分牌（对子单张点数和庄家明牌点数，A 记为 11）
+    pub(crate) fn set_split(&mut self, pair_value: u8, dealer_value: u8, split: bool) {
+        Self::set_flag(&mut self.split_table, (pair_value, dealer_value), split, Action::Split);
+    }
+
+    /// 设置对子是否只在允许分牌后加倍时分牌
+    pub(crate) fn set_split_with_double_after_split(&mut self, pair_value: u8, dealer_value: u8, split: bool) {
+        let table = &mut self.double_after_split_table;
+        Self::set_flag(table, (pair_value, dealer_value), split, Action::Split);
+    }
+
+    /// 设置不能加倍时的回退动作（要牌或停牌）
+    pub(crate) fn set_double_fallback(&mut self, player_value: u8, soft: bool, dealer_value: u8, action: Action) {
+        self.double_fallback_table.insert((player_value, soft, dealer_value), action);
+    }
+
+    /// 设置硬点数两张牌是否投降
+    pub(crate) fn set_surrender(&mut self, player_value: u8, dealer_value: u8, surrender: bool) {
+        Self::set_flag(&mut self.surrender_table, (player_value, dealer_value), surrender, Action::Surrender);
+    }
+
+    /// 设置硬点数两张牌是否早投降
+    pub(crate) fn set_early_surrender(&mut self, player_value: u8, dealer_value: u8, surrender: bool) {
+        let table = &mut self.early_surrender_table;
+        Self::set_flag(table, (player_value, dealer_value), surrender, Action::Surrender);
+    }
+
+    fn set_flag(table: &mut HashMap<(u8, u8), Action>, key: (u8, u8), flag: bool, action: Action) {
+        if flag {
+            table.insert(key, action);
+        } else {
+            table.remove(&key);
         }
     }
 
@@ -50,25 +251,59 @@ impl BasicStrategy {
         player_hand: &Hand,
         dealer_up_card: Card,
     ) -> Action {
-        let player_value = player_hand.value();
-        let player_is_soft = player_hand.is_soft();
-        let player_card_count = player_hand.card_count();
-        let dealer_value = Self::card_to_value(dealer_up_card);
-
         // 注意：分牌和投降的检查在 probability_calculator 中根据 rules 决定
         // 这里只查询策略表，不做规则检查
 
         // 检查是否可以分牌（优先级最高）
-        if player_hand.can_split() {
-            if let Some(action) = self.split_table.get(&(player_value, dealer_value)) {
-                if *action == Action::Split {
-                    return Action::Split;
-                }
-            }
+        if self.should_split(player_hand, dealer_up_card) {
+            return Action::Split;
         }
 
-        // 检查是否可以投降（只能在初始两张牌时）
-        if player_hand.can_double() {
+        self.get_action_without_split(player_hand, dealer_up_card)
+    }
+
+    /// 查询对子是否应该分牌
+    pub fn should_split(&self, player_hand: &Hand, dealer_up_card: Card) -> bool {
+        if !player_hand.can_split() {
+            return false;
+        }
+        let pair_value = Self::card_to_value(player_hand.cards[0].card);
+        let dealer_value = Self::card_to_value(dealer_up_card);
+        self.split_table.get(&(pair_value, dealer_value)) == Some(&Action::Split)
+    }
+
+    /// 按规则查询对子是否应该分牌（包括只在允许分牌后加倍时分牌的对子）
+    fn should_split_under(&self, player_hand: &Hand, dealer_up_card: Card, rules: &GameRules) -> bool {
+        if !player_hand.can_split() {
+            return false;
+        }
+        let pair_value = Self::card_to_value(player_hand.cards[0].card);
+        let dealer_value = Self::card_to_value(dealer_up_card);
+        self.splits(pair_value, dealer_value)
+            || (rules.allow_double_after_split && self.splits_with_double_after_split(pair_value, dealer_value))
+    }
+
+    /// 查询是否应该早投降（庄家检查黑杰克之前，优先于分牌）
+    pub fn should_surrender_early(&self, player_hand: &Hand, dealer_up_card: Card) -> bool {
+        if !player_hand.can_double() || player_hand.is_soft() {
+            return false;
+        }
+        let dealer_value = Self::card_to_value(dealer_up_card);
+        self.early_surrender_table.get(&(player_hand.value(), dealer_value)) == Some(&Action::Surrender)
+    }
+
+    /// 获取动作（跳过分牌，查询 Surrender/Hit/Stand/Double）
+    /// 用于回退策略，当分牌不允许时
+    pub fn get_action_without_split(
+        &self,
+        player_hand: &Hand,
+        dealer_up_card: Card,
+    ) -> Action {
+        let player_value = player_hand.value();
+        let dealer_value = Self::card_to_value(dealer_up_card);
+
+        // 检查是否可以投降（只能在初始两张牌时，投降表只适用于硬点数）
+        if player_hand.can_double() && !player_hand.is_soft() {
             if let Some(action) = self.surrender_table.get(&(player_value, dealer_value)) {
                 if *action == Action::Surrender {
                     return Action::Surrender;
@@ -76,20 +311,7 @@ impl BasicStrategy {
             }
         }
 
-        // 检查是否可以加倍
-        if player_hand.can_double() {
-            if let Some(action) = self.table.get(&(player_value, player_is_soft, 2, dealer_value)) {
-                if *action == Action::Double {
-                    return Action::Double;
-                }
-            }
-        }
-
-        // 查询要牌/停牌策略
-        self.table
-            .get(&(player_value, player_is_soft, player_card_count, dealer_value))
-            .copied()
-            .unwrap_or(Action::Stand)
+        self.get_action_without_split_surrender(player_hand, dealer_up_card)
     }
 
     /// 获取动作（跳过分牌和投降，只查询 Hit/Stand/Double）
@@ -120,8 +342,108 @@ impl BasicStrategy {
             .unwrap_or(Action::Stand)
     }
 
+    /// 获取不能加倍时的动作（例如分牌后不允许加倍）
+    /// 策略表为加倍时按回退表；没有回退时软18及以上停牌（Ds），其余要牌（D）
+    pub fn get_action_without_double(
+        &self,
+        player_hand: &Hand,
+        dealer_up_card: Card,
+    ) -> Action {
+        match self.get_action_without_split_surrender(player_hand, dealer_up_card) {
+            Action::Double => self.double_fallback(
+                player_hand.value(),
+                player_hand.is_soft(),
+                Self::card_to_value(dealer_up_card),
+            ),
+            action => action,
+        }
+    }
+
+    /// 策略表为加倍但不能加倍时的动作
+    pub(crate) fn double_fallback(&self, player_value: u8, soft: bool, dealer_value: u8) -> Action {
+        match self.double_fallback_table.get(&(player_value, soft, dealer_value)) {
+            Some(&action) => action,
+            None if soft && player_value >= 18 => Action::Stand,
+            None => Action::Hit,
+        }
+    }
+
+    /// 获取要牌后仍可加倍时的动作（任意张数加倍规则，跳过分牌和投降）
+    /// 多张牌的手牌按相同点数的两张牌查询是否加倍，不加倍时按实际牌数查询
+    pub fn get_action_with_drawn_double(
+        &self,
+        player_hand: &Hand,
+        dealer_up_card: Card,
+    ) -> Action {
+        let dealer_value = Self::card_to_value(dealer_up_card);
+        let key = (player_hand.value(), player_hand.is_soft(), 2, dealer_value);
+        if self.table.get(&key) == Some(&Action::Double) {
+            return Action::Double;
+        }
+        self.get_action_without_double(player_hand, dealer_up_card)
+    }
+
+    /// 差一张牌达到 Charlie（赔 1:1）时调整查询到的停牌动作
+    ///
+    /// 软牌再要一张不会爆牌，总是要牌；硬牌在爆牌风险低于停牌损失时要牌：
+    /// 硬15及以下总是要牌，硬16 对 2、3、7-A 要牌，硬17 对 9-A 要牌。
+    /// 这些单元格按 1:1 赔率推导，不随牌组数量变化；`decide` 只在 Charlie 赔 1:1 时使用。
+    /// 其他赔率或差两张及以上的手牌按普通基础策略，需要准确打法时使用最优策略模式
+    pub fn adjust_for_charlie(
+        &self,
+        player_hand: &Hand,
+        dealer_up_card: Card,
+        action: Action,
+    ) -> Action {
+        if action != Action::Stand {
+            return action;
+        }
+        let dealer_value = Self::card_to_value(dealer_up_card);
+        let hit = player_hand.is_soft()
+            || match player_hand.value() {
+                0..=15 => true,
+                16 => !(4..=6).contains(&dealer_value),
+                17 => dealer_value >= 9,
+                _ => false,
+            };
+        if hit {
+            Action::Hit
+        } else {
+            action
+        }
+    }
+
+    /// 欧式无暗牌（庄家黑杰克收走全部投注）规则下调整查询到的动作
+    ///
+    /// 庄家明牌为10点或A时，追加的投注可能全部输给庄家黑杰克：
+    /// 硬11不加倍改为要牌，8-8 对10点/A、A-A 对A不分牌
+    pub fn adjust_for_no_hole_card(
+        &self,
+        player_hand: &Hand,
+        dealer_up_card: Card,
+        action: Action,
+    ) -> Action {
+        let dealer_value = Self::card_to_value(dealer_up_card);
+        if dealer_value < 10 {
+            return action;
+        }
+
+        match action {
+            Action::Double if !player_hand.is_soft() && player_hand.value() == 11 => Action::Hit,
+            Action::Split => {
+                let pair_value = Self::card_to_value(player_hand.cards[0].card);
+                if pair_value == 8 || (pair_value == 11 && dealer_value == 11) {
+                    self.get_action_without_split(player_hand, dealer_up_card)
+                } else {
+                    Action::Split
+                }
+            }
+            action => action,
+        }
+    }
+
     /// 将牌转换为点数（用于策略查询）
-    fn card_to_value(card: Card) -> u8 {
+    pub(crate) fn card_to_value(card: Card) -> u8 {
         match card {
             Card::Ace => 11, // 庄家明牌A当作11
             Card::Number(n) => n,
@@ -135,10 +457,10 @@ impl BasicStrategy {
         // 硬点数策略（非软点数）
         // (玩家点数, false=硬, 牌数, 庄家明牌)
         
-        // 硬点数：5-8 总是要牌
-        for player_value in 5..=8 {
+        // 硬点数：4-8 总是要牌（硬4 只来自不分牌的 2-2）
+        for player_value in 4..=8 {
             for dealer_value in 2..=11 {
-                for card_count in 2..=10 {
+                for card_count in 2..=MAX_TABLE_CARDS {
                     table.insert((player_value, false, card_count, dealer_value), Action::Hit);
                 }
             }
@@ -152,7 +474,7 @@ impl BasicStrategy {
             table.insert((9, false, 2, dealer_value), Action::Hit);
         }
         for dealer_value in 2..=11 {
-            for card_count in 3..=10 {
+            for card_count in 3..=MAX_TABLE_CARDS {
                 table.insert((9, false, card_count, dealer_value), Action::Hit);
             }
         }
@@ -164,7 +486,7 @@ impl BasicStrategy {
         table.insert((10, false, 2, 10), Action::Hit);
         table.insert((10, false, 2, 11), Action::Hit);
         for dealer_value in 2..=11 {
-            for card_count in 3..=10 {
+            for card_count in 3..=MAX_TABLE_CARDS {
                 table.insert((10, false, card_count, dealer_value), Action::Hit);
             }
         }
@@ -175,7 +497,7 @@ impl BasicStrategy {
         }
         table.insert((11, false, 2, 11), Action::Hit);
         for dealer_value in 2..=11 {
-            for card_count in 3..=10 {
+            for card_count in 3..=MAX_TABLE_CARDS {
                 table.insert((11, false, card_count, dealer_value), Action::Hit);
             }
         }
@@ -191,7 +513,7 @@ impl BasicStrategy {
             table.insert((12, false, 2, dealer_value), Action::Hit);
         }
         for dealer_value in 2..=11 {
-            for card_count in 3..=10 {
+            for card_count in 3..=MAX_TABLE_CARDS {
                 table.insert((12, false, card_count, dealer_value), Action::Hit);
             }
         }
@@ -205,7 +527,7 @@ impl BasicStrategy {
                 table.insert((player_value, false, 2, dealer_value), Action::Hit);
             }
             for dealer_value in 2..=11 {
-                for card_count in 3..=10 {
+                for card_count in 3..=MAX_TABLE_CARDS {
                     table.insert((player_value, false, card_count, dealer_value), Action::Hit);
                 }
             }
@@ -214,7 +536,7 @@ impl BasicStrategy {
         // 硬点数：17-21 总是停牌
         for player_value in 17..=21 {
             for dealer_value in 2..=11 {
-                for card_count in 2..=10 {
+                for card_count in 2..=MAX_TABLE_CARDS {
                     table.insert((player_value, false, card_count, dealer_value), Action::Stand);
                 }
             }
@@ -223,6 +545,13 @@ impl BasicStrategy {
         // 软点数策略（有A且A当作11）
         // (玩家点数, true=软, 牌数, 庄家明牌)
 
+        // 软12 (A,A 不分牌时) 总是要牌
+        for dealer_value in 2..=11 {
+            for card_count in 2..=MAX_TABLE_CARDS {
+                table.insert((12, true, card_count, dealer_value), Action::Hit);
+            }
+        }
+
         // 软13-14 (A,2 或 A,3)
         for player_value in 13..=14 {
             for dealer_value in 5..=6 {
@@ -235,7 +564,7 @@ impl BasicStrategy {
                 table.insert((player_value, true, 2, dealer_value), Action::Hit);
             }
             for dealer_value in 2..=11 {
-                for card_count in 3..=10 {
+                for card_count in 3..=MAX_TABLE_CARDS {
                     table.insert((player_value, true, card_count, dealer_value), Action::Hit);
                 }
             }
@@ -253,7 +582,7 @@ impl BasicStrategy {
                 table.insert((player_value, true, 2, dealer_value), Action::Hit);
             }
             for dealer_value in 2..=11 {
-                for card_count in 3..=10 {
+                for card_count in 3..=MAX_TABLE_CARDS {
                     table.insert((player_value, true, card_count, dealer_value), Action::Hit);
                 }
             }
@@ -271,7 +600,7 @@ impl BasicStrategy {
             table.insert((17, true, 2, dealer_value), Action::Hit);
         }
         for dealer_value in 2..=11 {
-            for card_count in 3..=10 {
+            for card_count in 3..=MAX_TABLE_CARDS {
                 table.insert((17, true, card_count, dealer_value), Action::Stand);
             }
         }
@@ -288,7 +617,7 @@ impl BasicStrategy {
         }
         table.insert((18, true, 2, 11), Action::Stand);
         for dealer_value in 2..=11 {
-            for card_count in 3..=10 {
+            for card_count in 3..=MAX_TABLE_CARDS {
                 table.insert((18, true, card_count, dealer_value), Action::Stand);
             }
         }
@@ -296,7 +625,7 @@ impl BasicStrategy {
         // 软19-21 总是停牌
         for player_value in 19..=21 {
             for dealer_value in 2..=11 {
-                for card_count in 2..=10 {
+                for card_count in 2..=MAX_TABLE_CARDS {
                     table.insert((player_value, true, card_count, dealer_value), Action::Stand);
                 }
             }
@@ -306,17 +635,17 @@ impl BasicStrategy {
     /// 初始化分牌策略表
     fn init_split_table(table: &mut SplitTable) {
         // 分牌策略：根据玩家对子和庄家明牌决定
-        // (玩家点数, 庄家明牌点数) -> 是否分牌
+        // (对子单张点数, 庄家明牌点数) -> 是否分牌
         
         // A-A: 总是分牌
         for dealer_value in 2..=11 {
-            table.insert((11, dealer_value), Action::Split); // A-A = 11点
+            table.insert((11, dealer_value), Action::Split);
         }
         
         // 2-2, 3-3: 分牌（2-7），不分（8-11）
-        for player_value in 4..=6 { // 2-2=4, 3-3=6
+        for pair_value in 2..=3 {
             for dealer_value in 2..=7 {
-                table.insert((player_value, dealer_value), Action::Split);
+                table.insert((pair_value, dealer_value), Action::Split);
             }
         }
         
@@ -326,25 +655,25 @@ impl BasicStrategy {
         
         // 6-6: 分牌（2-6），不分（7-11）
         for dealer_value in 2..=6 {
-            table.insert((12, dealer_value), Action::Split); // 6-6=12
+            table.insert((6, dealer_value), Action::Split);
         }
         
         // 7-7: 分牌（2-7），不分（8-11）
         for dealer_value in 2..=7 {
-            table.insert((14, dealer_value), Action::Split); // 7-7=14
+            table.insert((7, dealer_value), Action::Split);
         }
         
         // 8-8: 总是分牌
         for dealer_value in 2..=11 {
-            table.insert((16, dealer_value), Action::Split); // 8-8=16
+            table.insert((8, dealer_value), Action::Split);
         }
         
         // 9-9: 分牌（2-6, 8-9），不分（7, 10, A）
         for dealer_value in 2..=6 {
-            table.insert((18, dealer_value), Action::Split); // 9-9=18
+            table.insert((9, dealer_value), Action::Split);
         }
-        table.insert((18, 8), Action::Split);
-        table.insert((18, 9), Action::Split);
+        table.insert((9, 8), Action::Split);
+        table.insert((9, 9), Action::Split);
         
         // 10-10: 不分（20点很强）
         
@@ -367,6 +696,94 @@ impl BasicStrategy {
         // 注意：这里只包含最常见的投降情况
         // 实际策略可能更复杂，包括软15、软16等
     }
+
+    /// 初始化早投降策略表（只用于硬点数，对子按总点数查询）
+    fn init_early_surrender_table(table: &mut SurrenderTable) {
+        // 对A：硬5-7、硬12-17 投降（含 3-3、6-6、7-7、8-8）
+        for player_value in (5..=7).chain(12..=17) {
+            table.insert((player_value, 11), Action::Surrender);
+        }
+
+        // 对10点：硬14-16 投降（含 7-7、8-8）
+        for player_value in 14..=16 {
+            table.insert((player_value, 10), Action::Surrender);
+        }
+    }
+}
+
+/// 规则允许的全部动作（没有分牌时的决策点）
+fn rule_actions(hand: &Hand, rules: &GameRules) -> Vec<Action> {
+    let mut actions = vec![Action::Stand, Action::Hit];
+    let stage_allows_double = hand.can_double() || rules.double == DoubleRule::AnyNumberOfCards;
+    if stage_allows_double && rules.double.allows(hand) {
+        actions.push(Action::Double);
+    }
+    if hand.can_double() {
+        if rules.allow_split && hand.can_split() {
+            actions.push(Action::Split);
+        }
+        if rules.surrender.allowed() {
+            actions.push(Action::Surrender);
+        }
+    }
+    actions
+}
+
+impl Strategy for BasicStrategy {
+    fn decide(&self, hand: &Hand, dealer_up_card: Card, shoe: &PointCounts, rules: &GameRules) -> Action {
+        self.decide_among(hand, dealer_up_card, shoe, rules, &rule_actions(hand, rules))
+    }
+
+    /// 查表后按规则回退：早投降优先；无暗牌规则下避免对10点/A追加投注；
+    /// 不能分牌时查投降和其他动作，不能投降时查要牌/停牌/加倍，不能加倍时按 `get_action_without_double`；
+    /// 要牌后可以加倍时按 `get_action_with_drawn_double`；最后按 Charlie 规则调整停牌
+    fn decide_among(
+        &self,
+        hand: &Hand,
+        dealer_up_card: Card,
+        _shoe: &PointCounts,
+        rules: &GameRules,
+        allowed: &[Action],
+    ) -> Action {
+        let can = |action| allowed.contains(&action);
+
+        // 早投降优先于分牌和其他动作
+        if can(Action::Surrender)
+            && rules.surrender.is_early_against(dealer_up_card)
+            && self.should_surrender_early(hand, dealer_up_card)
+        {
+            return Action::Surrender;
+        }
+
+        let mut action = if hand.card_count() > 2 && can(Action::Double) {
+            self.get_action_with_drawn_double(hand, dealer_up_card)
+        } else if self.should_split_under(hand, dealer_up_card, rules) {
+            Action::Split
+        } else {
+            self.get_action_without_split(hand, dealer_up_card)
+        };
+        // 无暗牌且输掉全部投注时，避免对10点/A追加投注
+        if rules.hole_card == HoleCardRule::NoHoleCard && self.adjusts_for_no_hole_card {
+            action = self.adjust_for_no_hole_card(hand, dealer_up_card, action);
+        }
+        if action == Action::Split && !can(Action::Split) {
+            action = self.get_action_without_split(hand, dealer_up_card);
+        }
+        if action == Action::Surrender && !can(Action::Surrender) {
+            action = self.get_action_without_split_surrender(hand, dealer_up_card);
+        }
+        if action == Action::Double && !can(Action::Double) {
+            action = self.get_action_without_double(hand, dealer_up_card);
+        }
+
+        // Charlie 赔 1:1 时，差一张牌达到 Charlie 的手牌继续要牌（其他赔率的单元格没有推导）
+        match rules.charlie {
+            Some(charlie) if charlie.payout == 1.0 && charlie.cards_needed(hand) == 1 => {
+                self.adjust_for_charlie(hand, dealer_up_card, action)
+            }
+            _ => action,
+        }
+    }
 }
 
 impl Default for BasicStrategy {
@@ -0,0 +1,464 @@
+//! 策略表的 JSON / CSV 导入导出
+//!
+//! 图表的列为庄家明牌（`2`-`10`、`A`），行为玩家的两张牌：硬点数 `H5`-`H20`、
+//! 软点数 `S13`-`S20`（A,2-A,9）、对子 `P2`-`P10` 和 `PA`，单元格为动作代码：
+//!
+//! | 代码 | 动作 |
+//! |------|------|
+//! | `H`  | 要牌 |
+//! | `S`  | 停牌 |
+//! | `D`  | 加倍，不能加倍时要牌 |
+//! | `Ds` | 加倍，不能加倍时停牌 |
+//! | `P`  | 分牌 |
+//! | `Ph` | 允许分牌后加倍时分牌，否则要牌 |
+//! | `R`  | 投降，不能投降时要牌 |
+//! | `Rs` | 投降，不能投降时停牌 |
+//!
+//! 可选行 `H4`、`S12` 是不分牌的 2-2、A-A（没有时由对子行不分牌的单元格给出）。
+//! 导入时检查缺少或重复的行、列和单元格、未知代码，以及互相矛盾的单元格：
+//! 分牌代码只能出现在对子行，软点数不能投降，对子行不分牌的单元格必须与相同总点数的行一致，
+//! `Ph` 不分牌时要牌，对应总点数的单元格必须为 `H`。
+//!
+//! 图表只描述两张牌的决策：要牌后的手牌按单元格不能加倍/投降时的动作决策
+//! （`D`、`R` 要牌，`Ds`、`Rs` 停牌）。早投降不在图表中，导入的策略不早投降；
+//! 导出手写表时，其中要牌后手牌的单元格和早投降表不会保留。
+
+use crate::error::{CalculatorError, Result};
+use crate::strategy::{BasicStrategy, MAX_TABLE_CARDS};
+use crate::types::Action;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// 庄家明牌点数（A 记为 11），按图表的列顺序
+const DEALER_VALUES: [u8; 10] = [2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+
+/// 策略图表：列为庄家明牌，行为玩家手牌，单元格为动作代码
+///
+/// JSON 格式即该结构的序列化；CSV 的第一行为表头（第一列为行名，其余为庄家明牌），
+/// 空行和 `#` 开头的行忽略
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrategyChart {
+    /// 庄家明牌（`2`-`10`、`A`）
+    pub upcards: Vec<String>,
+    /// 每行的玩家手牌和各列的动作代码
+    pub rows: Vec<ChartRow>,
+}
+
+/// 图表的一行
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChartRow {
+    /// 玩家手牌（`H16`、`S18`、`P8`、`PA` 等）
+    pub hand: String,
+    /// 与 `upcards` 一一对应的动作代码
+    pub actions: Vec<String>,
+}
+
+/// 单元格的动作代码
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChartCode {
+    Hit,
+    Stand,
+    DoubleOrHit,
+    DoubleOrStand,
+    Split,
+    SplitIfDoubleAfterSplit,
+    SurrenderOrHit,
+    SurrenderOrStand,
+}
+
+impl ChartCode {
+    fn parse(code: &str) -> Option<Self> {
+        let code = match code.to_ascii_uppercase().as_str() {
+            "H" => ChartCode::Hit,
+            "S" => ChartCode::Stand,
+            "D" => ChartCode::DoubleOrHit,
+            "DS" => ChartCode::DoubleOrStand,
+            "P" => ChartCode::Split,
+            "PH" => ChartCode::SplitIfDoubleAfterSplit,
+            "R" => ChartCode::SurrenderOrHit,
+            "RS" => ChartCode::SurrenderOrStand,
+            _ => return None,
+        };
+        Some(code)
+    }
+
+    fn code(self) -> &'static str {
+        match self {
+            ChartCode::Hit => "H",
+            ChartCode::Stand => "S",
+            ChartCode::DoubleOrHit => "D",
+            ChartCode::DoubleOrStand => "Ds",
+            ChartCode::Split => "P",
+            ChartCode::SplitIfDoubleAfterSplit => "Ph",
+            ChartCode::SurrenderOrHit => "R",
+            ChartCode::SurrenderOrStand => "Rs",
+        }
+    }
+
+    fn is_split(self) -> bool {
+        matches!(self, ChartCode::Split | ChartCode::SplitIfDoubleAfterSplit)
+    }
+
+    fn is_surrender(self) -> bool {
+        matches!(self, ChartCode::SurrenderOrHit | ChartCode::SurrenderOrStand)
+    }
+
+    /// 两张牌时查询的动作（投降另记在投降表中）
+    fn two_card_action(self) -> Action {
+        match self {
+            ChartCode::DoubleOrHit | ChartCode::DoubleOrStand => Action::Double,
+            code => code.drawn_action(),
+        }
+    }
+
+    /// 不能加倍或投降时（以及要牌之后）的动作
+    fn drawn_action(self) -> Action {
+        match self {
+            ChartCode::Stand | ChartCode::DoubleOrStand | ChartCode::SurrenderOrStand => Action::Stand,
+            _ => Action::Hit,
+        }
+    }
+}
+
+/// 图表中的手牌
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ChartHand {
+    /// 硬点数
+    Hard(u8),
+    /// 软点数
+    Soft(u8),
+    /// 对子（单张点数，A 记为 11）
+    Pair(u8),
+}
+
+impl ChartHand {
+    /// 导出的行（也是导入时必须有的行）
+    fn required() -> impl Iterator<Item = ChartHand> {
+        (5..=20)
+            .map(ChartHand::Hard)
+            .chain((13..=20).map(ChartHand::Soft))
+            .chain((2..=11).map(ChartHand::Pair))
+    }
+
+    fn parse(label: &str) -> Option<Self> {
+        let label = label.trim().to_ascii_uppercase();
+        let (kind, value) = label.split_at_checked(1)?;
+        let hand = match (kind, value) {
+            ("P", "A") => ChartHand::Pair(11),
+            ("H", _) => ChartHand::Hard(value.parse().ok()?),
+            ("S", _) => ChartHand::Soft(value.parse().ok()?),
+            ("P", _) => ChartHand::Pair(value.parse().ok()?),
+            _ => return None,
+        };
+        let valid = match hand {
+            ChartHand::Hard(value) => (4..=20).contains(&value),
+            ChartHand::Soft(value) => (12..=20).contains(&value),
+            ChartHand::Pair(value) => (2..=11).contains(&value),
+        };
+        valid.then_some(hand)
+    }
+
+    fn label(self) -> String {
+        match self {
+            ChartHand::Hard(value) => format!("H{value}"),
+            ChartHand::Soft(value) => format!("S{value}"),
+            ChartHand::Pair(11) => "PA".to_string(),
+            ChartHand::Pair(value) => format!("P{value}"),
+        }
+    }
+
+    /// 对子不分牌时的总点数行
+    fn total(self) -> ChartHand {
+        match self {
+            ChartHand::Pair(11) => ChartHand::Soft(12),
+            ChartHand::Pair(value) => ChartHand::Hard(value * 2),
+            hand => hand,
+        }
+    }
+}
+
+fn upcard_label(dealer_value: u8) -> String {
+    match dealer_value {
+        11 => "A".to_string(),
+        value => value.to_string(),
+    }
+}
+
+fn parse_upcard(label: &str) -> Option<u8> {
+    let label = label.trim();
+    if label.eq_ignore_ascii_case("A") {
+        return Some(11);
+    }
+    label.parse().ok().filter(|value| (2..=10).contains(value))
+}
+
+fn invalid(message: String) -> CalculatorError {
+    CalculatorError::InvalidStrategyChart(message)
+}
+
+impl StrategyChart {
+    /// 从 CSV 文本读取图表（不检查单元格，见 `to_strategy`）
+    ///
+    /// 支持带引号的单元格、`\r\n` 换行和 UTF-8 BOM（Excel 保存的 CSV），跳过空行和 `#` 开头的注释行
+    ///
+    /// # Errors
+    /// CSV 格式错误或没有表头时返回 `InvalidStrategyChart`
+    pub fn from_csv_str(text: &str) -> Result<Self> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .comment(Some(b'#'))
+            .from_reader(text.trim_start_matches('\u{feff}').as_bytes());
+        let mut lines = Vec::new();
+        for record in reader.records() {
+            let record = record.map_err(|err| invalid(err.to_string()))?;
+            let cells: Vec<String> = record.iter().map(str::to_string).collect();
+            let comment = cells.first().is_some_and(|cell| cell.starts_with('#'));
+            if !comment && cells.iter().any(|cell| !cell.is_empty()) {
+                lines.push(cells);
+            }
+        }
+        let mut lines = lines.into_iter().map(Vec::into_iter);
+        let header = lines.next().ok_or_else(|| invalid("CSV 缺少表头".to_string()))?;
+        let upcards = header.skip(1).collect();
+        let rows = lines
+            .map(|mut cells| ChartRow {
+                hand: cells.next().unwrap_or_default(),
+                actions: cells.collect(),
+            })
+            .collect();
+        Ok(Self { upcards, rows })
+    }
+
+    /// 从 JSON 文本读取图表
+    ///
+    /// # Errors
+    /// JSON 格式错误时返回 `InvalidStrategyChart`
+    pub fn from_json_str(text: &str) -> Result<Self> {
+        serde_json::from_str(text).map_err(|err| invalid(err.to_string()))
+    }
+
+    /// 导出为 CSV 文本
+    pub fn to_csv(&self) -> String {
+        let mut csv = std::iter::once("hand")
+            .chain(self.upcards.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(",");
+        csv.push('\n');
+        for row in &self.rows {
+            csv.push_str(&row.hand);
+            for action in &row.actions {
+                csv.push(',');
+                csv.push_str(action);
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// 导出为 JSON 文本
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// 检查列和行，按庄家明牌 2-10、A 的顺序返回每行的动作代码
+    fn parse_cells(&self) -> Result<HashMap<ChartHand, [ChartCode; 10]>> {
+        let mut columns = Vec::with_capacity(self.upcards.len());
+        for upcard in &self.upcards {
+            let value = parse_upcard(upcard).ok_or_else(|| invalid(format!("未知的列: {upcard}")))?;
+            if columns.contains(&value) {
+                return Err(invalid(format!("重复的列: {upcard}")));
+            }
+            columns.push(value);
+        }
+        if let Some(&missing) = DEALER_VALUES.iter().find(|value| !columns.contains(value)) {
+            return Err(invalid(format!("缺少列: {}", upcard_label(missing))));
+        }
+
+        let mut cells = HashMap::new();
+        for row in &self.rows {
+            let hand = ChartHand::parse(&row.hand).ok_or_else(|| invalid(format!("未知的行: {}", row.hand)))?;
+            let label = hand.label();
+            if row.actions.len() != columns.len() {
+                return Err(invalid(format!(
+                    "行 {label} 有 {} 个单元格，应为 {} 个",
+                    row.actions.len(),
+                    columns.len()
+                )));
+            }
+            let mut codes = [ChartCode::Hit; 10];
+            for (&dealer_value, action) in columns.iter().zip(&row.actions) {
+                let cell = format!("行 {label} 列 {}", upcard_label(dealer_value));
+                if action.trim().is_empty() {
+                    return Err(invalid(format!("{cell} 缺少单元格")));
+                }
+                let code = ChartCode::parse(action.trim())
+                    .ok_or_else(|| invalid(format!("{cell}: 未知的动作代码 {action}")))?;
+                if code.is_split() && !matches!(hand, ChartHand::Pair(_)) {
+                    return Err(invalid(format!("{cell}: 只有对子行可以分牌")));
+                }
+                if code.is_surrender() && matches!(hand.total(), ChartHand::Soft(_)) {
+                    return Err(invalid(format!("{cell}: 软点数不能投降")));
+                }
+                codes[dealer_value as usize - 2] = code;
+            }
+            if cells.insert(hand, codes).is_some() {
+                return Err(invalid(format!("重复的行: {label}")));
+            }
+        }
+        if let Some(missing) = ChartHand::required().find(|hand| !cells.contains_key(hand)) {
+            return Err(invalid(format!("缺少行: {}", missing.label())));
+        }
+        Ok(cells)
+    }
+
+    /// 从图表文件读取，按扩展名（`.csv` / `.json`）选择格式（不检查单元格，见 `to_strategy`）
+    ///
+    /// # Errors
+    /// 文件无法读取、扩展名不支持或格式错误时返回 `InvalidStrategyChart`，错误信息带文件路径
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| invalid(format!("{}: {err}", path.display())))?;
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+        let result = match extension.as_deref() {
+            Some("csv") => Self::from_csv_str(&text),
+            Some("json") => Self::from_json_str(&text),
+            _ => Err(invalid("只支持 .csv 和 .json 文件".to_string())),
+        };
+        with_path(path, result)
+    }
+
+    /// 转换为基础策略
+    ///
+    /// # Errors
+    /// 缺少或重复的行、列和单元格，未知代码，或互相矛盾的单元格返回 `InvalidStrategyChart`
+    pub fn to_strategy(&self) -> Result<BasicStrategy> {
+        let mut cells = self.parse_cells()?;
+
+        // 对子行不分牌的单元格与总点数行一致；没有总点数行（H4、S12）时由对子行补充
+        for pair_value in 2..=11 {
+            let pair = ChartHand::Pair(pair_value);
+            let total = pair.total();
+            let pair_codes = cells[&pair];
+            let defined = cells.contains_key(&total);
+            let total_codes = cells.entry(total).or_insert([ChartCode::Hit; 10]);
+            for (index, (&pair_code, total_code)) in pair_codes.iter().zip(total_codes.iter_mut()).enumerate() {
+                let expected = match pair_code {
+                    ChartCode::Split => continue,
+                    ChartCode::SplitIfDoubleAfterSplit => ChartCode::Hit,
+                    code => code,
+                };
+                if !defined {
+                    *total_code = expected;
+                } else if *total_code != expected {
+                    return Err(invalid(format!(
+                        "行 {} 列 {} 为 {}，与行 {} 的 {} 矛盾",
+                        pair.label(),
+                        upcard_label(DEALER_VALUES[index]),
+                        pair_code.code(),
+                        total.label(),
+                        total_code.code()
+                    )));
+                }
+            }
+        }
+
+        let mut strategy = BasicStrategy::empty();
+        for (&hand, codes) in &cells {
+            for (&dealer_value, &code) in DEALER_VALUES.iter().zip(codes) {
+                match hand {
+                    ChartHand::Pair(pair_value) => {
+                        strategy.set_split(pair_value, dealer_value, code == ChartCode::Split);
+                        let das_split = code == ChartCode::SplitIfDoubleAfterSplit;
+                        strategy.set_split_with_double_after_split(pair_value, dealer_value, das_split);
+                    }
+                    ChartHand::Hard(value) | ChartHand::Soft(value) => {
+                        let soft = matches!(hand, ChartHand::Soft(_));
+                        strategy.set_action(value, soft, 2, dealer_value, code.two_card_action());
+                        strategy.set_double_fallback(value, soft, dealer_value, code.drawn_action());
+                        for card_count in 3..=MAX_TABLE_CARDS {
+                            strategy.set_action(value, soft, card_count, dealer_value, code.drawn_action());
+                        }
+                        if !soft {
+                            strategy.set_surrender(value, dealer_value, code.is_surrender());
+                        }
+                    }
+                }
+            }
+        }
+
+        // 图表中没有的总点数（硬21、软21 等）：硬17、软19 及以上停牌，其余要牌
+        for (value, soft) in (4..=21).map(|value| (value, false)).chain((12..=21).map(|value| (value, true))) {
+            let action = if value >= if soft { 19 } else { 17 } { Action::Stand } else { Action::Hit };
+            for card_count in 2..=MAX_TABLE_CARDS {
+                for dealer_value in DEALER_VALUES {
+                    if strategy.table_action(value, soft, card_count, dealer_value).is_none() {
+                        strategy.set_action(value, soft, card_count, dealer_value, action);
+                    }
+                }
+            }
+        }
+        Ok(strategy)
+    }
+}
+
+/// 导出两张牌的图表（硬5-20、软13-20、全部对子）
+impl From<&BasicStrategy> for StrategyChart {
+    fn from(strategy: &BasicStrategy) -> Self {
+        let rows = ChartHand::required()
+            .map(|hand| ChartRow {
+                hand: hand.label(),
+                actions: DEALER_VALUES
+                    .iter()
+                    .map(|&dealer_value| chart_code(strategy, hand, dealer_value).code().to_string())
+                    .collect(),
+            })
+            .collect();
+        StrategyChart {
+            upcards: DEALER_VALUES.iter().map(|&value| upcard_label(value)).collect(),
+            rows,
+        }
+    }
+}
+
+/// 图表错误的信息加上文件路径
+pub(crate) fn with_path<T>(path: &Path, result: Result<T>) -> Result<T> {
+    result.map_err(|err| match err {
+        CalculatorError::InvalidStrategyChart(message) => invalid(format!("{}: {message}", path.display())),
+        other => other,
+    })
+}
+
+/// 一个单元格的动作代码
+fn chart_code(strategy: &BasicStrategy, hand: ChartHand, dealer_value: u8) -> ChartCode {
+    let (value, soft) = match hand {
+        ChartHand::Pair(pair_value) if strategy.splits(pair_value, dealer_value) => return ChartCode::Split,
+        ChartHand::Pair(pair_value) if strategy.splits_with_double_after_split(pair_value, dealer_value) => {
+            return ChartCode::SplitIfDoubleAfterSplit
+        }
+        ChartHand::Pair(_) => return chart_code(strategy, hand.total(), dealer_value),
+        ChartHand::Hard(value) => (value, false),
+        ChartHand::Soft(value) => (value, true),
+    };
+    let action = strategy.table_action(value, soft, 2, dealer_value).unwrap_or(Action::Stand);
+    let fallback = match action {
+        Action::Double => strategy.double_fallback(value, soft, dealer_value),
+        action => action,
+    };
+    // 投降先于加倍查询
+    let surrender = !soft && strategy.surrenders(value, dealer_value);
+    match (surrender, action, fallback) {
+        (true, _, Action::Stand) => ChartCode::SurrenderOrStand,
+        (true, _, _) => ChartCode::SurrenderOrHit,
+        (false, Action::Double, Action::Stand) => ChartCode::DoubleOrStand,
+        (false, Action::Double, _) => ChartCode::DoubleOrHit,
+        (false, Action::Stand, _) => ChartCode::Stand,
+        (false, _, _) => ChartCode::Hit,
+    }
+}
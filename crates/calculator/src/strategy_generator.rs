@@ -0,0 +1,272 @@
+//! 基础策略生成器：按规则和起始牌组的精确 EV 推导策略表
+//!
+//! 策略表的 CSV/JSON 导入导出见 `strategy_chart`。
+//!
+//! `BasicStrategy::new` 是手写的一套表，不区分软17规则、牌组数量和分牌后加倍。
+//! `BasicStrategy::for_rules` 对起始牌组中每种庄家明牌和玩家两张牌的组合计算各动作的精确 EV
+//! （之后的决策按组合依赖最优策略），再按总点数决策：
+//! - 对子逐一比较分牌和其他动作，分牌 EV 最高时分牌
+//! - 相同总点数（硬/软）的其余组合按发牌概率加权，选择要牌、停牌、加倍中 EV 之和最高的动作；
+//!   选择加倍时，不能加倍的回退动作为同一组合中要牌、停牌 EV 之和较高者；
+//!   要牌后的手牌（三张及以上）只比较要牌和停牌
+//! - 投降 EV 之和高于所选动作时投降；早投降对全部组合（含对子）比较
+//!
+//! 只有两张牌不能组成的单元格（硬21、非黑杰克的软21）从手写表复制；
+//! 起始牌组发不出的组合没有单元格，查询时按停牌。
+
+use crate::calculator::Calculator;
+use crate::error::Result;
+use crate::rules::{GameRules, HoleCardRule};
+use crate::strategy::{BasicStrategy, MAX_TABLE_CARDS};
+use crate::types::{Action, Card, CardCounts, DecisionEV, Hand, PlayingCard, StrategyMode, Suit};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// 按点数排列的牌面：A、2-9、十点牌
+const RANKS: [Card; 10] = [
+    Card::Ace,
+    Card::Number(2),
+    Card::Number(3),
+    Card::Number(4),
+    Card::Number(5),
+    Card::Number(6),
+    Card::Number(7),
+    Card::Number(8),
+    Card::Number(9),
+    Card::Number(10),
+];
+
+/// 一种两张牌组合在某个庄家明牌下的决策
+struct Composition {
+    /// 发到这种组合的相对概率（庄家已偷看时乘以庄家没有黑杰克的概率）
+    weight: f64,
+    hand: Hand,
+    evs: DecisionEV,
+    /// 对子按策略表分牌
+    split: bool,
+}
+
+impl Composition {
+    fn ev(&self, action: Action) -> Option<f64> {
+        self.evs.get(action)
+    }
+}
+
+/// 一组组合中某个动作的加权 EV 之和（有组合不能执行该动作时返回 None）
+fn weighted_ev(compositions: &[&Composition], action: Action) -> Option<f64> {
+    compositions
+        .iter()
+        .map(|composition| composition.ev(action).map(|ev| composition.weight * ev))
+        .sum()
+}
+
+/// 加权 EV 之和最高的动作
+fn best_action(compositions: &[&Composition], actions: &[Action]) -> Option<(Action, f64)> {
+    actions
+        .iter()
+        .filter_map(|&action| weighted_ev(compositions, action).map(|ev| (action, ev)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// 剩余牌组中某个点数的张数（十点牌包括 10 和 J/Q/K）
+fn rank_count(deck: &CardCounts, card: Card) -> u32 {
+    let count = |card| deck.get(&card).copied().unwrap_or(0);
+    match card {
+        Card::Number(10) => count(Card::Number(10)) + count(Card::Face),
+        card => count(card),
+    }
+}
+
+/// 从剩余牌组中移除一张牌（十点牌先移除 10，再移除 J/Q/K）
+fn remove_card(deck: &mut CardCounts, card: Card) {
+    let card = match card {
+        Card::Number(10) if deck.get(&Card::Number(10)).copied().unwrap_or(0) == 0 => Card::Face,
+        card => card,
+    };
+    if let Some(count) = deck.get_mut(&card) {
+        *count -= 1;
+    }
+}
+
+/// 按规则和起始牌组生成基础策略（见 `BasicStrategy::generate`）
+pub(crate) fn generate(rules: &GameRules, deck: &CardCounts) -> Result<BasicStrategy> {
+    rules.validate()?;
+    rules.validate_deck(deck)?;
+
+    // 不同庄家明牌的子状态互不重叠，每个明牌使用独立的计算器
+    let column = |&dealer_up_card: &Card| -> Result<(Card, Vec<Composition>)> {
+        if rank_count(deck, dealer_up_card) == 0 {
+            return Ok((dealer_up_card, Vec::new()));
+        }
+        let calculator = Calculator::with_strategy_mode(rules.clone(), StrategyMode::Optimal);
+        let mut remaining = deck.clone();
+        remove_card(&mut remaining, dealer_up_card);
+        Ok((dealer_up_card, compositions(&calculator, dealer_up_card, &remaining)?))
+    };
+    #[cfg(feature = "parallel")]
+    let columns: Vec<(Card, Vec<Composition>)> = RANKS.par_iter().map(column).collect::<Result<_>>()?;
+    #[cfg(not(feature = "parallel"))]
+    let columns: Vec<(Card, Vec<Composition>)> = RANKS.iter().map(column).collect::<Result<_>>()?;
+
+    // 生成的表已经考虑无暗牌规则，不再按 `adjust_for_no_hole_card` 调整
+    let mut strategy = BasicStrategy::empty();
+    for (dealer_up_card, compositions) in &columns {
+        fill_dealer_column(&mut strategy, rules, *dealer_up_card, compositions);
+    }
+    copy_unreachable_cells(&mut strategy, &BasicStrategy::new());
+    Ok(strategy)
+}
+
+/// 从 `hand_written` 复制两张牌不能组成的 21 点单元格（要牌后或分牌后才会出现）
+fn copy_unreachable_cells(strategy: &mut BasicStrategy, hand_written: &BasicStrategy) {
+    for soft in [false, true] {
+        for card_count in 2..=MAX_TABLE_CARDS {
+            for dealer_value in 2..=11 {
+                if let Some(action) = hand_written.table_action(21, soft, card_count, dealer_value) {
+                    strategy.set_action(21, soft, card_count, dealer_value, action);
+                }
+            }
+        }
+    }
+}
+
+/// 庄家明牌下每种两张牌组合（不含黑杰克）的各动作 EV
+fn compositions(
+    calculator: &Calculator,
+    dealer_up_card: Card,
+    deck: &CardCounts,
+) -> Result<Vec<Composition>> {
+    let rules = calculator.rules();
+    let peeked = rules.hole_card == HoleCardRule::Peek
+        && !rules.surrender.is_early_against(dealer_up_card);
+    let mut compositions = Vec::new();
+
+    for (i, &first) in RANKS.iter().enumerate() {
+        for &second in &RANKS[i..] {
+            let first_count = rank_count(deck, first) as f64;
+            let second_count = rank_count(deck, second) as f64 - f64::from(first == second);
+            let hand = Hand {
+                cards: [first, second].map(|card| PlayingCard { card, suit: Suit::Spades }).to_vec(),
+            };
+            if first_count <= 0.0 || second_count <= 0.0 || hand.is_blackjack() {
+                continue;
+            }
+            let mut remaining = deck.clone();
+            remove_card(&mut remaining, first);
+            remove_card(&mut remaining, second);
+
+            let evs = calculator.calculate_action_evs(&hand, dealer_up_card, &remaining)?;
+            if evs.actions.is_empty() {
+                continue;
+            }
+            // 庄家已偷看时 EV 为没有黑杰克的条件期望，权重乘以没有黑杰克的概率
+            let mut weight = first_count * second_count * if first == second { 1.0 } else { 2.0 };
+            if peeked {
+                weight *= 1.0 - dealer_blackjack_prob(dealer_up_card, &remaining);
+            }
+            let split = evs.get(Action::Split).is_some_and(|split_ev| {
+                evs.actions
+                    .iter()
+                    .all(|action_ev| action_ev.action == Action::Split || action_ev.ev < split_ev)
+            });
+            compositions.push(Composition {
+                weight,
+                hand,
+                evs,
+                split,
+            });
+        }
+    }
+    Ok(compositions)
+}
+
+/// 庄家明牌为A或十点牌时暗牌组成黑杰克的概率
+fn dealer_blackjack_prob(dealer_up_card: Card, deck: &CardCounts) -> f64 {
+    let total: u32 = deck.values().sum();
+    let hole_cards = match dealer_up_card {
+        Card::Ace => rank_count(deck, Card::Number(10)),
+        Card::Number(10) | Card::Face => rank_count(deck, Card::Ace),
+        _ => 0,
+    };
+    if total == 0 {
+        0.0
+    } else {
+        hole_cards as f64 / total as f64
+    }
+}
+
+/// 按一个庄家明牌下的组合 EV 填写该列的分牌、动作和投降单元格
+fn fill_dealer_column(
+    strategy: &mut BasicStrategy,
+    rules: &GameRules,
+    dealer_up_card: Card,
+    compositions: &[Composition],
+) {
+    let dealer_value = BasicStrategy::card_to_value(dealer_up_card);
+    let early = rules.surrender.is_early_against(dealer_up_card);
+
+    for composition in compositions.iter().filter(|composition| composition.hand.can_split()) {
+        let pair_value = BasicStrategy::card_to_value(composition.hand.cards[0].card);
+        strategy.set_split(pair_value, dealer_value, composition.split);
+    }
+
+    let mut totals: Vec<(u8, bool)> = compositions
+        .iter()
+        .map(|composition| (composition.hand.value(), composition.hand.is_soft()))
+        .collect();
+    totals.sort_unstable();
+    totals.dedup();
+
+    for (player_value, soft) in totals {
+        let group: Vec<&Composition> = compositions
+            .iter()
+            .filter(|composition| {
+                composition.hand.value() == player_value && composition.hand.is_soft() == soft
+            })
+            .collect();
+        // 分牌的对子不按总点数决策；全部分牌时（规则不允许再分牌等情况下）仍按全部组合
+        let unsplit: Vec<&Composition> = group.iter().copied().filter(|composition| !composition.split).collect();
+        let unsplit = if unsplit.is_empty() { group.clone() } else { unsplit };
+
+        let Some((action, action_ev)) =
+            best_action(&unsplit, &[Action::Hit, Action::Stand, Action::Double])
+        else {
+            continue;
+        };
+        strategy.set_action(player_value, soft, 2, dealer_value, action);
+        if action == Action::Double {
+            if let Some((fallback, _)) = best_action(&unsplit, &[Action::Hit, Action::Stand]) {
+                strategy.set_double_fallback(player_value, soft, dealer_value, fallback);
+            }
+        }
+        if let Some((drawn_action, _)) = best_action(&group, &[Action::Hit, Action::Stand]) {
+            for card_count in 3..=MAX_TABLE_CARDS {
+                strategy.set_action(player_value, soft, card_count, dealer_value, drawn_action);
+            }
+        }
+        if soft {
+            continue;
+        }
+
+        // 投降表只用于硬点数；早投降在分牌之前决定，比较全部组合
+        let surrender = !early
+            && weighted_ev(&unsplit, Action::Surrender).is_some_and(|ev| ev > action_ev);
+        strategy.set_surrender(player_value, dealer_value, surrender);
+        let early_surrender = early
+            && weighted_ev(&group, Action::Surrender).is_some_and(|surrender_ev| {
+                let play_ev: f64 = group
+                    .iter()
+                    .map(|composition| {
+                        let ev = if composition.split {
+                            composition.ev(Action::Split)
+                        } else {
+                            composition.ev(action)
+                        };
+                        composition.weight * ev.unwrap_or(f64::NEG_INFINITY)
+                    })
+                    .sum();
+                surrender_ev > play_ev
+            });
+        strategy.set_early_surrender(player_value, dealer_value, early_surrender);
+    }
+}
@@ -0,0 +1,217 @@
+//! 逐张跟踪牌靴的实时 EV
+//!
+//! `ShoeTracker` 记录已发出的牌，每次发牌后返回剩余牌组的上桌 EV 和建议下注
+//! （按下注阶梯，或设置了 `KellyBetting` 时按 Kelly 准则）。
+//! 内部的 `Calculator` 在多次计算之间保留缓存：每发一张牌剩余牌组只变化一张，
+//! 大部分子牌组的结果可以直接复用；撤销时直接恢复之前算好的结果。
+
+use crate::betting::KellyBetting;
+use crate::calculator::{create_full_deck, Calculator};
+use crate::error::{CalculatorError, Result};
+use crate::rules::GameRules;
+use crate::types::{Card, CardCounts, TableEVResult};
+use serde::{Deserialize, Serialize};
+
+/// 按优势递增的下注阶梯
+///
+/// 没有优势时下最小注；有优势时每 `advantage_per_unit` 的优势多下一个最小注单位，
+/// 不超过最大注。
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BetRamp {
+    /// 最小注（也是每个加注单位）
+    pub min_bet: f64,
+    /// 最大注
+    pub max_bet: f64,
+    /// 每多下一个单位所需的优势（例如 0.005 = 0.5%）
+    pub advantage_per_unit: f64,
+}
+
+impl BetRamp {
+    /// 指定 EV 下的建议下注
+    pub fn bet_for(&self, ev: f64) -> f64 {
+        if ev <= 0.0 || self.advantage_per_unit <= 0.0 {
+            return self.min_bet;
+        }
+        let units = 1.0 + (ev / self.advantage_per_unit).floor();
+        (self.min_bet * units).min(self.max_bet)
+    }
+}
+
+impl Default for BetRamp {
+    fn default() -> Self {
+        Self {
+            min_bet: 10.0,
+            max_bet: 200.0,
+            advantage_per_unit: 0.005,
+        }
+    }
+}
+
+/// 一次更新的结果
+#[derive(Debug, Clone)]
+pub struct TrackerUpdate {
+    /// 剩余张数
+    pub cards_remaining: u32,
+    /// 剩余牌组的上桌 EV
+    pub table_ev: TableEVResult,
+    /// 建议下注
+    pub recommended_bet: f64,
+}
+
+/// 已发出的一张牌，以及发出之前剩余牌组的计算结果（撤销时恢复）
+#[derive(Debug, Clone)]
+struct DealtCard {
+    card: Card,
+    previous: Option<TableEVResult>,
+}
+
+/// 逐张跟踪牌靴的实时 EV
+pub struct ShoeTracker {
+    calculator: Calculator,
+    bet_ramp: BetRamp,
+    /// Kelly 下注设置（设置后代替下注阶梯）
+    kelly: Option<KellyBetting>,
+    /// 完整牌靴（重新洗牌时恢复）
+    full_deck: CardCounts,
+    /// 剩余牌组
+    deck: CardCounts,
+    /// 已发出的牌（按发牌顺序）
+    dealt: Vec<DealtCard>,
+    /// 当前剩余牌组的计算结果
+    current: Option<TableEVResult>,
+}
+
+impl ShoeTracker {
+    /// 按规则创建完整牌靴的跟踪器（基础策略）
+    pub fn new(rules: GameRules) -> Self {
+        Self::from_calculator(Calculator::new(rules))
+    }
+
+    /// 使用已配置的计算器（策略模式、保险策略）创建跟踪器，牌靴副数取自计算器的规则
+    pub fn from_calculator(calculator: Calculator) -> Self {
+        let deck = create_full_deck(calculator.rules().deck_count);
+        Self::with_deck(calculator, deck)
+    }
+
+    /// 从指定的剩余牌组开始跟踪（例如中途加入的牌靴）
+    pub fn with_deck(calculator: Calculator, deck: CardCounts) -> Self {
+        Self {
+            calculator,
+            bet_ramp: BetRamp::default(),
+            kelly: None,
+            full_deck: deck.clone(),
+            deck,
+            dealt: Vec::new(),
+            current: None,
+        }
+    }
+
+    /// 设置下注阶梯
+    pub fn with_bet_ramp(mut self, bet_ramp: BetRamp) -> Self {
+        self.bet_ramp = bet_ramp;
+        self
+    }
+
+    /// 按 Kelly 准则建议下注（以剩余牌组的上桌 EV 作为优势）
+    ///
+    /// # Errors
+    /// 下注设置无效时返回 `InvalidBetting`
+    pub fn with_kelly_betting(mut self, kelly: KellyBetting) -> Result<Self> {
+        kelly.validate()?;
+        self.kelly = Some(kelly);
+        Ok(self)
+    }
+
+    /// 更新 Kelly 下注使用的资金（没有设置 Kelly 下注时不起作用）
+    pub fn set_bankroll(&mut self, bankroll: f64) {
+        if let Some(kelly) = &mut self.kelly {
+            kelly.bankroll = bankroll;
+        }
+    }
+
+    /// 剩余牌组
+    pub fn remaining(&self) -> &CardCounts {
+        &self.deck
+    }
+
+    /// 剩余张数
+    pub fn cards_remaining(&self) -> u32 {
+        self.deck.values().sum()
+    }
+
+    /// 已发出的牌（按发牌顺序）
+    pub fn dealt_cards(&self) -> Vec<Card> {
+        self.dealt.iter().map(|dealt| dealt.card).collect()
+    }
+
+    /// 内部计算器
+    pub fn calculator(&self) -> &Calculator {
+        &self.calculator
+    }
+
+    /// 记录一张发出的牌但不重新计算（连续记录多张牌后再调用 `update`）
+    ///
+    /// # Errors
+    /// 剩余牌组中没有这张牌时返回 `CardNotInShoe`，状态不变
+    pub fn record(&mut self, card: Card) -> Result<()> {
+        match self.deck.get_mut(&card) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => return Err(CalculatorError::CardNotInShoe(card)),
+        }
+        self.dealt.push(DealtCard {
+            card,
+            previous: self.current.take(),
+        });
+        Ok(())
+    }
+
+    /// 发出一张牌并返回更新后的结果
+    ///
+    /// # Errors
+    /// 剩余牌组中没有这张牌（状态不变），或发牌后剩余牌不足以计算 EV（牌已记录）
+    pub fn deal(&mut self, card: Card) -> Result<TrackerUpdate> {
+        self.record(card)?;
+        self.update()
+    }
+
+    /// 撤销最后一张发出的牌，返回被撤销的牌（之前的计算结果随之恢复）
+    ///
+    /// 没有可撤销的牌时返回 None
+    pub fn undo(&mut self) -> Option<Card> {
+        let dealt = self.dealt.pop()?;
+        *self.deck.entry(dealt.card).or_insert(0) += 1;
+        self.current = dealt.previous;
+        Some(dealt.card)
+    }
+
+    /// 重新洗牌：恢复完整牌靴（保留计算器缓存）
+    pub fn reshuffle(&mut self) {
+        self.deck = self.full_deck.clone();
+        self.dealt.clear();
+        self.current = None;
+    }
+
+    /// 当前剩余牌组的上桌 EV 和建议下注（已计算过时直接返回）
+    ///
+    /// # Errors
+    /// 剩余牌组无效或剩余牌不足 4 张
+    pub fn update(&mut self) -> Result<TrackerUpdate> {
+        let table_ev = match &self.current {
+            Some(result) => result.clone(),
+            None => {
+                let result = self.calculator.calculate_table_ev(&self.deck)?;
+                self.current = Some(result.clone());
+                result
+            }
+        };
+        let recommended_bet = match &self.kelly {
+            Some(kelly) => kelly.bet_for_table_ev(&table_ev),
+            None => self.bet_ramp.bet_for(table_ev.ev),
+        };
+        Ok(TrackerUpdate {
+            cards_remaining: self.cards_remaining(),
+            table_ev,
+            recommended_bet,
+        })
+    }
+}
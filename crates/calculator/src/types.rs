@@ -13,6 +13,76 @@ pub enum Card {
     Face,
 }
 
+impl Card {
+    /// 是否为存在的牌面（点数牌只能是 2-10）
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Card::Number(n) => (2..=10).contains(n),
+            Card::Ace | Card::Face => true,
+        }
+    }
+
+    /// 每副牌中的张数（J/Q/K 合计 12 张，无效牌面为 0）
+    pub fn copies_per_deck(&self) -> u32 {
+        match self {
+            Card::Face => 12,
+            _ if self.is_valid() => 4,
+            _ => 0,
+        }
+    }
+}
+
+/// 牌面等级（区分 J/Q/K，用于需要具体牌面的副注）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Rank {
+    Ace,
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+}
+
+impl Rank {
+    /// 所有牌面等级（A 到 K）
+    pub const ALL: [Rank; 13] = [
+        Rank::Ace,
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+    ];
+
+    /// 在 `Rank::ALL` 中的索引（A = 0, K = 12）
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// 转换为计算用的牌面值（J/Q/K 合并为 `Card::Face`）
+    pub fn to_card(self) -> Card {
+        match self {
+            Rank::Ace => Card::Ace,
+            Rank::Jack | Rank::Queen | Rank::King => Card::Face,
+            rank => Card::Number(rank.index() as u8 + 1),
+        }
+    }
+}
+
 /// 花色
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Suit {
@@ -22,6 +92,16 @@ pub enum Suit {
     Clubs,
 }
 
+impl Suit {
+    /// 所有花色
+    pub const ALL: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+
+    /// 在 `Suit::ALL` 中的索引
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
 /// 完整牌
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct PlayingCard {
@@ -29,6 +109,16 @@ pub struct PlayingCard {
     pub suit: Suit,
 }
 
+impl PlayingCard {
+    /// 由牌面等级和花色创建
+    pub fn from_rank(rank: Rank, suit: Suit) -> Self {
+        Self {
+            card: rank.to_card(),
+            suit,
+        }
+    }
+}
+
 /// 玩家动作
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Action {
@@ -118,8 +208,13 @@ impl Hand {
             }
         }
 
-        // 如果有 A 且总点数 <= 21，说明是软点数
-        aces > 0 && total <= 21
+        // 与 value() 相同地把 A 逐个降为 1 点，仍有 A 当作 11 点时为软点数
+        while total > 21 && aces > 0 {
+            total -= 10;
+            aces -= 1;
+        }
+
+        aces > 0
     }
 
     /// 是否为黑杰克 (21点，两张牌)
@@ -137,7 +232,7 @@ impl Hand {
         self.cards.len()
     }
 
-    /// 是否可以加倍（通常只能在初始两张牌时）
+    /// 是否为可以加倍的初始两张牌（规则的加倍限制见 `DoubleRule::allows`）
     pub fn can_double(&self) -> bool {
         self.cards.len() == 2
     }
@@ -189,26 +284,20 @@ impl Default for Hand {
 pub type CardCounts = std::collections::HashMap<Card, u32>;
 
 /// 点数计数数组（用于高效概率计算）
-/// 索引：0=A(作为1点), 1=A(作为11点), 2-10=对应点数, 11=10点牌(J/Q/K)
-/// 为了简化，我们使用：0=A, 1=2, 2=3, ..., 9=10, 10=10点牌(J/Q/K)
-/// 实际上可以合并：0=A, 1=2, ..., 9=10, 10=10点牌
-/// 更简单的方式：0=A, 1=2, 2=3, ..., 9=10, 10=10点牌（J/Q/K）
-/// 但为了与百家乐一致，我们使用：0=A, 1=2, ..., 9=10
-pub type PointCounts = [u32; 11]; // 0=A, 1=2, 2=3, ..., 9=10, 10=10点牌(J/Q/K)
+/// 索引：0=A, 1=2, 2=3, ..., 8=9, 9=10, 10=J/Q/K
+///
+/// `card_counts_to_point_counts` 分开统计 10 和 J/Q/K；EV 引擎和交给 `Strategy` 的剩余牌组
+/// 把 J/Q/K 合并到索引 9（全部十点牌），索引 10 总为 0
+pub type PointCounts = [u32; 11];
 
 /// 将牌转换为点数索引（用于 PointCounts）
-/// 返回 (点数索引, 是否为A)
-pub fn card_to_point_index(card: Card) -> (usize, bool) {
+/// 返回 (点数索引, 是否为A)；无效牌面（点数牌不在 2-10）返回 None
+pub fn card_to_point_index(card: Card) -> Option<(usize, bool)> {
     match card {
-        Card::Ace => (0, true),
-        Card::Number(n) => {
-            if n == 10 {
-                (9, false) // 10点
-            } else {
-                (n as usize, false)
-            }
-        }
-        Card::Face => (10, false), // J/Q/K 作为10点牌
+        Card::Ace => Some((0, true)),
+        Card::Number(n @ 2..=10) => Some((n as usize - 1, false)), // 2-10 对应索引 1-9
+        Card::Number(_) => None,
+        Card::Face => Some((10, false)), // J/Q/K 作为10点牌
     }
 }
 
@@ -216,12 +305,21 @@ pub fn card_to_point_index(card: Card) -> (usize, bool) {
 pub fn point_index_to_value(index: usize) -> u8 {
     match index {
         0 => 1,  // A
-        1..=9 => index as u8, // 2-10
+        1..=9 => index as u8 + 1, // 2-10
         10 => 10, // J/Q/K
         _ => 0,
     }
 }
 
+/// 将点数索引转换为牌（索引 9 为 10，索引 10 为 J/Q/K）
+pub fn point_index_to_card(index: usize) -> Card {
+    match index {
+        0 => Card::Ace,
+        10 => Card::Face,
+        _ => Card::Number(index as u8 + 1),
+    }
+}
+
 /// 将牌转换为点数（用于概率计算）
 /// A = 1, 2-10 = 2-10, J/Q/K = 10
 pub fn card_to_point(card: Card) -> u8 {
@@ -233,11 +331,14 @@ pub fn card_to_point(card: Card) -> u8 {
 }
 
 /// 将 CardCounts 转换为 PointCounts
+///
+/// 无效牌面不计入，需要报错时先用 `GameRules::validate_deck` 检查
 pub fn card_counts_to_point_counts(card_counts: &CardCounts) -> PointCounts {
     let mut point_counts: PointCounts = [0; 11];
     for (&card, &count) in card_counts {
-        let (index, _) = card_to_point_index(card);
-        point_counts[index] += count;
+        if let Some((index, _)) = card_to_point_index(card) {
+            point_counts[index] += count;
+        }
     }
     point_counts
 }
@@ -265,8 +366,24 @@ pub struct GameOutcome {
     pub player_blackjack_prob_double: f64,
     /// 庄家黑杰克概率（加倍投注）
     pub dealer_blackjack_prob_double: f64,
+    /// 玩家 Charlie 自动获胜概率（普通投注，按 Charlie 赔率结算）
+    pub player_charlie_prob: f64,
+    /// 玩家 Charlie 自动获胜概率（加倍投注）
+    pub player_charlie_prob_double: f64,
     /// 投降概率（损失0.5倍投注）
     pub surrender_prob: f64,
+    /// 分牌概率（该局玩家选择分牌）
+    pub split_prob: f64,
+    /// 分牌局的期望收益（已按概率加权，以原始投注为单位）
+    pub split_ev: f64,
+    /// 分牌局净收益平方的期望（已按概率加权）
+    pub split_second_moment: f64,
+    /// 购买保险的概率（保险是附加投注，不计入结果概率总和）
+    pub insurance_prob: f64,
+    /// 保险注的期望收益（已按概率加权，以原始投注为单位）
+    pub insurance_ev: f64,
+    /// 保险注对净收益平方期望的贡献（保险注的平方加上与主注的交叉项，已按概率加权）
+    pub insurance_second_moment: f64,
 }
 
 impl GameOutcome {
@@ -288,8 +405,19 @@ impl GameOutcome {
         self.push_prob_double += other.push_prob_double * weight;
         self.player_blackjack_prob_double += other.player_blackjack_prob_double * weight;
         self.dealer_blackjack_prob_double += other.dealer_blackjack_prob_double * weight;
+        // 累加 Charlie 概率
+        self.player_charlie_prob += other.player_charlie_prob * weight;
+        self.player_charlie_prob_double += other.player_charlie_prob_double * weight;
         // 累加投降概率
         self.surrender_prob += other.surrender_prob * weight;
+        // 累加分牌概率和分牌收益
+        self.split_prob += other.split_prob * weight;
+        self.split_ev += other.split_ev * weight;
+        self.split_second_moment += other.split_second_moment * weight;
+        // 累加保险概率和保险收益
+        self.insurance_prob += other.insurance_prob * weight;
+        self.insurance_ev += other.insurance_ev * weight;
+        self.insurance_second_moment += other.insurance_second_moment * weight;
     }
     
     /// 累加加倍投注的结果
@@ -299,6 +427,7 @@ impl GameOutcome {
         self.push_prob_double += other.push_prob * weight;
         self.player_blackjack_prob_double += other.player_blackjack_prob * weight;
         self.dealer_blackjack_prob_double += other.dealer_blackjack_prob * weight;
+        self.player_charlie_prob_double += other.player_charlie_prob * weight;
     }
 
     /// 归一化（确保概率总和为1）
@@ -313,7 +442,10 @@ impl GameOutcome {
             + self.push_prob_double
             + self.player_blackjack_prob_double
             + self.dealer_blackjack_prob_double
-            + self.surrender_prob;
+            + self.player_charlie_prob
+            + self.player_charlie_prob_double
+            + self.surrender_prob
+            + self.split_prob;
         if total > 0.0 {
             self.player_win_prob /= total;
             self.dealer_win_prob /= total;
@@ -325,7 +457,104 @@ impl GameOutcome {
             self.push_prob_double /= total;
             self.player_blackjack_prob_double /= total;
             self.dealer_blackjack_prob_double /= total;
+            self.player_charlie_prob /= total;
+            self.player_charlie_prob_double /= total;
             self.surrender_prob /= total;
+            self.split_prob /= total;
+            self.split_ev /= total;
+            self.split_second_moment /= total;
+            self.insurance_prob /= total;
+            self.insurance_ev /= total;
+            self.insurance_second_moment /= total;
+        }
+    }
+}
+
+/// 保险策略（庄家明牌为A时）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum InsurancePolicy {
+    /// 从不购买保险
+    #[default]
+    Never,
+    /// 按当前剩余牌组保险 EV 为正时购买（玩家黑杰克时即选择等额赔付）
+    WhenPositive,
+}
+
+/// 保险和等额赔付的期望值（庄家明牌为A，以原始投注为单位）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct InsuranceEV {
+    /// 庄家暗牌为10点牌（黑杰克）的概率
+    pub dealer_blackjack_prob: f64,
+    /// 购买保险（原始投注的一半，赔率 2:1）的期望收益
+    pub insurance: f64,
+    /// 玩家黑杰克时选择等额赔付（1:1）的期望收益
+    pub even_money: f64,
+    /// 玩家黑杰克时不选等额赔付的期望收益
+    pub decline_even_money: f64,
+}
+
+impl InsuranceEV {
+    /// 保险是否有利（EV 为正）
+    pub fn should_insure(&self) -> bool {
+        self.insurance > 0.0
+    }
+}
+
+/// 策略模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum StrategyMode {
+    /// 按固定的基础策略表决策
+    #[default]
+    Basic,
+    /// 组合依赖最优策略：每个决策都选择当前剩余牌组下精确 EV 最高的动作
+    Optimal,
+}
+
+/// 庄家最终点数概率分布
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct DealerDistribution {
+    /// 停在17点
+    pub total_17: f64,
+    /// 停在18点
+    pub total_18: f64,
+    /// 停在19点
+    pub total_19: f64,
+    /// 停在20点
+    pub total_20: f64,
+    /// 停在21点（非黑杰克）
+    pub total_21: f64,
+    /// 黑杰克
+    pub blackjack: f64,
+    /// 爆牌
+    pub bust: f64,
+}
+
+impl DealerDistribution {
+    /// 概率总和
+    pub fn total_prob(&self) -> f64 {
+        self.total_17
+            + self.total_18
+            + self.total_19
+            + self.total_20
+            + self.total_21
+            + self.blackjack
+            + self.bust
+    }
+
+    /// 庄家没有黑杰克时的条件分布（即庄家偷看之后玩家面对的分布）
+    pub fn without_blackjack(&self) -> Self {
+        let no_blackjack = self.total_prob() - self.blackjack;
+        if no_blackjack <= 0.0 {
+            return Self::default();
+        }
+        Self {
+            total_17: self.total_17 / no_blackjack,
+            total_18: self.total_18 / no_blackjack,
+            total_19: self.total_19 / no_blackjack,
+            total_20: self.total_20 / no_blackjack,
+            total_21: self.total_21 / no_blackjack,
+            blackjack: 0.0,
+            bust: self.bust / no_blackjack,
         }
     }
 }
@@ -341,6 +570,10 @@ pub struct TableEVResult {
     pub ev_double: f64,
     /// 投降的 EV
     pub ev_surrender: f64,
+    /// 分牌的 EV
+    pub ev_split: f64,
+    /// 保险注的 EV
+    pub ev_insurance: f64,
     /// 玩家获胜概率
     pub player_win_prob: f64,
     /// 庄家获胜概率
@@ -351,7 +584,284 @@ pub struct TableEVResult {
     pub player_blackjack_prob: f64,
     /// 庄家黑杰克概率
     pub dealer_blackjack_prob: f64,
+    /// 玩家 Charlie 自动获胜概率（不计入玩家获胜概率）
+    pub charlie_prob: f64,
     /// 投降概率
     pub surrender_prob: f64,
+    /// 分牌概率
+    pub split_prob: f64,
+    /// 购买保险的概率
+    pub insurance_prob: f64,
+    /// 每局净收益的方差（以原始投注为单位的平方，含加倍、分牌和保险注）
+    pub variance: f64,
+    /// 每局净收益的标准差（方差的平方根）
+    pub std_dev: f64,
+}
+
+
+/// 单个动作的期望值
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ActionEV {
+    /// 玩家动作
+    pub action: Action,
+    /// 期望值（以原始投注为单位）
+    pub ev: f64,
+}
+
+/// 某个决策点上所有合法动作的期望值
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DecisionEV {
+    /// 各合法动作的 EV（庄家已偷看时为没有黑杰克的条件期望）
+    pub actions: Vec<ActionEV>,
+}
+
+impl DecisionEV {
+    /// 查询指定动作的 EV（动作不合法时返回 None）
+    pub fn get(&self, action: Action) -> Option<f64> {
+        self.actions
+            .iter()
+            .find(|action_ev| action_ev.action == action)
+            .map(|action_ev| action_ev.ev)
+    }
+
+    /// EV 最高的动作
+    pub fn best(&self) -> Option<ActionEV> {
+        self.actions
+            .iter()
+            .copied()
+            .max_by(|a, b| a.ev.total_cmp(&b.ev))
+    }
+}
+
+/// 基础策略与组合依赖最优策略的上桌 EV 对比
+#[derive(Debug, Clone)]
+pub struct StrategyComparison {
+    /// 按基础策略游戏的结果
+    pub basic: TableEVResult,
+    /// 按组合依赖最优策略游戏的结果
+    pub optimal: TableEVResult,
+    /// 基础策略相对最优策略损失的 EV（`optimal.ev - basic.ev`）
+    pub ev_gain: f64,
+}
+
+/// 移除一张某点数的牌对上桌 EV 的影响
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RankEffect {
+    /// 点数（十点牌记为 `Number(10)`，包含 J/Q/K）
+    pub card: Card,
+    /// 起始牌组中该点数的张数
+    pub count: u32,
+    /// 移除一张后的上桌 EV
+    pub ev: f64,
+    /// 移除效应：`ev - baseline_ev`
+    pub eor: f64,
+}
+
+/// 移除效应（EOR）表：起始牌组的上桌 EV 及移除每种点数一张后的 EV 变化
+///
+/// 起始牌组中没有的点数不出现在表中
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EffectsOfRemoval {
+    /// 起始牌组的上桌 EV
+    pub baseline_ev: f64,
+    /// 按 A、2-9、十点牌顺序排列的移除效应
+    pub ranks: Vec<RankEffect>,
+}
+
+impl EffectsOfRemoval {
+    /// 查询某点数的移除效应（J/Q/K 与 10 相同；点数不在表中时返回 None）
+    pub fn get(&self, card: Card) -> Option<&RankEffect> {
+        let card = match card {
+            Card::Face => Card::Number(10),
+            card => card,
+        };
+        self.ranks.iter().find(|effect| effect.card == card)
+    }
+}
+
+/// 合并净收益取值时的容差（浮点运算得到的相同收益视为一个取值）
+const PAYOUT_EPSILON: f64 = 1e-9;
+
+/// 一局净收益的一个取值
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PayoutProbability {
+    /// 净收益（以原始投注为单位，含加倍、分牌和保险注）
+    pub payout: f64,
+    /// 概率
+    pub prob: f64,
 }
 
+/// 一局净收益的概率分布
+///
+/// 取值按净收益从小到大排列，例如 -2（加倍输）、-1、-0.5（投降）、0（平局）、
+/// +1、+1.5（3:2 黑杰克）、+2，分牌局为各手之和（最多 ±2 × 手数）。
+/// EV、方差、偏度和风险指标都由同一个分布计算
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PayoutDistribution {
+    /// 按净收益从小到大排列的取值
+    pub outcomes: Vec<PayoutProbability>,
+}
+
+impl PayoutDistribution {
+    /// 空分布
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 只有一个取值的分布
+    pub fn single(payout: f64, prob: f64) -> Self {
+        let mut distribution = Self::new();
+        distribution.add(payout, prob);
+        distribution
+    }
+
+    /// 累加一个取值的概率（与已有取值相同时合并）
+    pub fn add(&mut self, payout: f64, prob: f64) {
+        if prob == 0.0 {
+            return;
+        }
+        let index = self
+            .outcomes
+            .partition_point(|outcome| outcome.payout < payout - PAYOUT_EPSILON);
+        match self.outcomes.get_mut(index) {
+            Some(outcome) if (outcome.payout - payout).abs() <= PAYOUT_EPSILON => {
+                outcome.prob += prob;
+            }
+            _ => self.outcomes.insert(index, PayoutProbability { payout, prob }),
+        }
+    }
+
+    /// 按权重累加另一个分布
+    pub fn merge(&mut self, other: &PayoutDistribution, weight: f64) {
+        for outcome in &other.outcomes {
+            self.add(outcome.payout, outcome.prob * weight);
+        }
+    }
+
+    /// 每个取值加上 `delta`（如保险注的结算）
+    pub fn shifted(&self, delta: f64) -> Self {
+        self.map_payouts(|payout| payout + delta)
+    }
+
+    /// 每个取值乘以 `factor`（如加倍）
+    pub fn scaled(&self, factor: f64) -> Self {
+        self.map_payouts(|payout| payout * factor)
+    }
+
+    fn map_payouts(&self, f: impl Fn(f64) -> f64) -> Self {
+        let mut distribution = Self::new();
+        for outcome in &self.outcomes {
+            distribution.add(f(outcome.payout), outcome.prob);
+        }
+        distribution
+    }
+
+    /// 两个独立结果之和的分布（卷积）
+    pub fn convolve(&self, other: &PayoutDistribution) -> Self {
+        let mut distribution = Self::new();
+        for a in &self.outcomes {
+            for b in &other.outcomes {
+                distribution.add(a.payout + b.payout, a.prob * b.prob);
+            }
+        }
+        distribution
+    }
+
+    /// 概率总和（完整的一局为 1）
+    pub fn total_prob(&self) -> f64 {
+        self.outcomes.iter().map(|outcome| outcome.prob).sum()
+    }
+
+    /// 某个净收益的概率
+    pub fn prob(&self, payout: f64) -> f64 {
+        self.outcomes
+            .iter()
+            .find(|outcome| (outcome.payout - payout).abs() <= PAYOUT_EPSILON)
+            .map_or(0.0, |outcome| outcome.prob)
+    }
+
+    /// 期望收益（EV）
+    pub fn ev(&self) -> f64 {
+        self.outcomes.iter().map(|outcome| outcome.payout * outcome.prob).sum()
+    }
+
+    /// `k` 阶中心矩
+    pub fn central_moment(&self, k: i32) -> f64 {
+        let ev = self.ev();
+        self.outcomes
+            .iter()
+            .map(|outcome| (outcome.payout - ev).powi(k) * outcome.prob)
+            .sum()
+    }
+
+    /// 方差
+    pub fn variance(&self) -> f64 {
+        self.central_moment(2)
+    }
+
+    /// 标准差
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    /// 偏度（三阶标准矩；方差为 0 时为 0）
+    pub fn skewness(&self) -> f64 {
+        let variance = self.variance();
+        if variance <= 0.0 {
+            return 0.0;
+        }
+        self.central_moment(3) / variance.powf(1.5)
+    }
+
+    /// 超额峰度（四阶标准矩减 3；方差为 0 时为 0）
+    pub fn excess_kurtosis(&self) -> f64 {
+        let variance = self.variance();
+        if variance <= 0.0 {
+            return 0.0;
+        }
+        self.central_moment(4) / (variance * variance) - 3.0
+    }
+
+    /// 净收益为负（输钱）的概率
+    pub fn loss_prob(&self) -> f64 {
+        self.prob_where(|payout| payout < -PAYOUT_EPSILON)
+    }
+
+    /// 净收益为 0 的概率
+    pub fn push_prob(&self) -> f64 {
+        self.prob_where(|payout| payout.abs() <= PAYOUT_EPSILON)
+    }
+
+    /// 净收益为正（赢钱）的概率
+    pub fn win_prob(&self) -> f64 {
+        self.prob_where(|payout| payout > PAYOUT_EPSILON)
+    }
+
+    fn prob_where(&self, f: impl Fn(f64) -> bool) -> f64 {
+        self.outcomes
+            .iter()
+            .filter(|outcome| f(outcome.payout))
+            .map(|outcome| outcome.prob)
+            .sum()
+    }
+
+    /// 累计概率达到 `level` 的最小净收益（如 `quantile(0.05)` 为 5% 风险价值对应的收益）
+    ///
+    /// 分布为空时返回 None
+    pub fn quantile(&self, level: f64) -> Option<f64> {
+        let target = level.clamp(0.0, 1.0) * self.total_prob();
+        let mut cumulative = 0.0;
+        for outcome in &self.outcomes {
+            cumulative += outcome.prob;
+            if cumulative >= target - PAYOUT_EPSILON {
+                return Some(outcome.payout);
+            }
+        }
+        self.outcomes.last().map(|outcome| outcome.payout)
+    }
+
+    /// 净收益不超过 `payout` 的概率
+    pub fn cdf(&self, payout: f64) -> f64 {
+        self.prob_where(|value| value <= payout + PAYOUT_EPSILON)
+    }
+}
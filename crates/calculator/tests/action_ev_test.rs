@@ -0,0 +1,74 @@
+//! 单个决策点的动作 EV 测试
+
+mod common;
+
+use calculator::{Action, Calculator, Card, CardCounts, GameRules};
+use common::{hand_of, deck_without};
+
+#[test]
+fn test_only_tens_deck_exact() {
+    // 剩余全是10点牌：玩家20对庄家10，庄家必然20
+    let mut deck = CardCounts::new();
+    deck.insert(Card::Face, 10);
+    
+    let hand = hand_of(&[Card::Face, Card::Face]);
+    let result = Calculator::with_default_rules().calculate_action_evs(&hand, Card::Face, &deck).unwrap();
+    
+    assert_eq!(result.get(Action::Stand), Some(0.0));
+    assert_eq!(result.get(Action::Hit), Some(-1.0));
+    assert_eq!(result.get(Action::Double), Some(-2.0));
+    assert_eq!(result.get(Action::Surrender), Some(-0.5));
+    // 分牌后每手都是20点，与庄家20平局
+    assert_eq!(result.get(Action::Split), Some(0.0));
+    // 停牌和分牌并列最优
+    assert_eq!(result.best().unwrap().ev, 0.0);
+}
+
+#[test]
+fn test_hard_11_vs_6_doubles() {
+    let hand = hand_of(&[Card::Number(6), Card::Number(5)]);
+    let deck = deck_without(&[Card::Number(6), Card::Number(5), Card::Number(6)]);
+    let result = Calculator::with_default_rules().calculate_action_evs(&hand, Card::Number(6), &deck).unwrap();
+    
+    assert_eq!(result.best().unwrap().action, Action::Double);
+    assert!(result.get(Action::Double).unwrap() > result.get(Action::Hit).unwrap());
+}
+
+#[test]
+fn test_hard_16_vs_10() {
+    let hand = hand_of(&[Card::Number(10), Card::Number(6)]);
+    let deck = deck_without(&[Card::Number(10), Card::Number(6), Card::Face]);
+    let result = Calculator::with_default_rules().calculate_action_evs(&hand, Card::Face, &deck).unwrap();
+    
+    // 16 对 10：投降优于要牌和停牌
+    assert_eq!(result.get(Action::Surrender), Some(-0.5));
+    assert_eq!(result.best().unwrap().action, Action::Surrender);
+    let stand = result.get(Action::Stand).unwrap();
+    assert!(stand < -0.5 && stand > -0.6, "16 对 10 停牌 EV 约为 -0.54，实际: {}", stand);
+}
+
+#[test]
+fn test_split_only_when_allowed() {
+    let hand = hand_of(&[Card::Number(8), Card::Number(8)]);
+    let deck = deck_without(&[Card::Number(8), Card::Number(8), Card::Number(6)]);
+    
+    let rules = GameRules {
+        allow_split: false,
+        ..GameRules::default()
+    };
+    let no_split = Calculator::new(rules).calculate_action_evs(&hand, Card::Number(6), &deck).unwrap();
+    assert_eq!(no_split.get(Action::Split), None);
+    
+    let with_split = Calculator::with_default_rules().calculate_action_evs(&hand, Card::Number(6), &deck).unwrap();
+    assert_eq!(with_split.best().unwrap().action, Action::Split);
+}
+
+#[test]
+fn test_multi_card_hand_only_hit_or_stand() {
+    let hand = hand_of(&[Card::Number(5), Card::Number(4), Card::Number(3)]);
+    let deck = deck_without(&[Card::Number(5), Card::Number(4), Card::Number(3), Card::Number(7)]);
+    let result = Calculator::with_default_rules().calculate_action_evs(&hand, Card::Number(7), &deck).unwrap();
+    
+    let actions: Vec<Action> = result.actions.iter().map(|a| a.action).collect();
+    assert_eq!(actions, vec![Action::Stand, Action::Hit]);
+}
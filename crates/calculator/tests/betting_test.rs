@@ -0,0 +1,111 @@
+//! Kelly 下注测试
+
+mod common;
+
+use calculator::betting::ADVANTAGE_PER_TRUE_COUNT;
+use calculator::{
+    create_full_deck, Calculator, CalculatorError, Card, CountAdvantage, GameRules, KellyBetting,
+    ShoeTracker,
+};
+use common::single_deck_rules;
+
+#[test]
+fn test_kelly_bet_rounding_and_limits() {
+    let kelly = KellyBetting::default();
+    // 10000 × 0.5 × 1% / 1.3 ≈ 38.46，向下取整到 5 的倍数
+    assert!((kelly.kelly_bet(0.01) - 10000.0 * 0.5 * 0.01 / 1.3).abs() < 1e-9);
+    assert_eq!(kelly.bet_for(0.01), 35.0);
+    // 没有优势时下最小注，优势很大时不超过最大注
+    assert_eq!(kelly.kelly_bet(-0.01), 0.0);
+    assert_eq!(kelly.bet_for(-0.01), 10.0);
+    assert_eq!(kelly.bet_for(0.001), 10.0);
+    assert_eq!(kelly.bet_for(0.1), 200.0);
+
+    // 完整 Kelly 是半 Kelly 的两倍，资金减半时下注减半
+    let full = KellyBetting {
+        kelly_fraction: 1.0,
+        chip_unit: 1.0,
+        ..kelly
+    };
+    assert_eq!(full.bet_for(0.01), 76.0);
+    assert_eq!(full.with_bankroll(5000.0).bet_for(0.01), 38.0);
+}
+
+#[test]
+fn test_true_count_advantage() {
+    let advantage = CountAdvantage::new(-0.005);
+    assert_eq!(advantage.per_true_count, ADVANTAGE_PER_TRUE_COUNT);
+    assert!(advantage.advantage(1.0).abs() < 1e-12);
+    assert!((advantage.advantage(3.0) - 0.01).abs() < 1e-12);
+
+    let kelly = KellyBetting::default();
+    assert_eq!(kelly.bet_for_true_count(0.0, &advantage), 10.0);
+    assert_eq!(kelly.bet_for_true_count(3.0, &advantage), kelly.bet_for(0.01));
+    assert!(kelly.bet_for_true_count(5.0, &advantage) > kelly.bet_for_true_count(3.0, &advantage));
+
+    // 由规则计算完整牌靴的上桌 EV
+    let rules = single_deck_rules();
+    let from_rules = CountAdvantage::from_rules(&rules).unwrap();
+    let full_shoe = Calculator::new(rules).calculate_table_ev(&create_full_deck(1)).unwrap();
+    assert_eq!(from_rules.base_ev, full_shoe.ev);
+    assert_eq!(
+        CountAdvantage::from_rules(&GameRules { deck_count: 0, ..GameRules::default() }).unwrap_err(),
+        CalculatorError::InvalidDeckCount(0)
+    );
+}
+
+#[test]
+fn test_validate() {
+    assert!(KellyBetting::default().validate().is_ok());
+    let invalid = [
+        KellyBetting { bankroll: -1.0, ..KellyBetting::default() },
+        KellyBetting { kelly_fraction: 0.0, ..KellyBetting::default() },
+        KellyBetting { kelly_fraction: 1.5, ..KellyBetting::default() },
+        KellyBetting { variance: 0.0, ..KellyBetting::default() },
+        KellyBetting { chip_unit: 0.0, ..KellyBetting::default() },
+        KellyBetting { min_bet: 500.0, ..KellyBetting::default() },
+        KellyBetting { max_bet: f64::NAN, ..KellyBetting::default() },
+    ];
+    for kelly in invalid {
+        assert!(
+            matches!(kelly.validate(), Err(CalculatorError::InvalidBetting(_))),
+            "{:?}",
+            kelly
+        );
+    }
+}
+
+#[test]
+fn test_tracker_kelly_bet() {
+    let kelly = KellyBetting {
+        bankroll: 1000.0,
+        chip_unit: 1.0,
+        min_bet: 5.0,
+        ..KellyBetting::default()
+    };
+    let mut tracker = ShoeTracker::new(single_deck_rules()).with_kelly_betting(kelly).unwrap();
+    assert_eq!(tracker.update().unwrap().recommended_bet, 5.0);
+
+    // 拿掉小牌后按上桌 EV 计算 Kelly 下注
+    for card in [2, 3, 4, 5, 6].map(Card::Number) {
+        for _ in 0..2 {
+            tracker.record(card).unwrap();
+        }
+    }
+    let update = tracker.update().unwrap();
+    assert!(update.table_ev.ev > 0.0);
+    assert_eq!(update.recommended_bet, kelly.bet_for_table_ev(&update.table_ev));
+    // 方差取该牌组的每局方差
+    let with_variance = KellyBetting { variance: update.table_ev.variance, ..kelly };
+    assert_eq!(update.recommended_bet, with_variance.bet_for(update.table_ev.ev));
+    assert!(update.recommended_bet > 5.0);
+
+    // 资金变化后下注随之变化
+    tracker.set_bankroll(2000.0);
+    let doubled = tracker.update().unwrap().recommended_bet;
+    assert_eq!(doubled, kelly.with_bankroll(2000.0).bet_for_table_ev(&update.table_ev));
+    assert!(doubled > update.recommended_bet);
+
+    let invalid = KellyBetting { variance: -1.0, ..KellyBetting::default() };
+    assert!(ShoeTracker::new(single_deck_rules()).with_kelly_betting(invalid).is_err());
+}
@@ -0,0 +1,76 @@
+//! 跨调用缓存测试：缓存键必须精确区分牌组，复用缓存的结果与全新计算一致
+
+mod common;
+
+use calculator::{create_full_8_deck, Calculator, Card, CardCounts, StrategyMode};
+use common::single_deck_no_split_rules;
+
+/// 单副牌
+fn single_deck() -> CardCounts {
+    let mut deck = CardCounts::new();
+    deck.insert(Card::Ace, 4);
+    for n in 2..=10 {
+        deck.insert(Card::Number(n), 4);
+    }
+    deck.insert(Card::Face, 12);
+    deck
+}
+
+#[test]
+fn test_rolling_hash_collision_is_distinguished() {
+    // 9 点多 1 张、十点牌少 31 张：两副牌组在 `hash × 31 + count` 滚动签名下相同
+    let mut deck = create_full_8_deck();
+    *deck.get_mut(&Card::Number(9)).unwrap() -= 1;
+    let mut other = deck.clone();
+    *other.get_mut(&Card::Number(9)).unwrap() += 1;
+    *other.get_mut(&Card::Face).unwrap() -= 31;
+
+    let calculator = Calculator::with_default_rules();
+    let first = calculator.dealer_distribution(None, &deck).unwrap();
+    let warm = calculator.dealer_distribution(None, &other).unwrap();
+    let fresh = Calculator::with_default_rules().dealer_distribution(None, &other).unwrap();
+
+    assert_eq!(warm, fresh, "复用缓存的分布应与全新计算完全一致");
+    assert!(
+        (first.bust - warm.bust).abs() > 1e-3,
+        "十点牌少 31 张时庄家爆牌率应明显不同: {} vs {}",
+        first.bust,
+        warm.bust
+    );
+}
+
+#[test]
+fn test_warm_cache_matches_fresh_calculation() {
+    for mode in [StrategyMode::Basic, StrategyMode::Optimal] {
+        let calculator = Calculator::with_strategy_mode(single_deck_no_split_rules(), mode);
+        calculator.calculate_table_ev(&single_deck()).unwrap();
+
+        // 发出一张 5 之后的牌组：大部分子牌组已在缓存中
+        let mut deck = single_deck();
+        *deck.get_mut(&Card::Number(5)).unwrap() -= 1;
+        let warm = calculator.calculate_table_ev(&deck).unwrap();
+        let fresh =
+            Calculator::with_strategy_mode(single_deck_no_split_rules(), mode).calculate_table_ev(&deck).unwrap();
+
+        assert_eq!(warm.ev, fresh.ev, "{:?} 模式下复用缓存的 EV 应与全新计算一致", mode);
+        assert_eq!(warm.player_win_prob, fresh.player_win_prob);
+        assert_eq!(warm.dealer_win_prob, fresh.dealer_win_prob);
+    }
+}
+
+#[test]
+fn test_cache_persists_and_clears() {
+    let calculator = Calculator::new(single_deck_no_split_rules());
+    assert_eq!(calculator.cache_size(), 0);
+
+    let first = calculator.calculate_table_ev(&single_deck()).unwrap();
+    let size = calculator.cache_size();
+    assert!(size > 0, "计算后应保留缓存");
+
+    let again = calculator.calculate_table_ev(&single_deck()).unwrap();
+    assert_eq!(calculator.cache_size(), size, "相同牌组的重复查询不应新增缓存条目");
+    assert_eq!(first.ev, again.ev);
+
+    calculator.clear_cache();
+    assert_eq!(calculator.cache_size(), 0);
+}
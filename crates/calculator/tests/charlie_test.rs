@@ -0,0 +1,120 @@
+//! Charlie 自动获胜规则测试
+
+mod common;
+
+use calculator::{
+    Action, Calculator, CalculatorError, Card, CharlieRule, GameRules, StrategyMode,
+    create_full_8_deck,
+};
+use common::{hand_of, deck_without};
+
+fn rules_with(charlie: Option<CharlieRule>) -> GameRules {
+    GameRules {
+        charlie,
+        ..GameRules::default()
+    }
+}
+
+#[test]
+fn test_charlie_rule() {
+    let charlie = CharlieRule::five_card();
+    let four_cards = hand_of(&[Card::Number(2), Card::Number(3), Card::Number(4), Card::Number(5)]);
+    let five_cards = hand_of(&[Card::Number(2), Card::Number(3), Card::Number(4), Card::Number(5), Card::Number(6)]);
+    let five_busted = hand_of(&[Card::Number(2), Card::Number(3), Card::Number(4), Card::Number(5), Card::Face]);
+
+    assert!(!charlie.is_charlie(&four_cards));
+    assert!(charlie.is_charlie(&five_cards));
+    assert!(!charlie.is_charlie(&five_busted));
+    assert_eq!(charlie.cards_needed(&four_cards), 1);
+    assert!(!CharlieRule::six_card().is_charlie(&five_cards));
+    assert!(!GameRules::default().is_charlie(&five_cards));
+
+    let invalid = rules_with(Some(CharlieRule { cards: 2, payout: 1.0 }));
+    assert_eq!(invalid.validate().unwrap_err(), CalculatorError::InvalidCharlieCards(2));
+    let invalid = rules_with(Some(CharlieRule { cards: 5, payout: 0.0 }));
+    assert_eq!(invalid.validate().unwrap_err(), CalculatorError::InvalidPayout(0.0));
+}
+
+#[test]
+fn test_charlie_changes_hit_stand() {
+    // 四张牌的硬16 对 10：一张不爆的牌即可自动获胜，要牌优于停牌
+    let cards = [Card::Number(2), Card::Number(3), Card::Number(4), Card::Number(7)];
+    let hand = hand_of(&cards);
+    let mut seen = cards.to_vec();
+    seen.push(Card::Face);
+    let deck = deck_without(&seen);
+
+    let without = Calculator::new(rules_with(None))
+        .calculate_action_evs(&hand, Card::Face, &deck)
+        .unwrap();
+    let with = Calculator::new(rules_with(Some(CharlieRule::five_card())))
+        .calculate_action_evs(&hand, Card::Face, &deck)
+        .unwrap();
+
+    assert_eq!(with.get(Action::Stand), without.get(Action::Stand));
+    assert!(with.get(Action::Hit).unwrap() > without.get(Action::Hit).unwrap());
+    assert_eq!(with.best().unwrap().action, Action::Hit);
+
+    // 四张牌的软19 再要一张不会爆牌，必然达到 Charlie：要牌 EV = 1
+    let cards = [Card::Ace, Card::Number(2), Card::Number(2), Card::Number(4)];
+    let hand = hand_of(&cards);
+    let mut seen = cards.to_vec();
+    seen.push(Card::Number(9));
+    let deck = deck_without(&seen);
+    let result = Calculator::new(rules_with(Some(CharlieRule::five_card())))
+        .calculate_action_evs(&hand, Card::Number(9), &deck)
+        .unwrap();
+    assert!((result.get(Action::Hit).unwrap() - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_basic_strategy_hits_toward_charlie() {
+    let charlie = Calculator::new(rules_with(Some(CharlieRule::five_card())));
+    let plain = Calculator::with_default_rules();
+    let deck = create_full_8_deck();
+
+    let soft_19 = hand_of(&[Card::Ace, Card::Number(2), Card::Number(2), Card::Number(4)]);
+    assert_eq!(plain.basic_action(&soft_19, Card::Number(9), 1, &deck), Ok(Action::Stand));
+    assert_eq!(charlie.basic_action(&soft_19, Card::Number(9), 1, &deck), Ok(Action::Hit));
+
+    let hard_17 = hand_of(&[Card::Number(2), Card::Number(3), Card::Number(4), Card::Number(8)]);
+    assert_eq!(charlie.basic_action(&hard_17, Card::Face, 1, &deck), Ok(Action::Hit));
+    assert_eq!(charlie.basic_action(&hard_17, Card::Number(7), 1, &deck), Ok(Action::Stand));
+
+    // 调整只按 1:1 赔率推导，其他赔率按普通基础策略
+    let two_to_one = Calculator::new(rules_with(Some(CharlieRule { payout: 2.0, ..CharlieRule::five_card() })));
+    assert_eq!(two_to_one.basic_action(&soft_19, Card::Number(9), 1, &deck), Ok(Action::Stand));
+    assert_eq!(two_to_one.basic_action(&hard_17, Card::Face, 1, &deck), Ok(Action::Stand));
+
+    // 还差两张牌时不调整
+    let three_card_soft_19 = hand_of(&[Card::Ace, Card::Number(4), Card::Number(4)]);
+    assert_eq!(charlie.basic_action(&three_card_soft_19, Card::Number(9), 1, &deck), Ok(Action::Stand));
+}
+
+#[test]
+fn test_charlie_raises_table_ev() {
+    let deck = create_full_8_deck();
+    let ev = |charlie, mode| {
+        Calculator::with_strategy_mode(rules_with(charlie), mode)
+            .calculate_table_ev(&deck)
+            .unwrap()
+    };
+
+    for mode in [StrategyMode::Basic, StrategyMode::Optimal] {
+        let none = ev(None, mode);
+        let six = ev(Some(CharlieRule::six_card()), mode);
+        let five = ev(Some(CharlieRule::five_card()), mode);
+        let five_double_pay = ev(Some(CharlieRule { cards: 5, payout: 2.0 }), mode);
+
+        assert_eq!(none.charlie_prob, 0.0);
+        assert!(six.charlie_prob > 0.0 && five.charlie_prob > six.charlie_prob);
+        assert!(none.ev < six.ev && six.ev < five.ev && five.ev < five_double_pay.ev);
+        // 五张 Charlie 约为玩家增加 1.5%
+        assert!(
+            (0.01..0.02).contains(&(five.ev - none.ev)),
+            "{:?}: 五张 Charlie 的增益: {}",
+            mode,
+            five.ev - none.ev
+        );
+    }
+}
@@ -0,0 +1,39 @@
+//! 集成测试共用的辅助函数
+
+#![allow(dead_code)]
+
+use calculator::{create_full_8_deck, CardCounts, Card, GameRules, Hand, PlayingCard, Suit};
+
+/// 用牌面创建手牌
+pub fn hand_of(cards: &[Card]) -> Hand {
+    let mut hand = Hand::new();
+    for &card in cards {
+        hand.add_card(PlayingCard { card, suit: Suit::Spades });
+    }
+    hand
+}
+
+/// 从完整 8 副牌中移除已见的牌
+pub fn deck_without(seen: &[Card]) -> CardCounts {
+    let mut deck = create_full_8_deck();
+    for card in seen {
+        *deck.get_mut(card).unwrap() -= 1;
+    }
+    deck
+}
+
+/// 单副牌的默认规则
+pub fn single_deck_rules() -> GameRules {
+    GameRules {
+        deck_count: 1,
+        ..GameRules::default()
+    }
+}
+
+/// 单副牌、不允许分牌的规则（保持测试快速）
+pub fn single_deck_no_split_rules() -> GameRules {
+    GameRules {
+        allow_split: false,
+        ..single_deck_rules()
+    }
+}
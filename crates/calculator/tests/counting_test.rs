@@ -0,0 +1,154 @@
+//! 算牌系统测试
+
+use calculator::counting::{decks_for_cards, decks_remaining};
+use calculator::{
+    create_full_deck, Calculator, CalculatorError, Card, CountSystem, GameRules, RunningCounter,
+};
+
+fn rules_with_decks(deck_count: u8) -> GameRules {
+    GameRules {
+        deck_count,
+        ..GameRules::default()
+    }
+}
+
+#[test]
+fn test_builtin_systems() {
+    for system in CountSystem::builtin() {
+        assert_eq!(CountSystem::by_name(&system.name).unwrap(), system);
+        // 除 KO 外都是平衡系统
+        assert_eq!(system.is_balanced(), system.name != "ko", "{}", system.name);
+    }
+    assert_eq!(CountSystem::by_name(" Hi-Lo ").unwrap(), CountSystem::hi_lo());
+    assert_eq!(
+        CountSystem::by_name("red-7").unwrap_err(),
+        CalculatorError::UnknownCountSystem("red-7".to_string())
+    );
+
+    let hi_lo = CountSystem::default();
+    assert_eq!(hi_lo.tag(Card::Number(2)).unwrap(), 1.0);
+    assert_eq!(hi_lo.tag(Card::Number(7)).unwrap(), 0.0);
+    assert_eq!(hi_lo.tag(Card::Number(10)).unwrap(), -1.0);
+    assert_eq!(hi_lo.tag(Card::Face).unwrap(), -1.0);
+    assert_eq!(hi_lo.tag(Card::Ace).unwrap(), -1.0);
+    assert_eq!(hi_lo.tag(Card::Number(11)).unwrap_err(), CalculatorError::InvalidRank(Card::Number(11)));
+
+    assert_eq!(CountSystem::wong_halves().tag(Card::Number(5)).unwrap(), 1.5);
+    assert_eq!(CountSystem::omega_2().tag(Card::Number(9)).unwrap(), -1.0);
+}
+
+#[test]
+fn test_ko_initial_running_count() {
+    let ko = CountSystem::ko();
+    assert_eq!(ko.imbalance(), 4.0);
+    assert_eq!(ko.initial_running_count(1), 0.0);
+    assert_eq!(ko.initial_running_count(6), -20.0);
+    assert_eq!(CountSystem::hi_lo().initial_running_count(6), 0.0);
+
+    // 两副牌从 -4 开始，发完一副牌（净增 4）后回到 0
+    let rules = rules_with_decks(2);
+    let mut counter = RunningCounter::new(ko, &rules);
+    assert_eq!(counter.running_count(), -4.0);
+    let one_deck: Vec<Card> = create_full_deck(1)
+        .into_iter()
+        .flat_map(|(card, count)| std::iter::repeat_n(card, count as usize))
+        .collect();
+    counter.count_all(one_deck).unwrap();
+    assert_eq!(counter.running_count(), 0.0);
+    assert_eq!(counter.decks_remaining(), 1.0);
+}
+
+#[test]
+fn test_running_count_from_deck_matches_stream() {
+    let rules = rules_with_decks(6);
+    let dealt = [
+        Card::Number(2),
+        Card::Number(5),
+        Card::Face,
+        Card::Number(6),
+        Card::Number(3),
+        Card::Ace,
+        Card::Number(9),
+        Card::Number(4),
+    ];
+    let mut remaining = create_full_deck(6);
+    for card in &dealt {
+        *remaining.get_mut(card).unwrap() -= 1;
+    }
+
+    for system in CountSystem::builtin() {
+        let mut counter = RunningCounter::new(system.clone(), &rules);
+        counter.count_all(dealt).unwrap();
+        let from_deck = system.running_count(&remaining, &rules).unwrap();
+        assert!((counter.running_count() - from_deck).abs() < 1e-12, "{}", system.name);
+        let true_count = system.true_count(&remaining, &rules).unwrap();
+        assert!((counter.true_count() - true_count).abs() < 1e-12, "{}", system.name);
+    }
+
+    // Hi-Lo：五张小牌、两张大牌，RC = +3，剩余 304 张
+    let mut counter = RunningCounter::new(CountSystem::hi_lo(), &rules);
+    counter.count_all(dealt).unwrap();
+    assert_eq!(counter.running_count(), 3.0);
+    assert_eq!(counter.cards_seen(), 8);
+    assert_eq!(counter.cards_remaining(), 304);
+    assert!((counter.true_count() - 3.0 / (304.0 / 52.0)).abs() < 1e-12);
+
+    counter.reset();
+    assert_eq!(counter.running_count(), 0.0);
+    assert_eq!(counter.cards_remaining(), 312);
+}
+
+#[test]
+fn test_decks_remaining() {
+    let rules = rules_with_decks(6);
+    assert_eq!(decks_remaining(&rules, 0), 6.0);
+    assert_eq!(decks_remaining(&rules, 78), 4.5);
+    assert_eq!(decks_remaining(&rules, 1000), 0.0);
+    assert_eq!(decks_for_cards(26), 0.5);
+
+    // 牌发完时 true count 仍然有限
+    let mut counter = RunningCounter::new(CountSystem::hi_lo(), &rules_with_decks(1));
+    counter.count_all(std::iter::repeat_n(Card::Number(5), 52)).unwrap();
+    assert!(counter.true_count().is_finite());
+}
+
+#[test]
+fn test_invalid_input() {
+    let rules = rules_with_decks(1);
+    let hi_lo = CountSystem::hi_lo();
+
+    let mut deck = create_full_deck(1);
+    deck.insert(Card::Number(5), 5);
+    assert!(matches!(
+        hi_lo.running_count(&deck, &rules),
+        Err(CalculatorError::ImpossibleComposition { .. })
+    ));
+
+    let mut counter = RunningCounter::new(hi_lo, &rules);
+    assert_eq!(counter.count(Card::Number(1)), Err(CalculatorError::InvalidRank(Card::Number(1))));
+    assert_eq!(counter.cards_seen(), 0);
+}
+
+#[test]
+fn test_custom_system_and_positive_count_favors_player() {
+    // 自定义系统：只计 5（+1）和 A（-1）
+    let tags = [-1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+    let system = CountSystem::new("ace-five", tags);
+    assert!(system.is_balanced());
+    assert_eq!(system.tag(Card::Number(5)).unwrap(), 1.0);
+
+    // 单副牌发出 8 张小牌：Hi-Lo true count 为正，上桌 EV 高于完整牌靴
+    let rules = rules_with_decks(1);
+    let mut deck = create_full_deck(1);
+    for card in [Card::Number(4), Card::Number(5), Card::Number(6)] {
+        *deck.get_mut(&card).unwrap() -= 2;
+    }
+    *deck.get_mut(&Card::Number(3)).unwrap() -= 2;
+    let true_count = CountSystem::hi_lo().true_count(&deck, &rules).unwrap();
+    assert!((true_count - 8.0 / (44.0 / 52.0)).abs() < 1e-12);
+
+    let calculator = Calculator::new(rules.clone());
+    let full = calculator.calculate_table_ev(&create_full_deck(1)).unwrap();
+    let depleted = calculator.calculate_table_ev(&deck).unwrap();
+    assert!(depleted.ev > full.ev + 0.02);
+}
@@ -6,9 +6,17 @@
 //! - 高牌（10, J, Q, K）较少：玩家不容易拿到黑杰克
 //! - A 较少：玩家不容易拿到黑杰克
 
-use calculator::{Calculator, Card, CardCounts};
+use calculator::{Calculator, Card, CardCounts, GameRules};
 use calculator::create_full_8_deck;
 
+/// 创建计算器（偏斜牌组中某些牌超过 8 副牌的张数，按 16 副牌检查牌组）
+fn skewed_deck_calculator() -> Calculator {
+    Calculator::new(GameRules {
+        deck_count: 16,
+        ..GameRules::default()
+    })
+}
+
 /// 创建低牌较多的牌组（对庄家有利）
 /// 低牌多意味着庄家不容易爆牌（因为庄家必须 <17 要牌）
 fn create_low_card_rich_deck() -> CardCounts {
@@ -101,9 +109,9 @@ fn create_only_low_and_mid_deck() -> CardCounts {
 #[test]
 fn test_low_card_rich_deck() {
     let deck = create_low_card_rich_deck();
-    let calculator = Calculator::with_default_rules();
+    let calculator = skewed_deck_calculator();
     
-    let result = calculator.calculate_table_ev(&deck);
+    let result = calculator.calculate_table_ev(&deck).unwrap();
     
     println!("低牌较多牌组 EV: {:.6}", result.ev);
     println!("玩家黑杰克率: {:.6}", result.player_blackjack_prob);
@@ -120,13 +128,14 @@ fn test_low_card_rich_deck() {
         + result.push_prob
         + result.player_blackjack_prob
         + result.dealer_blackjack_prob
-        + result.surrender_prob;
+        + result.surrender_prob
+        + result.split_prob;
     
     assert!((total_prob - 1.0).abs() < 0.01, "概率总和应该接近1，实际: {}", total_prob);
     
     // 低牌多时，EV 应该比满8副牌更差（更负）
     let full_deck = create_full_8_deck();
-    let full_result = calculator.calculate_table_ev(&full_deck);
+    let full_result = calculator.calculate_table_ev(&full_deck).unwrap();
     
     println!("满8副牌 EV: {:.6}", full_result.ev);
     assert!(result.ev < full_result.ev, 
@@ -137,9 +146,9 @@ fn test_low_card_rich_deck() {
 #[test]
 fn test_high_card_poor_deck() {
     let deck = create_high_card_poor_deck();
-    let calculator = Calculator::with_default_rules();
+    let calculator = skewed_deck_calculator();
     
-    let result = calculator.calculate_table_ev(&deck);
+    let result = calculator.calculate_table_ev(&deck).unwrap();
     
     println!("高牌较少牌组 EV: {:.6}", result.ev);
     println!("玩家黑杰克率: {:.6}", result.player_blackjack_prob);
@@ -155,13 +164,14 @@ fn test_high_card_poor_deck() {
         + result.push_prob
         + result.player_blackjack_prob
         + result.dealer_blackjack_prob
-        + result.surrender_prob;
+        + result.surrender_prob
+        + result.split_prob;
     
     assert!((total_prob - 1.0).abs() < 0.01, "概率总和应该接近1，实际: {}", total_prob);
     
     // 高牌少时，EV 应该比满8副牌更差
     let full_deck = create_full_8_deck();
-    let full_result = calculator.calculate_table_ev(&full_deck);
+    let full_result = calculator.calculate_table_ev(&full_deck).unwrap();
     
     assert!(result.ev < full_result.ev, 
         "高牌少时 EV 应该比满8副牌更差。高牌少牌组 EV: {}, 满8副牌 EV: {}", 
@@ -171,9 +181,9 @@ fn test_high_card_poor_deck() {
 #[test]
 fn test_ace_poor_deck() {
     let deck = create_ace_poor_deck();
-    let calculator = Calculator::with_default_rules();
+    let calculator = skewed_deck_calculator();
     
-    let result = calculator.calculate_table_ev(&deck);
+    let result = calculator.calculate_table_ev(&deck).unwrap();
     
     println!("A较少牌组 EV: {:.6}", result.ev);
     println!("玩家黑杰克率: {:.6}", result.player_blackjack_prob);
@@ -188,13 +198,14 @@ fn test_ace_poor_deck() {
         + result.push_prob
         + result.player_blackjack_prob
         + result.dealer_blackjack_prob
-        + result.surrender_prob;
+        + result.surrender_prob
+        + result.split_prob;
     
     assert!((total_prob - 1.0).abs() < 0.01, "概率总和应该接近1，实际: {}", total_prob);
     
     // A 少时，EV 应该比满8副牌更差
     let full_deck = create_full_8_deck();
-    let full_result = calculator.calculate_table_ev(&full_deck);
+    let full_result = calculator.calculate_table_ev(&full_deck).unwrap();
     
     assert!(result.ev < full_result.ev, 
         "A少时 EV 应该比满8副牌更差。A少牌组 EV: {}, 满8副牌 EV: {}", 
@@ -204,9 +215,9 @@ fn test_ace_poor_deck() {
 #[test]
 fn test_extremely_unfavorable_deck() {
     let deck = create_extremely_unfavorable_deck();
-    let calculator = Calculator::with_default_rules();
+    let calculator = skewed_deck_calculator();
     
-    let result = calculator.calculate_table_ev(&deck);
+    let result = calculator.calculate_table_ev(&deck).unwrap();
     
     println!("极端不利牌组 EV: {:.6}", result.ev);
     println!("玩家黑杰克率: {:.6}", result.player_blackjack_prob);
@@ -221,13 +232,14 @@ fn test_extremely_unfavorable_deck() {
         + result.push_prob
         + result.player_blackjack_prob
         + result.dealer_blackjack_prob
-        + result.surrender_prob;
+        + result.surrender_prob
+        + result.split_prob;
     
     assert!((total_prob - 1.0).abs() < 0.01, "概率总和应该接近1，实际: {}", total_prob);
     
     // 与满8副牌对比
     let full_deck = create_full_8_deck();
-    let full_result = calculator.calculate_table_ev(&full_deck);
+    let full_result = calculator.calculate_table_ev(&full_deck).unwrap();
     
     println!("满8副牌 EV: {:.6}", full_result.ev);
     assert!(result.ev < full_result.ev, 
@@ -246,9 +258,9 @@ fn test_extremely_unfavorable_deck() {
 #[test]
 fn test_only_low_and_mid_deck() {
     let deck = create_only_low_and_mid_deck();
-    let calculator = Calculator::with_default_rules();
+    let calculator = skewed_deck_calculator();
     
-    let result = calculator.calculate_table_ev(&deck);
+    let result = calculator.calculate_table_ev(&deck).unwrap();
     
     println!("只有低牌和中牌的牌组 EV: {:.6}", result.ev);
     println!("玩家黑杰克率: {:.6}", result.player_blackjack_prob);
@@ -266,13 +278,14 @@ fn test_only_low_and_mid_deck() {
         + result.push_prob
         + result.player_blackjack_prob
         + result.dealer_blackjack_prob
-        + result.surrender_prob;
+        + result.surrender_prob
+        + result.split_prob;
     
     assert!((total_prob - 1.0).abs() < 0.01, "概率总和应该接近1，实际: {}", total_prob);
     
     // 没有高牌和A时，EV 应该比满8副牌更差
     let full_deck = create_full_8_deck();
-    let full_result = calculator.calculate_table_ev(&full_deck);
+    let full_result = calculator.calculate_table_ev(&full_deck).unwrap();
     
     assert!(result.ev < full_result.ev, 
         "只有低牌和中牌时 EV 应该比满8副牌更差。只有低牌和中牌牌组 EV: {}, 满8副牌 EV: {}", 
@@ -295,8 +308,8 @@ fn test_balanced_unfavorable_deck() {
         deck.insert(Card::Number(n), 35);  // 较多
     }
     
-    let calculator = Calculator::with_default_rules();
-    let result = calculator.calculate_table_ev(&deck);
+    let calculator = skewed_deck_calculator();
+    let result = calculator.calculate_table_ev(&deck).unwrap();
     
     println!("平衡不利牌组 EV: {:.6}", result.ev);
     
@@ -306,13 +319,14 @@ fn test_balanced_unfavorable_deck() {
         + result.push_prob
         + result.player_blackjack_prob
         + result.dealer_blackjack_prob
-        + result.surrender_prob;
+        + result.surrender_prob
+        + result.split_prob;
     
     assert!((total_prob - 1.0).abs() < 0.01, "概率总和应该接近1，实际: {}", total_prob);
     
     // 应该比满8副牌更差
     let full_deck = create_full_8_deck();
-    let full_result = calculator.calculate_table_ev(&full_deck);
+    let full_result = calculator.calculate_table_ev(&full_deck).unwrap();
     
     assert!(result.ev < full_result.ev, 
         "平衡不利牌组 EV 应该比满8副牌更差。平衡不利牌组 EV: {}, 满8副牌 EV: {}", 
@@ -350,9 +364,9 @@ fn test_comparison_favorable_vs_unfavorable() {
         deck
     };
     
-    let calculator = Calculator::with_default_rules();
-    let favorable_result = calculator.calculate_table_ev(&favorable_deck);
-    let unfavorable_result = calculator.calculate_table_ev(&unfavorable_deck);
+    let calculator = skewed_deck_calculator();
+    let favorable_result = calculator.calculate_table_ev(&favorable_deck).unwrap();
+    let unfavorable_result = calculator.calculate_table_ev(&unfavorable_deck).unwrap();
     
     println!("有利牌组 EV: {:.6}", favorable_result.ev);
     println!("不利牌组 EV: {:.6}", unfavorable_result.ev);
@@ -0,0 +1,91 @@
+//! 庄家最终点数分布测试
+
+use calculator::{create_full_8_deck, Calculator, Card, CardCounts, GameRules};
+
+/// 从牌组中移除一张牌
+fn deck_without(mut deck: CardCounts, card: Card) -> CardCounts {
+    if let Some(count) = deck.get_mut(&card) {
+        *count -= 1;
+    }
+    deck
+}
+
+#[test]
+fn test_distribution_sums_to_one() {
+    let calculator = Calculator::with_default_rules();
+    let mut up_cards = vec![Card::Ace, Card::Face];
+    up_cards.extend((2..=10).map(Card::Number));
+
+    for up_card in up_cards {
+        let deck = deck_without(create_full_8_deck(), up_card);
+        let dist = calculator.dealer_distribution(Some(up_card), &deck).unwrap();
+        assert!(
+            (dist.total_prob() - 1.0).abs() < 1e-9,
+            "明牌 {:?} 的分布总和应为1，实际: {}",
+            up_card,
+            dist.total_prob()
+        );
+        assert!((dist.without_blackjack().total_prob() - 1.0).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_blackjack_only_with_ace_or_ten_up() {
+    let calculator = Calculator::with_default_rules();
+
+    let deck = deck_without(create_full_8_deck(), Card::Number(6));
+    let dist = calculator.dealer_distribution(Some(Card::Number(6)), &deck).unwrap();
+    assert_eq!(dist.blackjack, 0.0);
+
+    // 8副牌明牌A时，底牌为10点牌的概率 = 128 / 415
+    let deck = deck_without(create_full_8_deck(), Card::Ace);
+    let dist = calculator.dealer_distribution(Some(Card::Ace), &deck).unwrap();
+    assert!((dist.blackjack - 128.0 / 415.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_soft_17_rule_changes_distribution() {
+    let deck = deck_without(create_full_8_deck(), Card::Number(6));
+
+    let s17 = Calculator::new(GameRules {
+        dealer_stands_on_soft_17: true,
+        ..GameRules::default()
+    })
+    .dealer_distribution(Some(Card::Number(6)), &deck).unwrap();
+    let h17 = Calculator::new(GameRules {
+        dealer_stands_on_soft_17: false,
+        ..GameRules::default()
+    })
+    .dealer_distribution(Some(Card::Number(6)), &deck).unwrap();
+
+    // 软17继续要牌：停在17点变少，爆牌变多
+    assert!(h17.total_17 < s17.total_17);
+    assert!(h17.bust > s17.bust);
+    // 明牌6，8副牌 S17 爆牌率约 42%
+    assert!(s17.bust > 0.41 && s17.bust < 0.43, "S17 明牌6爆牌率: {}", s17.bust);
+}
+
+#[test]
+fn test_exact_tiny_deck() {
+    // 明牌7，剩余全是10点牌：庄家必然停在17点
+    let mut deck = CardCounts::new();
+    deck.insert(Card::Face, 5);
+
+    let dist = Calculator::with_default_rules().dealer_distribution(Some(Card::Number(7)), &deck).unwrap();
+    assert!((dist.total_17 - 1.0).abs() < 1e-12);
+    assert_eq!(dist.bust, 0.0);
+}
+
+#[test]
+fn test_unknown_up_card() {
+    let rules = GameRules {
+        dealer_stands_on_soft_17: true,
+        ..GameRules::default()
+    };
+    let dist = Calculator::new(rules).dealer_distribution(None, &create_full_8_deck()).unwrap();
+
+    assert!((dist.total_prob() - 1.0).abs() < 1e-9);
+    // 8副牌 S17 庄家整体爆牌率约 28%，黑杰克约 4.7%
+    assert!(dist.bust > 0.27 && dist.bust < 0.30, "整体爆牌率: {}", dist.bust);
+    assert!((dist.blackjack - 0.0474).abs() < 0.002, "黑杰克概率: {}", dist.blackjack);
+}
@@ -0,0 +1,177 @@
+//! 指数打法（Illustrious 18、Fab 4）测试
+
+mod common;
+
+use calculator::strategy::BasicStrategy;
+use calculator::{
+    Action, Calculator, CalculatorError, Card, CountSystem, Deviation, DeviationTable, DoubleRule,
+    GameRules, StrategyMode, SurrenderRule, create_full_deck,
+};
+use common::hand_of;
+
+#[test]
+fn test_builtin_tables() {
+    let illustrious_18 = DeviationTable::illustrious_18();
+    // 17 条打法 + 保险
+    assert_eq!(illustrious_18.len(), 17);
+    assert_eq!(illustrious_18.insurance_index, Some(3.0));
+    assert!(illustrious_18.takes_insurance(3.0));
+    assert!(!illustrious_18.takes_insurance(2.9));
+    assert_eq!(illustrious_18.system, CountSystem::hi_lo());
+
+    assert_eq!(DeviationTable::fab_4().len(), 4);
+    assert_eq!(DeviationTable::fab_4().insurance_index, None);
+    assert_eq!(DeviationTable::illustrious_18_fab_4().len(), 21);
+
+    for name in DeviationTable::BUILTIN_NAMES {
+        assert!(!DeviationTable::by_name(name).unwrap().is_empty());
+    }
+    assert_eq!(
+        DeviationTable::by_name("wong-22").unwrap_err(),
+        CalculatorError::UnknownDeviationTable("wong-22".to_string())
+    );
+}
+
+#[test]
+fn test_deviation_thresholds() {
+    let table = DeviationTable::illustrious_18();
+    let strategy = BasicStrategy::new();
+    let action = |cards: &[Card], up, true_count| {
+        table.get_action(&strategy, &hand_of(cards), up, true_count)
+    };
+
+    // 三张牌的硬16 对 10：true count 0 及以上停牌
+    let three_card_16 = [Card::Number(4), Card::Number(5), Card::Number(7)];
+    assert_eq!(action(&three_card_16, Card::Face, 0.0), Action::Stand);
+    assert_eq!(action(&three_card_16, Card::Face, -0.5), Action::Hit);
+
+    // 硬12 对 4：true count 低于 0 时要牌
+    let hard_12 = [Card::Face, Card::Number(2)];
+    assert_eq!(action(&hard_12, Card::Number(4), 1.0), Action::Stand);
+    assert_eq!(action(&hard_12, Card::Number(4), -1.0), Action::Hit);
+
+    // 10-10 对 6：true count 4 及以上分牌
+    let tens = [Card::Face, Card::Number(10)];
+    assert_eq!(action(&tens, Card::Number(6), 4.0), Action::Split);
+    assert_eq!(action(&tens, Card::Number(6), 3.0), Action::Stand);
+    // 不是对子的 20 点不分牌
+    let three_card_20 = [Card::Number(9), Card::Number(5), Card::Number(6)];
+    assert_eq!(action(&three_card_20, Card::Number(6), 6.0), Action::Stand);
+
+    // 10 对 10：两张牌 true count 4 及以上加倍，三张牌不能加倍
+    assert_eq!(action(&[Card::Number(6), Card::Number(4)], Card::Face, 4.0), Action::Double);
+    assert_eq!(action(&[Card::Number(6), Card::Number(4)], Card::Face, 3.0), Action::Hit);
+    let three_card_10 = [Card::Number(3), Card::Number(3), Card::Number(4)];
+    assert_eq!(action(&three_card_10, Card::Face, 4.0), Action::Hit);
+}
+
+#[test]
+fn test_surrender_precedence() {
+    let strategy = BasicStrategy::new();
+    let hard_16 = hand_of(&[Card::Face, Card::Number(6)]);
+    let eights = hand_of(&[Card::Number(8), Card::Number(8)]);
+    let hard_15 = hand_of(&[Card::Face, Card::Number(5)]);
+    let hard_14 = hand_of(&[Card::Face, Card::Number(4)]);
+
+    // 停牌指数不改变基础策略的投降和分牌
+    let illustrious_18 = DeviationTable::illustrious_18();
+    assert_eq!(illustrious_18.get_action(&strategy, &hard_16, Card::Face, 2.0), Action::Surrender);
+    assert_eq!(illustrious_18.get_action(&strategy, &eights, Card::Face, 2.0), Action::Split);
+
+    // Fab 4：硬15 对 10 在 true count 低于 0 时不投降，硬14 对 10 在 +3 及以上投降
+    let fab_4 = DeviationTable::fab_4();
+    assert_eq!(fab_4.get_action(&strategy, &hard_15, Card::Face, 0.0), Action::Surrender);
+    assert_eq!(fab_4.get_action(&strategy, &hard_15, Card::Face, -1.0), Action::Hit);
+    assert_eq!(fab_4.get_action(&strategy, &hard_14, Card::Face, 3.0), Action::Surrender);
+    assert_eq!(fab_4.get_action(&strategy, &hard_14, Card::Face, 2.0), Action::Hit);
+    assert_eq!(fab_4.get_action(&strategy, &hard_15, Card::Ace, 1.0), Action::Surrender);
+
+    // 合并表：不投降时按 I18 的停牌指数
+    let combined = DeviationTable::illustrious_18_fab_4();
+    assert_eq!(combined.get_action(&strategy, &hard_15, Card::Face, 4.0), Action::Surrender);
+    let three_card_15 = hand_of(&[Card::Number(5), Card::Number(5), Card::Number(5)]);
+    assert_eq!(combined.get_action(&strategy, &three_card_15, Card::Face, 4.0), Action::Stand);
+}
+
+#[test]
+fn test_index_action_respects_rules() {
+    let rules = |surrender, double| GameRules {
+        surrender,
+        double,
+        ..GameRules::default()
+    };
+    let hard_14 = hand_of(&[Card::Face, Card::Number(4)]);
+    let hard_9 = hand_of(&[Card::Number(5), Card::Number(4)]);
+    let deck = create_full_deck(8);
+
+    let late = Calculator::new(rules(SurrenderRule::Late, DoubleRule::AnyTwoCards))
+        .with_deviations(DeviationTable::illustrious_18_fab_4());
+    assert_eq!(late.index_action(&hard_14, Card::Face, 1, &deck, 3.0), Ok(Action::Surrender));
+    assert_eq!(late.index_action(&hard_9, Card::Number(2), 1, &deck, 1.0), Ok(Action::Double));
+    // 分牌后的手牌不能投降
+    assert_eq!(late.index_action(&hard_14, Card::Face, 2, &deck, 3.0), Ok(Action::Hit));
+    // 没有设置指数打法时与基础策略相同
+    let plain = Calculator::new(rules(SurrenderRule::Late, DoubleRule::AnyTwoCards));
+    assert_eq!(plain.index_action(&hard_14, Card::Face, 1, &deck, 3.0), Ok(Action::Hit));
+
+    let restricted = Calculator::new(rules(SurrenderRule::None, DoubleRule::TenToEleven))
+        .with_deviations(DeviationTable::illustrious_18_fab_4());
+    assert_eq!(restricted.index_action(&hard_14, Card::Face, 1, &deck, 3.0), Ok(Action::Hit));
+    assert_eq!(restricted.index_action(&hard_9, Card::Number(2), 1, &deck, 1.0), Ok(Action::Hit));
+}
+
+#[test]
+fn test_deviations_in_engine() {
+    let rules = GameRules {
+        deck_count: 1,
+        ..GameRules::default()
+    };
+    let deck = create_full_deck(1);
+    let basic = Calculator::new(rules.clone()).calculate_table_ev(&deck).unwrap();
+
+    // 空表与基础策略完全相同
+    let empty = Calculator::new(rules.clone())
+        .with_deviations(DeviationTable::new(CountSystem::hi_lo()))
+        .calculate_table_ev(&deck)
+        .unwrap();
+    assert_eq!(empty.ev, basic.ev);
+
+    // 低牌大量发出的牌组：指数打法优于基础策略，且不超过最优策略
+    let mut rich = create_full_deck(1);
+    for card in [Card::Number(2), Card::Number(3), Card::Number(4), Card::Number(5), Card::Number(6)] {
+        *rich.get_mut(&card).unwrap() -= 2;
+    }
+    let basic = Calculator::new(rules.clone()).calculate_table_ev(&rich).unwrap();
+    let index = Calculator::new(rules.clone())
+        .with_deviations(DeviationTable::illustrious_18_fab_4())
+        .calculate_table_ev(&rich)
+        .unwrap();
+    let optimal = Calculator::with_strategy_mode(rules.clone(), StrategyMode::Optimal)
+        .calculate_table_ev(&rich)
+        .unwrap();
+    assert!(index.ev > basic.ev, "{} vs {}", index.ev, basic.ev);
+    assert!(index.ev <= optimal.ev);
+
+    // 最优策略模式不受影响
+    let optimal_with_table = Calculator::with_strategy_mode(rules, StrategyMode::Optimal)
+        .with_deviations(DeviationTable::illustrious_18())
+        .calculate_table_ev(&rich)
+        .unwrap();
+    assert_eq!(optimal_with_table.ev, optimal.ev);
+}
+
+#[test]
+fn test_custom_deviation() {
+    // 软18 对 A 在 true count 2 及以上停牌
+    let table = DeviationTable::new(CountSystem::hi_lo()).with_deviations([Deviation {
+        soft: true,
+        ..Deviation::at_or_above(18, 11, 2.0, Action::Stand)
+    }]);
+    let strategy = BasicStrategy::new();
+    let soft_18 = hand_of(&[Card::Ace, Card::Number(7)]);
+    assert_eq!(table.get_action(&strategy, &soft_18, Card::Ace, 2.0), Action::Stand);
+    assert_eq!(
+        table.get_action(&strategy, &soft_18, Card::Ace, 1.0),
+        strategy.get_action(&soft_18, Card::Ace)
+    );
+}
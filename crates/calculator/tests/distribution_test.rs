@@ -0,0 +1,159 @@
+//! 每局净收益分布测试
+
+mod common;
+
+use calculator::{
+    create_full_deck, Calculator, Card, CardCounts, CharlieRule, GameRules, HoleCardRule,
+    InsurancePolicy, PayoutDistribution, StrategyMode, SurrenderRule,
+};
+use common::single_deck_rules;
+
+/// 分布的总概率、EV 和方差与上桌 EV 的结果一致
+fn assert_matches_table_ev(calculator: &Calculator, deck: &CardCounts) -> PayoutDistribution {
+    let table_ev = calculator.calculate_table_ev(deck).unwrap();
+    let distribution = calculator.calculate_payout_distribution(deck).unwrap();
+    assert!((distribution.total_prob() - 1.0).abs() < 1e-12, "{}", distribution.total_prob());
+    assert!((distribution.ev() - table_ev.ev).abs() < 1e-12, "{} vs {}", distribution.ev(), table_ev.ev);
+    assert!(
+        (distribution.variance() - table_ev.variance).abs() < 1e-10,
+        "{} vs {}",
+        distribution.variance(),
+        table_ev.variance
+    );
+    distribution
+}
+
+#[test]
+fn test_default_rules_distribution() {
+    let calculator = Calculator::new(single_deck_rules());
+    let distribution = assert_matches_table_ev(&calculator, &create_full_deck(1));
+
+    // 不分牌时只有 ±2（加倍）、±1、-0.5（投降）、0 和 +1.5（黑杰克）
+    let payouts: Vec<f64> = distribution.outcomes.iter().map(|outcome| outcome.payout).collect();
+    assert_eq!(payouts, vec![-2.0, -1.0, -0.5, 0.0, 1.0, 1.5, 2.0]);
+    assert!(distribution.outcomes.iter().all(|outcome| outcome.prob > 0.0));
+    assert!(distribution.prob(1.5) > 0.04 && distribution.prob(1.5) < 0.05);
+
+    let total = distribution.loss_prob() + distribution.push_prob() + distribution.win_prob();
+    assert!((total - 1.0).abs() < 1e-12);
+    assert!(distribution.loss_prob() > distribution.win_prob());
+    assert_eq!(distribution.quantile(0.0), Some(-2.0));
+    assert_eq!(distribution.quantile(0.3), Some(-1.0));
+    assert_eq!(distribution.quantile(1.0), Some(2.0));
+    assert!((distribution.cdf(0.0) - distribution.loss_prob() - distribution.push_prob()).abs() < 1e-12);
+    assert_eq!(distribution.std_dev(), distribution.variance().sqrt());
+}
+
+#[test]
+fn test_split_and_surrender_distribution() {
+    let rules = GameRules {
+        surrender: SurrenderRule::Late,
+        ..GameRules::preset("strip-s17").unwrap()
+    };
+    for mode in [StrategyMode::Basic, StrategyMode::Optimal] {
+        let rules = GameRules {
+            deck_count: 1,
+            ..rules.clone()
+        };
+        let calculator = Calculator::with_strategy_mode(rules, mode);
+        let distribution = assert_matches_table_ev(&calculator, &create_full_deck(1));
+
+        // 投降 -0.5；分牌后加倍可以输赢 3 个以上单位
+        assert!(distribution.prob(-0.5) > 0.0);
+        assert!(distribution.prob(-3.0) > 0.0);
+        assert!(distribution.prob(4.0) > 0.0);
+        assert!(distribution.outcomes.windows(2).all(|pair| pair[0].payout < pair[1].payout));
+    }
+}
+
+#[test]
+fn test_hole_card_rules_distribution() {
+    for hole_card in [
+        HoleCardRule::Peek,
+        HoleCardRule::NoHoleCard,
+        HoleCardRule::NoHoleCardOriginalBetsOnly,
+    ] {
+        let rules = GameRules {
+            deck_count: 1,
+            allow_split: true,
+            hole_card,
+            charlie: Some(CharlieRule::five_card()),
+            ..GameRules::default()
+        };
+        assert_matches_table_ev(&Calculator::new(rules), &create_full_deck(1));
+    }
+}
+
+#[test]
+fn test_six_to_five_payout() {
+    let rules = GameRules {
+        blackjack_payout: 1.2,
+        ..single_deck_rules()
+    };
+    let distribution = assert_matches_table_ev(&Calculator::new(rules), &create_full_deck(1));
+    assert_eq!(distribution.prob(1.5), 0.0);
+    assert!(distribution.prob(1.2) > 0.04);
+}
+
+#[test]
+fn test_insurance_distribution() {
+    // 拿掉全部小牌后十点牌占一半，保险 EV 为正
+    let mut deck = create_full_deck(1);
+    for n in 2..=6 {
+        deck.remove(&Card::Number(n));
+    }
+    let calculator =
+        Calculator::new(single_deck_rules()).with_insurance_policy(InsurancePolicy::WhenPositive);
+    let distribution = assert_matches_table_ev(&calculator, &deck);
+
+    // 买保险后庄家黑杰克只输 0（原始投注输 1，保险赢 1），没有黑杰克时多输 0.5
+    assert!(distribution.prob(-1.5) > 0.0);
+    assert!(distribution.prob(0.5) > 0.0);
+}
+
+#[test]
+fn test_distribution_metrics() {
+    let mut distribution = PayoutDistribution::single(-1.0, 0.5);
+    distribution.add(1.0, 0.25);
+    distribution.add(3.0, 0.25);
+    distribution.add(1.0 + 1e-12, 0.0);
+
+    assert_eq!(distribution.outcomes.len(), 3);
+    assert_eq!(distribution.ev(), 0.5);
+    assert_eq!(distribution.variance(), 2.75);
+    assert!(distribution.skewness() > 0.0);
+    assert_eq!(distribution.quantile(0.5), Some(-1.0));
+    assert_eq!(distribution.quantile(0.6), Some(1.0));
+
+    let doubled = distribution.scaled(2.0);
+    assert_eq!(doubled.ev(), 1.0);
+    let insured = distribution.shifted(-0.5);
+    assert_eq!(insured.ev(), 0.0);
+
+    // 两个独立结果之和
+    let sum = distribution.convolve(&distribution);
+    assert!((sum.total_prob() - 1.0).abs() < 1e-15);
+    assert_eq!(sum.ev(), 1.0);
+    assert_eq!(sum.variance(), 5.5);
+    assert_eq!(sum.prob(-2.0), 0.25);
+
+    assert_eq!(PayoutDistribution::new().quantile(0.5), None);
+    assert_eq!(PayoutDistribution::single(0.0, 1.0).skewness(), 0.0);
+}
+
+#[test]
+fn test_serialization_round_trip() {
+    let calculator = Calculator::new(single_deck_rules());
+    let distribution = calculator.calculate_payout_distribution(&create_full_deck(1)).unwrap();
+    let json = serde_json::to_string(&distribution).unwrap();
+    let parsed: PayoutDistribution = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.outcomes.len(), distribution.outcomes.len());
+    for (a, b) in parsed.outcomes.iter().zip(&distribution.outcomes) {
+        assert_eq!(a.payout, b.payout);
+        assert!((a.prob - b.prob).abs() < 1e-15);
+    }
+
+    let mut deck = CardCounts::new();
+    deck.insert(Card::Face, 3);
+    assert!(calculator.calculate_payout_distribution(&deck).is_err());
+}
@@ -0,0 +1,108 @@
+//! 加倍限制规则测试（任意两张 / 9-11 / 10-11 / 任意张数）
+
+mod common;
+
+use calculator::{Action, Calculator, Card, DoubleRule, GameRules, StrategyMode, create_full_8_deck};
+use common::{hand_of, deck_without};
+
+fn rules_with(double: DoubleRule) -> GameRules {
+    GameRules {
+        double,
+        ..GameRules::default()
+    }
+}
+
+#[test]
+fn test_double_rule_allows() {
+    let hard_9 = hand_of(&[Card::Number(5), Card::Number(4)]);
+    let hard_10 = hand_of(&[Card::Number(6), Card::Number(4)]);
+    let soft_17 = hand_of(&[Card::Ace, Card::Number(6)]);
+    let three_card_11 = hand_of(&[Card::Number(2), Card::Number(3), Card::Number(6)]);
+
+    assert!(DoubleRule::AnyTwoCards.allows(&hard_9));
+    assert!(DoubleRule::AnyTwoCards.allows(&soft_17));
+    assert!(!DoubleRule::AnyTwoCards.allows(&three_card_11));
+
+    assert!(DoubleRule::NineToEleven.allows(&hard_9));
+    assert!(DoubleRule::NineToEleven.allows(&hard_10));
+    assert!(!DoubleRule::NineToEleven.allows(&soft_17));
+    assert!(!DoubleRule::NineToEleven.allows(&three_card_11));
+
+    assert!(!DoubleRule::TenToEleven.allows(&hard_9));
+    assert!(DoubleRule::TenToEleven.allows(&hard_10));
+
+    assert!(DoubleRule::AnyNumberOfCards.allows(&soft_17));
+    assert!(DoubleRule::AnyNumberOfCards.allows(&three_card_11));
+    assert_eq!(GameRules::default().double, DoubleRule::AnyTwoCards);
+}
+
+#[test]
+fn test_legal_actions_respect_double_rule() {
+    let hard_9 = hand_of(&[Card::Number(5), Card::Number(4)]);
+    let deck = deck_without(&[Card::Number(5), Card::Number(4), Card::Number(6)]);
+
+    let any_two = Calculator::new(rules_with(DoubleRule::AnyTwoCards));
+    let nine_to_eleven = Calculator::new(rules_with(DoubleRule::NineToEleven));
+    let ten_to_eleven = Calculator::new(rules_with(DoubleRule::TenToEleven));
+
+    let result = any_two.calculate_action_evs(&hard_9, Card::Number(6), &deck).unwrap();
+    assert_eq!(result.best().unwrap().action, Action::Double);
+    let result = nine_to_eleven.calculate_action_evs(&hard_9, Card::Number(6), &deck).unwrap();
+    assert_eq!(result.best().unwrap().action, Action::Double);
+    let result = ten_to_eleven.calculate_action_evs(&hard_9, Card::Number(6), &deck).unwrap();
+    assert_eq!(result.get(Action::Double), None);
+    assert_eq!(result.best().unwrap().action, Action::Hit);
+
+    // 软牌在 9-11 规则下不能加倍
+    let soft_17 = hand_of(&[Card::Ace, Card::Number(6)]);
+    let deck = deck_without(&[Card::Ace, Card::Number(6), Card::Number(5)]);
+    let result = nine_to_eleven.calculate_action_evs(&soft_17, Card::Number(5), &deck).unwrap();
+    assert_eq!(result.get(Action::Double), None);
+}
+
+#[test]
+fn test_double_any_number_of_cards() {
+    // 三张牌的硬 11 对 6：只有任意张数加倍规则下可以加倍，且加倍优于要牌
+    let hand = hand_of(&[Card::Number(2), Card::Number(3), Card::Number(6)]);
+    let deck = deck_without(&[Card::Number(2), Card::Number(3), Card::Number(6), Card::Number(6)]);
+
+    let result = Calculator::with_default_rules()
+        .calculate_action_evs(&hand, Card::Number(6), &deck)
+        .unwrap();
+    assert_eq!(result.get(Action::Double), None);
+
+    let result = Calculator::new(rules_with(DoubleRule::AnyNumberOfCards))
+        .calculate_action_evs(&hand, Card::Number(6), &deck)
+        .unwrap();
+    assert_eq!(result.best().unwrap().action, Action::Double);
+    assert!(result.get(Action::Double).unwrap() > result.get(Action::Hit).unwrap());
+}
+
+#[test]
+fn test_restricted_double_lowers_table_ev() {
+    let deck = create_full_8_deck();
+    let ev = |double, mode| {
+        Calculator::with_strategy_mode(rules_with(double), mode)
+            .calculate_table_ev(&deck)
+            .unwrap()
+            .ev
+    };
+
+    for mode in [StrategyMode::Basic, StrategyMode::Optimal] {
+        let any_two = ev(DoubleRule::AnyTwoCards, mode);
+        let nine_to_eleven = ev(DoubleRule::NineToEleven, mode);
+        let ten_to_eleven = ev(DoubleRule::TenToEleven, mode);
+        let any_number = ev(DoubleRule::AnyNumberOfCards, mode);
+
+        // 限制越多 EV 越低，任意张数加倍 EV 更高
+        assert!(nine_to_eleven < any_two, "{:?}: {} vs {}", mode, nine_to_eleven, any_two);
+        assert!(ten_to_eleven < nine_to_eleven, "{:?}: {} vs {}", mode, ten_to_eleven, nine_to_eleven);
+        assert!(any_number > any_two, "{:?}: {} vs {}", mode, any_number, any_two);
+
+        // 最优策略下只限 9-11 约损失 0.1%，只限 10-11 约损失 0.18%
+        if mode == StrategyMode::Optimal {
+            assert!((0.0005..0.0015).contains(&(any_two - nine_to_eleven)));
+            assert!((0.0012..0.0025).contains(&(any_two - ten_to_eleven)));
+        }
+    }
+}
@@ -1,7 +1,6 @@
 //! 边界情况和特殊手牌测试
 
-use calculator::{Calculator, Card, CardCounts, GameRules};
-use std::collections::HashMap;
+use calculator::{Calculator, CalculatorError, Card, CardCounts, GameRules, SurrenderRule};
 
 /// 创建最小牌组（4张牌）
 fn create_minimal_deck() -> CardCounts {
@@ -33,6 +32,7 @@ fn create_low_card_deck() -> CardCounts {
 }
 
 /// 创建只有A的牌组
+#[allow(dead_code)]
 fn create_ace_only_deck() -> CardCounts {
     let mut deck = CardCounts::new();
     deck.insert(Card::Ace, 20);
@@ -44,7 +44,7 @@ fn test_minimal_deck() {
     let deck = create_minimal_deck();
     let calculator = Calculator::with_default_rules();
     
-    let result = calculator.calculate_table_ev(&deck);
+    let result = calculator.calculate_table_ev(&deck).unwrap();
     
     // 验证概率总和接近1
     let total_prob = result.player_win_prob
@@ -52,7 +52,8 @@ fn test_minimal_deck() {
         + result.push_prob
         + result.player_blackjack_prob
         + result.dealer_blackjack_prob
-        + result.surrender_prob;
+        + result.surrender_prob
+        + result.split_prob;
     
     assert!((total_prob - 1.0).abs() < 0.01, "概率总和应该接近1，实际: {}", total_prob);
 }
@@ -62,7 +63,7 @@ fn test_high_card_deck() {
     let deck = create_high_card_deck();
     let calculator = Calculator::with_default_rules();
     
-    let result = calculator.calculate_table_ev(&deck);
+    let result = calculator.calculate_table_ev(&deck).unwrap();
     
     // 高牌较多时，虽然没有A无法组成黑杰克，但应该有游戏结果
     // 验证概率总和
@@ -71,7 +72,8 @@ fn test_high_card_deck() {
         + result.push_prob
         + result.player_blackjack_prob
         + result.dealer_blackjack_prob
-        + result.surrender_prob;
+        + result.surrender_prob
+        + result.split_prob;
     
     assert!((total_prob - 1.0).abs() < 0.01, "概率总和应该接近1，实际: {}", total_prob);
 }
@@ -81,7 +83,7 @@ fn test_low_card_deck() {
     let deck = create_low_card_deck();
     let calculator = Calculator::with_default_rules();
     
-    let result = calculator.calculate_table_ev(&deck);
+    let result = calculator.calculate_table_ev(&deck).unwrap();
     
     // 低牌较多时，玩家黑杰克概率应该较低（因为没有10点牌）
     // 但应该仍然有游戏结果
@@ -90,7 +92,8 @@ fn test_low_card_deck() {
         + result.push_prob
         + result.player_blackjack_prob
         + result.dealer_blackjack_prob
-        + result.surrender_prob;
+        + result.surrender_prob
+        + result.split_prob;
     
     assert!((total_prob - 1.0).abs() < 0.01, "概率总和应该接近1，实际: {}", total_prob);
 }
@@ -102,10 +105,14 @@ fn test_empty_deck() {
     
     let result = calculator.calculate_table_ev(&deck);
     
-    // 空牌组应该返回零结果
-    assert_eq!(result.ev, 0.0);
-    assert_eq!(result.player_win_prob, 0.0);
-    assert_eq!(result.dealer_win_prob, 0.0);
+    // 空牌组无法发完初始牌，应该返回错误而不是零结果
+    assert_eq!(
+        result.unwrap_err(),
+        CalculatorError::InsufficientCards {
+            required: 4,
+            available: 0
+        }
+    );
 }
 
 #[test]
@@ -119,7 +126,7 @@ fn test_single_deck() {
     deck.insert(Card::Face, 12); // J, Q, K
     
     let calculator = Calculator::with_default_rules();
-    let result = calculator.calculate_table_ev(&deck);
+    let result = calculator.calculate_table_ev(&deck).unwrap();
     
     // 验证概率总和
     let total_prob = result.player_win_prob
@@ -127,7 +134,8 @@ fn test_single_deck() {
         + result.push_prob
         + result.player_blackjack_prob
         + result.dealer_blackjack_prob
-        + result.surrender_prob;
+        + result.surrender_prob
+        + result.split_prob;
     
     assert!((total_prob - 1.0).abs() < 0.01, "概率总和应该接近1，实际: {}", total_prob);
     
@@ -138,11 +146,13 @@ fn test_single_deck() {
 #[test]
 fn test_rules_no_surrender() {
     let deck = create_minimal_deck();
-    let mut rules = GameRules::default();
-    rules.allow_surrender = false;
+    let rules = GameRules {
+        surrender: SurrenderRule::None,
+        ..GameRules::default()
+    };
     
     let calculator = Calculator::new(rules);
-    let result = calculator.calculate_table_ev(&deck);
+    let result = calculator.calculate_table_ev(&deck).unwrap();
     
     // 不允许投降时，投降概率应该为0
     assert_eq!(result.surrender_prob, 0.0);
@@ -153,7 +163,8 @@ fn test_rules_no_surrender() {
         + result.push_prob
         + result.player_blackjack_prob
         + result.dealer_blackjack_prob
-        + result.surrender_prob;
+        + result.surrender_prob
+        + result.split_prob;
     
     assert!((total_prob - 1.0).abs() < 0.01, "概率总和应该接近1，实际: {}", total_prob);
 }
@@ -161,11 +172,13 @@ fn test_rules_no_surrender() {
 #[test]
 fn test_rules_soft_17() {
     let deck = create_minimal_deck();
-    let mut rules = GameRules::default();
-    rules.dealer_stands_on_soft_17 = true; // 软17停牌
+    let rules = GameRules {
+        dealer_stands_on_soft_17: true, // 软17停牌
+        ..GameRules::default()
+    };
     
     let calculator = Calculator::new(rules);
-    let result = calculator.calculate_table_ev(&deck);
+    let result = calculator.calculate_table_ev(&deck).unwrap();
     
     // 验证概率总和
     let total_prob = result.player_win_prob
@@ -173,7 +186,8 @@ fn test_rules_soft_17() {
         + result.push_prob
         + result.player_blackjack_prob
         + result.dealer_blackjack_prob
-        + result.surrender_prob;
+        + result.surrender_prob
+        + result.split_prob;
     
     assert!((total_prob - 1.0).abs() < 0.01, "概率总和应该接近1，实际: {}", total_prob);
 }
@@ -181,11 +195,13 @@ fn test_rules_soft_17() {
 #[test]
 fn test_rules_hard_17() {
     let deck = create_minimal_deck();
-    let mut rules = GameRules::default();
-    rules.dealer_stands_on_soft_17 = false; // 硬17停牌（软17继续要牌）
+    let rules = GameRules {
+        dealer_stands_on_soft_17: false, // 硬17停牌（软17继续要牌）
+        ..GameRules::default()
+    };
     
     let calculator = Calculator::new(rules);
-    let result = calculator.calculate_table_ev(&deck);
+    let result = calculator.calculate_table_ev(&deck).unwrap();
     
     // 验证概率总和
     let total_prob = result.player_win_prob
@@ -193,7 +209,8 @@ fn test_rules_hard_17() {
         + result.push_prob
         + result.player_blackjack_prob
         + result.dealer_blackjack_prob
-        + result.surrender_prob;
+        + result.surrender_prob
+        + result.split_prob;
     
     assert!((total_prob - 1.0).abs() < 0.01, "概率总和应该接近1，实际: {}", total_prob);
 }
@@ -201,14 +218,16 @@ fn test_rules_hard_17() {
 #[test]
 fn test_blackjack_payout_6_5() {
     let deck = create_minimal_deck();
-    let mut rules = GameRules::default();
-    rules.blackjack_payout = 1.2; // 6:5 = 1.2
+    let rules = GameRules {
+        blackjack_payout: 1.2, // 6:5 = 1.2
+        ..GameRules::default()
+    };
     
     let calculator = Calculator::new(rules);
-    let result = calculator.calculate_table_ev(&deck);
+    let result = calculator.calculate_table_ev(&deck).unwrap();
     
     // 6:5 赔率下，EV应该更差（对玩家不利）
-    let result_3_2 = Calculator::with_default_rules().calculate_table_ev(&deck);
+    let result_3_2 = Calculator::with_default_rules().calculate_table_ev(&deck).unwrap();
     
     // 6:5 的 EV 应该比 3:2 更差
     assert!(result.ev < result_3_2.ev, "6:5 赔率应该比 3:2 赔率对玩家更不利");
@@ -219,7 +238,8 @@ fn test_blackjack_payout_6_5() {
         + result.push_prob
         + result.player_blackjack_prob
         + result.dealer_blackjack_prob
-        + result.surrender_prob;
+        + result.surrender_prob
+        + result.split_prob;
     
     assert!((total_prob - 1.0).abs() < 0.01, "概率总和应该接近1，实际: {}", total_prob);
 }
@@ -233,7 +253,7 @@ fn test_very_small_deck() {
     deck.insert(Card::Number(9), 2);
     
     let calculator = Calculator::with_default_rules();
-    let result = calculator.calculate_table_ev(&deck);
+    let result = calculator.calculate_table_ev(&deck).unwrap();
     
     // 验证概率总和
     let total_prob = result.player_win_prob
@@ -241,8 +261,30 @@ fn test_very_small_deck() {
         + result.push_prob
         + result.player_blackjack_prob
         + result.dealer_blackjack_prob
-        + result.surrender_prob;
+        + result.surrender_prob
+        + result.split_prob;
     
     assert!((total_prob - 1.0).abs() < 0.01, "概率总和应该接近1，实际: {}", total_prob);
 }
 
+
+#[test]
+fn test_ten_and_face_equivalent() {
+    // 10 和 J/Q/K 都是10点牌，互换后 EV 应该完全相同
+    let mut with_tens = CardCounts::new();
+    with_tens.insert(Card::Ace, 2);
+    with_tens.insert(Card::Number(6), 3);
+    with_tens.insert(Card::Number(9), 3);
+    with_tens.insert(Card::Number(10), 6);
+    
+    let mut with_faces = with_tens.clone();
+    with_faces.remove(&Card::Number(10));
+    with_faces.insert(Card::Face, 6);
+    
+    let calculator = Calculator::with_default_rules();
+    let result_tens = calculator.calculate_table_ev(&with_tens).unwrap();
+    let result_faces = calculator.calculate_table_ev(&with_faces).unwrap();
+    
+    assert!((result_tens.ev - result_faces.ev).abs() < 1e-12);
+    assert!(result_tens.player_blackjack_prob > 0.0, "A + 10 应该组成黑杰克");
+}
@@ -0,0 +1,93 @@
+//! 移除效应（EOR）测试
+
+use calculator::{
+    create_full_deck, Calculator, CalculatorError, Card, CountSystem, EffectsOfRemoval, GameRules,
+};
+
+fn single_deck_eor() -> EffectsOfRemoval {
+    let rules = GameRules {
+        deck_count: 1,
+        ..GameRules::default()
+    };
+    Calculator::new(rules).effects_of_removal(&create_full_deck(1)).unwrap()
+}
+
+#[test]
+fn test_single_deck_effects() {
+    let eor = single_deck_eor();
+    assert_eq!(eor.ranks.len(), 10);
+    assert_eq!(eor.get(Card::Number(10)), eor.get(Card::Face));
+    assert_eq!(eor.get(Card::Face).unwrap().count, 16);
+    assert_eq!(eor.get(Card::Number(5)).unwrap().count, 4);
+
+    // 移除小牌对玩家有利，5 最有利；移除 A 和十点牌对玩家不利
+    let eor_of = |card| eor.get(card).unwrap().eor;
+    for n in 2..=6 {
+        assert!(eor_of(Card::Number(n)) > 0.003, "{}: {}", n, eor_of(Card::Number(n)));
+        assert!(eor_of(Card::Number(n)) <= eor_of(Card::Number(5)));
+    }
+    assert!(eor_of(Card::Ace) < -0.004);
+    assert!(eor_of(Card::Face) < -0.004);
+    assert!(eor_of(Card::Number(9)) < 0.0);
+
+    for effect in &eor.ranks {
+        assert_eq!(effect.eor, effect.ev - eor.baseline_ev);
+    }
+    // 移除一整副牌的效应相互抵消：按张数加权的移除效应之和接近 0
+    let weighted: f64 = eor.ranks.iter().map(|effect| effect.count as f64 * effect.eor).sum();
+    assert!(weighted.abs() < 0.01, "{}", weighted);
+}
+
+#[test]
+fn test_betting_correlation() {
+    let eor = single_deck_eor();
+    let correlation = |system: CountSystem| system.betting_correlation(&eor);
+
+    // Hi-Lo 约 0.97，Wong Halves 更高，忽略 A 的 Hi-Opt I 较低
+    let hi_lo = correlation(CountSystem::hi_lo());
+    assert!(hi_lo > 0.95, "{}", hi_lo);
+    assert!(correlation(CountSystem::wong_halves()) > hi_lo);
+    assert!(correlation(CountSystem::hi_opt_1()) < hi_lo);
+    // 标签取反时相关系数取反，标签全部相同时为 0
+    let reversed = CountSystem::new("reversed", CountSystem::hi_lo().tags.map(|tag| -tag));
+    assert!((correlation(reversed) + hi_lo).abs() < 1e-12);
+    assert_eq!(correlation(CountSystem::new("flat", [1.0; 10])), 0.0);
+}
+
+#[test]
+fn test_depleted_shoe() {
+    // 起始牌组可以是任意剩余牌组；没有的点数不出现在表中
+    let rules = GameRules {
+        deck_count: 1,
+        ..GameRules::default()
+    };
+    let mut deck = create_full_deck(1);
+    deck.insert(Card::Number(5), 0);
+    deck.insert(Card::Face, 0);
+    let eor = Calculator::new(rules.clone()).effects_of_removal(&deck).unwrap();
+    assert_eq!(eor.ranks.len(), 9);
+    assert!(eor.get(Card::Number(5)).is_none());
+    // 只剩 10 时移除 10
+    assert_eq!(eor.get(Card::Face).unwrap().count, 4);
+    let baseline = Calculator::new(rules.clone()).calculate_table_ev(&deck).unwrap();
+    assert_eq!(eor.baseline_ev, baseline.ev);
+
+    let mut tiny = deck.clone();
+    tiny.retain(|&card, _| card == Card::Number(10));
+    assert_eq!(
+        Calculator::new(rules).effects_of_removal(&tiny).unwrap_err(),
+        CalculatorError::InsufficientCards { required: 5, available: 4 }
+    );
+}
+
+#[test]
+fn test_serialization_round_trip() {
+    let eor = single_deck_eor();
+    let json = serde_json::to_string(&eor).unwrap();
+    let parsed: EffectsOfRemoval = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.ranks.len(), eor.ranks.len());
+    for (parsed, effect) in parsed.ranks.iter().zip(&eor.ranks) {
+        assert_eq!((parsed.card, parsed.count), (effect.card, effect.count));
+        assert!((parsed.eor - effect.eor).abs() < 1e-15);
+    }
+}
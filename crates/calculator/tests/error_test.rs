@@ -0,0 +1,179 @@
+//! 错误处理测试：无效牌组和规则返回具体的错误而不是零结果
+
+use calculator::{
+    card_counts_to_point_counts, card_to_point_index, create_full_8_deck, Calculator, CalculatorError,
+    Card, CardCounts, GameRules, Hand, PlayingCard, Suit,
+};
+
+/// 两张牌的手牌
+fn hand(first: Card, second: Card) -> Hand {
+    let mut hand = Hand::new();
+    for card in [first, second] {
+        hand.add_card(PlayingCard {
+            card,
+            suit: Suit::Spades,
+        });
+    }
+    hand
+}
+
+#[test]
+fn test_invalid_ranks() {
+    let calculator = Calculator::with_default_rules();
+    for card in [Card::Number(0), Card::Number(1), Card::Number(11)] {
+        let mut deck = create_full_8_deck();
+        deck.insert(card, 1);
+        assert_eq!(
+            calculator.calculate_table_ev(&deck).unwrap_err(),
+            CalculatorError::InvalidRank(card)
+        );
+        // 实际牌局中玩家未见的剩余牌组
+        assert_eq!(
+            calculator.basic_action(&hand(Card::Face, Card::Number(6)), Card::Face, 1, &deck).unwrap_err(),
+            CalculatorError::InvalidRank(card)
+        );
+    }
+
+    // 手牌和明牌中的无效牌面同样被拒绝
+    let deck = create_full_8_deck();
+    let err = calculator
+        .calculate_action_evs(&hand(Card::Number(11), Card::Number(5)), Card::Face, &deck)
+        .unwrap_err();
+    assert_eq!(err, CalculatorError::InvalidRank(Card::Number(11)));
+    let err = calculator
+        .dealer_distribution(Some(Card::Number(1)), &deck)
+        .unwrap_err();
+    assert_eq!(err, CalculatorError::InvalidRank(Card::Number(1)));
+
+    // 实际牌局的决策同样检查牌面（不再把 1 当作 A、把 11 当作十点牌）
+    let sixteen = hand(Card::Face, Card::Number(6));
+    for card in [Card::Number(0), Card::Number(1), Card::Number(11)] {
+        assert_eq!(
+            calculator.basic_action(&sixteen, card, 1, &deck).unwrap_err(),
+            CalculatorError::InvalidRank(card)
+        );
+        assert_eq!(
+            calculator.basic_action(&hand(card, Card::Number(6)), Card::Face, 1, &deck).unwrap_err(),
+            CalculatorError::InvalidRank(card)
+        );
+        assert_eq!(
+            calculator.index_action(&sixteen, card, 1, &deck, 2.0).unwrap_err(),
+            CalculatorError::InvalidRank(card)
+        );
+        assert_eq!(
+            calculator.index_action(&hand(card, Card::Number(6)), Card::Face, 1, &deck, 2.0).unwrap_err(),
+            CalculatorError::InvalidRank(card)
+        );
+    }
+}
+
+#[test]
+fn test_point_index_rejects_invalid_ranks() {
+    assert_eq!(card_to_point_index(Card::Ace), Some((0, true)));
+    assert_eq!(card_to_point_index(Card::Number(2)), Some((1, false)));
+    assert_eq!(card_to_point_index(Card::Number(10)), Some((9, false)));
+    assert_eq!(card_to_point_index(Card::Face), Some((10, false)));
+    // 1 不是 A，0 不会下溢
+    for card in [Card::Number(0), Card::Number(1), Card::Number(11)] {
+        assert_eq!(card_to_point_index(card), None);
+    }
+
+    let mut deck = CardCounts::new();
+    deck.insert(Card::Number(1), 4);
+    deck.insert(Card::Number(5), 2);
+    let mut expected = [0; 11];
+    expected[4] = 2;
+    assert_eq!(card_counts_to_point_counts(&deck), expected);
+}
+
+#[test]
+fn test_impossible_composition() {
+    let rules = GameRules {
+        deck_count: 1,
+        ..GameRules::default()
+    };
+    let calculator = Calculator::new(rules);
+
+    let mut deck = CardCounts::new();
+    deck.insert(Card::Ace, 5);
+    deck.insert(Card::Face, 12);
+    assert_eq!(
+        calculator.calculate_table_ev(&deck).unwrap_err(),
+        CalculatorError::ImpossibleComposition {
+            card: Card::Ace,
+            count: 5,
+            deck_count: 1,
+            max: 4,
+        }
+    );
+    assert!(matches!(
+        calculator.basic_action(&hand(Card::Face, Card::Number(6)), Card::Face, 1, &deck),
+        Err(CalculatorError::ImpossibleComposition { card: Card::Ace, .. })
+    ));
+
+    // 剩余牌组本身合法，但加上手牌和明牌后超过一副牌
+    let mut deck = CardCounts::new();
+    deck.insert(Card::Number(8), 3);
+    deck.insert(Card::Face, 12);
+    let err = calculator
+        .calculate_action_evs(&hand(Card::Number(8), Card::Number(8)), Card::Number(8), &deck)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        CalculatorError::ImpossibleComposition {
+            card: Card::Number(8),
+            count: 6,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_invalid_rules() {
+    let deck = create_full_8_deck();
+    for payout in [0.0, -1.5, f64::NAN, f64::INFINITY] {
+        let rules = GameRules {
+            blackjack_payout: payout,
+            ..GameRules::default()
+        };
+        let err = Calculator::new(rules).calculate_table_ev(&deck).unwrap_err();
+        assert!(matches!(err, CalculatorError::InvalidPayout(_)), "赔率 {} 应无效", payout);
+    }
+
+    let rules = GameRules {
+        deck_count: 0,
+        ..GameRules::default()
+    };
+    assert_eq!(
+        Calculator::new(rules).calculate_table_ev(&deck).unwrap_err(),
+        CalculatorError::InvalidDeckCount(0)
+    );
+}
+
+#[test]
+fn test_insufficient_cards() {
+    let calculator = Calculator::with_default_rules();
+
+    let mut deck = CardCounts::new();
+    deck.insert(Card::Face, 3);
+    assert_eq!(
+        calculator.calculate_table_ev(&deck).unwrap_err(),
+        CalculatorError::InsufficientCards {
+            required: 4,
+            available: 3
+        }
+    );
+
+    // 保险和决策 EV 至少需要一张暗牌
+    let empty = CardCounts::new();
+    assert!(matches!(
+        calculator.calculate_insurance(&empty).unwrap_err(),
+        CalculatorError::InsufficientCards { required: 1, .. }
+    ));
+    assert!(matches!(
+        calculator
+            .calculate_action_evs(&hand(Card::Face, Card::Number(6)), Card::Face, &empty)
+            .unwrap_err(),
+        CalculatorError::InsufficientCards { required: 1, .. }
+    ));
+}
@@ -0,0 +1,217 @@
+//! 精确有理数验证模式测试（需要开启 `exact` 特性）
+#![cfg(feature = "exact")]
+
+use calculator::{
+    BigRational, Calculator, Card, CardCounts, CharlieRule, DeviationTable, DoubleRule, GameRules,
+    HoleCardRule, StrategyMode, SurrenderRule,
+};
+
+/// 每种点数各若干张的小牌组
+fn small_deck(per_rank: u32) -> CardCounts {
+    let mut deck = CardCounts::new();
+    deck.insert(Card::Ace, per_rank);
+    for n in 2..=10 {
+        deck.insert(Card::Number(n), per_rank);
+    }
+    deck.insert(Card::Face, per_rank);
+    deck
+}
+
+#[test]
+fn test_all_tens_is_exact_push() {
+    // 只有十点牌：玩家 20 点对庄家 20 点，必然平局
+    let mut deck = CardCounts::new();
+    deck.insert(Card::Face, 20);
+
+    let result = Calculator::with_default_rules().calculate_exact_ev(&deck).unwrap();
+    assert_eq!(result.ev, BigRational::from_integer(0.into()));
+    assert_eq!(result.float_ev, 0.0);
+}
+
+#[test]
+fn test_float_matches_exact_on_small_shoe() {
+    for mode in [StrategyMode::Basic, StrategyMode::Optimal] {
+        let calculator = Calculator::with_strategy_mode(GameRules::default(), mode);
+        let result = calculator.calculate_exact_ev(&small_deck(1)).unwrap();
+
+        assert!(
+            result.deviation.abs() < 1e-12,
+            "{:?} 模式下浮点结果偏差过大: {:e}",
+            mode,
+            result.deviation
+        );
+        assert_eq!(result.deviation, result.float_ev - result.ev_f64());
+    }
+}
+
+#[test]
+fn test_exact_payout_fraction() {
+    // 只有 A 和十点牌：6:5 和 3:2 赔率下精确 EV 之差必须是有理数
+    let mut deck = CardCounts::new();
+    deck.insert(Card::Ace, 3);
+    deck.insert(Card::Face, 6);
+
+    let rules = |blackjack_payout| GameRules {
+        blackjack_payout,
+        ..GameRules::default()
+    };
+    let three_to_two = Calculator::new(rules(1.5)).calculate_exact_ev(&deck).unwrap();
+    let six_to_five = Calculator::new(rules(1.2)).calculate_exact_ev(&deck).unwrap();
+
+    // 赔率只影响玩家黑杰克（庄家没有黑杰克）的结果，差值 = 0.3 × P(玩家赢黑杰克)
+    // 该概率由前四张牌决定，乘以 9 × 8 × 7 × 6 种发牌方式后必须是整数
+    let difference = &three_to_two.ev - &six_to_five.ev;
+    let blackjack_prob = difference / BigRational::new(3.into(), 10.into());
+    let ways = blackjack_prob.clone() * BigRational::from_integer(3024.into());
+    assert!(ways.is_integer(), "P(玩家赢黑杰克) 应为发牌方式数之比: {}", blackjack_prob);
+    assert!(blackjack_prob > BigRational::from_integer(0.into()));
+    assert!(three_to_two.deviation.abs() < 1e-12);
+    assert!(six_to_five.deviation.abs() < 1e-12);
+}
+
+#[test]
+fn test_float_matches_exact_with_double_rules() {
+    // 分牌但不允许分牌后加倍时，任意张数加倍规则下分牌手牌要牌后仍不能加倍
+    for double in [DoubleRule::TenToEleven, DoubleRule::AnyNumberOfCards] {
+        let rules = GameRules {
+            allow_split: true,
+            allow_double_after_split: false,
+            double,
+            ..GameRules::default()
+        };
+        for mode in [StrategyMode::Basic, StrategyMode::Optimal] {
+            let calculator = Calculator::with_strategy_mode(rules.clone(), mode);
+            let result = calculator.calculate_exact_ev(&small_deck(1)).unwrap();
+            assert!(
+                result.deviation.abs() < 1e-12,
+                "{:?} / {:?} 下浮点结果偏差过大: {:e}",
+                double,
+                mode,
+                result.deviation
+            );
+        }
+    }
+}
+
+#[test]
+fn test_float_matches_exact_with_charlie() {
+    // 无暗牌规则下庄家黑杰克仍然赢过 Charlie
+    for hole_card in [HoleCardRule::Peek, HoleCardRule::NoHoleCard] {
+        let rules = GameRules {
+            charlie: Some(CharlieRule::five_card()),
+            hole_card,
+            ..GameRules::default()
+        };
+        for mode in [StrategyMode::Basic, StrategyMode::Optimal] {
+            let calculator = Calculator::with_strategy_mode(rules.clone(), mode);
+            let result = calculator.calculate_exact_ev(&small_deck(1)).unwrap();
+            assert!(
+                result.deviation.abs() < 1e-12,
+                "{:?} / {:?} 下浮点结果偏差过大: {:e}",
+                hole_card,
+                mode,
+                result.deviation
+            );
+        }
+    }
+}
+
+#[test]
+fn test_float_matches_exact_with_deviations() {
+    // 指数打法按每个决策点的剩余牌组计算 true count，精确模式使用同一套决策
+    let calculator = Calculator::new(GameRules::default())
+        .with_deviations(DeviationTable::illustrious_18_fab_4());
+    let result = calculator.calculate_exact_ev(&small_deck(1)).unwrap();
+    assert!(result.deviation.abs() < 1e-12, "浮点结果偏差过大: {:e}", result.deviation);
+}
+
+/// 只有一种点数的牌组
+fn deck_of(card: Card, count: u32) -> CardCounts {
+    let mut deck = CardCounts::new();
+    deck.insert(card, count);
+    deck
+}
+
+fn fraction(numer: i64, denom: i64) -> BigRational {
+    BigRational::new(numer.into(), denom.into())
+}
+
+/// 精确 EV 等于手算的分数，浮点结果与之一致
+fn assert_exact(calculator: &Calculator, deck: &CardCounts, expected: BigRational) {
+    let result = calculator.calculate_exact_ev(deck).unwrap();
+    assert_eq!(result.ev, expected, "浮点结果: {}", result.float_ev);
+    assert!(result.deviation.abs() < 1e-12, "浮点结果偏差过大: {:e}", result.deviation);
+}
+
+#[test]
+fn test_one_ace_among_tens_by_hand() {
+    // 1 张 A、5 张十点牌：A 等可能落在 6 个位置之一
+    // A 在玩家两张牌中（2/6）：玩家黑杰克赢赔率；A 为庄家明牌或暗牌（各 1/6）：庄家黑杰克，
+    // 玩家 20 点输 1；其余情况 20 对 20 平局。EV = (2 × 赔率 − 2) / 6
+    let mut deck = deck_of(Card::Face, 5);
+    deck.insert(Card::Ace, 1);
+    let rules = |blackjack_payout, surrender| GameRules {
+        allow_split: false,
+        blackjack_payout,
+        surrender,
+        ..GameRules::default()
+    };
+    for mode in [StrategyMode::Basic, StrategyMode::Optimal] {
+        let three_to_two = Calculator::with_strategy_mode(rules(1.5, SurrenderRule::Late), mode);
+        assert_exact(&three_to_two, &deck, fraction(1, 6));
+        let six_to_five = Calculator::with_strategy_mode(rules(1.2, SurrenderRule::Late), mode);
+        assert_exact(&six_to_five, &deck, fraction(1, 15));
+    }
+
+    // 早投降：明牌为 A 时剩余全是十点牌，最优策略投降只输一半。EV = (3 − 0.5 − 1) / 6
+    let early = Calculator::with_strategy_mode(rules(1.5, SurrenderRule::Early), StrategyMode::Optimal);
+    assert_exact(&early, &deck, fraction(1, 4));
+}
+
+#[test]
+fn test_one_six_among_tens_by_hand() {
+    // 1 张 6、7 张十点牌：6 等可能落在 8 个位置之一
+    // 6 在玩家两张牌中（2/8）：16 点对明牌 10、剩余全是十点牌，后投降输 0.5（不可投降时输 1）
+    // 6 为庄家明牌或暗牌（各 1/8）：庄家 16 点要牌爆牌，玩家 20 点赢 1；其余情况平局
+    let mut deck = deck_of(Card::Face, 7);
+    deck.insert(Card::Number(6), 1);
+    let rules = |surrender| GameRules {
+        allow_split: false,
+        surrender,
+        ..GameRules::default()
+    };
+    for mode in [StrategyMode::Basic, StrategyMode::Optimal] {
+        let late = Calculator::with_strategy_mode(rules(SurrenderRule::Late), mode);
+        assert_exact(&late, &deck, fraction(1, 8));
+        let none = Calculator::with_strategy_mode(rules(SurrenderRule::None), mode);
+        assert_exact(&none, &deck, fraction(0, 1));
+    }
+}
+
+#[test]
+fn test_all_eights_split_by_hand() {
+    // 只有 8：玩家 8,8 对明牌 8，庄家 16 点要牌必然爆牌
+    // 最优策略每手 16 点停牌赢 1；基本策略的 16 对 8 要牌必然爆牌输 1，因此 EV 都等于 ±手数
+    let deck = deck_of(Card::Number(8), 16);
+    let no_split = GameRules {
+        allow_split: false,
+        ..GameRules::default()
+    };
+    let split = GameRules {
+        allow_split: true,
+        allow_resplit: false,
+        ..GameRules::default()
+    };
+    let resplit = GameRules {
+        allow_split: true,
+        allow_resplit: true,
+        max_split_hands: 4,
+        ..GameRules::default()
+    };
+    for (rules, hands) in [(no_split, 1), (split, 2), (resplit, 4)] {
+        let optimal = Calculator::with_strategy_mode(rules.clone(), StrategyMode::Optimal);
+        assert_exact(&optimal, &deck, fraction(hands, 1));
+        let basic = Calculator::with_strategy_mode(rules, StrategyMode::Basic);
+        assert_exact(&basic, &deck, fraction(-hands, 1));
+    }
+}
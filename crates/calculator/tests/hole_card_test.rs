@@ -0,0 +1,136 @@
+//! 庄家偷看与欧式无暗牌（ENHC / OBO）规则测试
+
+mod common;
+
+use calculator::strategy::BasicStrategy;
+use calculator::{
+    Action, Calculator, Card, CardCounts, GameRules, HoleCardRule, SurrenderRule, TableEVResult,
+    create_full_8_deck,
+};
+use common::{hand_of, deck_without};
+
+/// 创建一副完整的牌
+fn create_single_deck() -> CardCounts {
+    let mut deck = CardCounts::new();
+    deck.insert(Card::Ace, 4);
+    for n in 2..=10 {
+        deck.insert(Card::Number(n), 4);
+    }
+    deck.insert(Card::Face, 12);
+    deck
+}
+
+fn rules_with(hole_card: HoleCardRule, surrender: SurrenderRule) -> GameRules {
+    GameRules {
+        hole_card,
+        surrender,
+        ..GameRules::default()
+    }
+}
+
+fn total_prob(result: &TableEVResult) -> f64 {
+    result.player_win_prob
+        + result.dealer_win_prob
+        + result.push_prob
+        + result.player_blackjack_prob
+        + result.dealer_blackjack_prob
+        + result.surrender_prob
+        + result.split_prob
+}
+
+#[test]
+fn test_probability_sums_to_one_for_every_rule() {
+    let deck = create_single_deck();
+    for hole_card in [
+        HoleCardRule::Peek,
+        HoleCardRule::NoHoleCard,
+        HoleCardRule::NoHoleCardOriginalBetsOnly,
+    ] {
+        let rules = GameRules {
+            allow_split: true,
+            allow_double_after_split: true,
+            ..rules_with(hole_card, SurrenderRule::Late)
+        };
+        let result = Calculator::new(rules).calculate_table_ev(&deck).unwrap();
+        assert!(
+            (total_prob(&result) - 1.0).abs() < 1e-9,
+            "{:?} 概率总和应为1，实际: {}",
+            hole_card,
+            total_prob(&result)
+        );
+    }
+}
+
+#[test]
+fn test_no_hole_card_costs_player() {
+    let deck = create_full_8_deck();
+    let table_ev = |hole_card| {
+        Calculator::new(rules_with(hole_card, SurrenderRule::None)).calculate_table_ev(&deck).unwrap()
+    };
+    let peek = table_ev(HoleCardRule::Peek);
+    let enhc = table_ev(HoleCardRule::NoHoleCard);
+    let obo = table_ev(HoleCardRule::NoHoleCardOriginalBetsOnly);
+
+    // 不投降时 OBO 与偷看规则的输赢完全相同
+    assert!((obo.ev - peek.ev).abs() < 1e-12);
+    // 全部投注输给庄家黑杰克约损失 0.1%
+    assert!(enhc.ev < peek.ev);
+    assert!(peek.ev - enhc.ev < 0.003, "ENHC 差值: {}", peek.ev - enhc.ev);
+}
+
+#[test]
+fn test_hard_11_vs_ten_not_doubled_without_hole_card() {
+    let hand = hand_of(&[Card::Number(6), Card::Number(5)]);
+    let deck = deck_without(&[Card::Number(6), Card::Number(5), Card::Face]);
+
+    let peek = Calculator::new(rules_with(HoleCardRule::Peek, SurrenderRule::None))
+        .calculate_action_evs(&hand, Card::Face, &deck).unwrap();
+    let enhc = Calculator::new(rules_with(HoleCardRule::NoHoleCard, SurrenderRule::None))
+        .calculate_action_evs(&hand, Card::Face, &deck).unwrap();
+
+    assert_eq!(peek.best().unwrap().action, Action::Double);
+    assert_eq!(enhc.best().unwrap().action, Action::Hit);
+}
+
+#[test]
+fn test_dealer_blackjack_settlement_exact() {
+    // 明牌A，剩余全是10点牌：庄家必然黑杰克
+    let mut deck = CardCounts::new();
+    deck.insert(Card::Face, 10);
+    let hand = hand_of(&[Card::Number(10), Card::Number(8)]);
+
+    let peek = Calculator::new(rules_with(HoleCardRule::Peek, SurrenderRule::Late))
+        .calculate_action_evs(&hand, Card::Ace, &deck).unwrap();
+    assert!(peek.actions.is_empty(), "偷看后玩家没有决策");
+
+    let enhc = Calculator::new(rules_with(HoleCardRule::NoHoleCard, SurrenderRule::Early))
+        .calculate_action_evs(&hand, Card::Ace, &deck).unwrap();
+    assert_eq!(enhc.get(Action::Stand), Some(-1.0));
+    assert_eq!(enhc.get(Action::Double), Some(-2.0));
+    assert_eq!(enhc.get(Action::Surrender), Some(-0.5));
+
+    let obo_rules = rules_with(HoleCardRule::NoHoleCardOriginalBetsOnly, SurrenderRule::Early);
+    let obo = Calculator::new(obo_rules).calculate_action_evs(&hand, Card::Ace, &deck).unwrap();
+    assert_eq!(obo.get(Action::Stand), Some(-1.0));
+    assert_eq!(obo.get(Action::Double), Some(-1.0));
+    assert_eq!(obo.get(Action::Surrender), Some(-0.5));
+}
+
+#[test]
+fn test_strategy_adjusts_for_no_hole_card() {
+    let strategy = BasicStrategy::new();
+
+    let eights = hand_of(&[Card::Number(8), Card::Number(8)]);
+    let action = strategy.get_action(&eights, Card::Face);
+    assert_eq!(action, Action::Split);
+    assert_ne!(strategy.adjust_for_no_hole_card(&eights, Card::Face, action), Action::Split);
+    // 明牌小牌时不受影响
+    assert_eq!(
+        strategy.adjust_for_no_hole_card(&eights, Card::Number(6), Action::Split),
+        Action::Split
+    );
+
+    let aces = hand_of(&[Card::Ace, Card::Ace]);
+    assert_eq!(strategy.adjust_for_no_hole_card(&aces, Card::Face, Action::Split), Action::Split);
+    assert_ne!(strategy.adjust_for_no_hole_card(&aces, Card::Ace, Action::Split), Action::Split);
+}
@@ -0,0 +1,107 @@
+//! 保险和等额赔付测试
+
+mod common;
+
+use calculator::{
+    Calculator, Card, CardCounts, DeviationTable, InsurancePolicy, TableEVResult, create_full_8_deck,
+};
+use common::deck_without;
+
+/// 10点牌很多的剩余牌组（保险有利）
+fn create_ten_rich_deck() -> CardCounts {
+    let mut deck = CardCounts::new();
+    deck.insert(Card::Ace, 4);
+    deck.insert(Card::Number(5), 4);
+    deck.insert(Card::Number(9), 4);
+    deck.insert(Card::Face, 16);
+    deck
+}
+
+#[test]
+fn test_insurance_ev_exact() {
+    // 玩家 10,6，庄家明牌A：剩余 413 张中有 127 张10点牌
+    let deck = deck_without(&[Card::Number(10), Card::Number(6), Card::Ace]);
+    let result = Calculator::with_default_rules().calculate_insurance(&deck).unwrap();
+
+    let ten_prob = 127.0 / 413.0;
+    assert!((result.dealer_blackjack_prob - ten_prob).abs() < 1e-12);
+    assert!((result.insurance - 0.5 * (3.0 * ten_prob - 1.0)).abs() < 1e-12);
+    assert!(!result.should_insure(), "满牌靴时保险不利");
+
+    assert_eq!(result.even_money, 1.0);
+    assert!((result.decline_even_money - 1.5 * (1.0 - ten_prob)).abs() < 1e-12);
+}
+
+#[test]
+fn test_insurance_positive_in_ten_rich_deck() {
+    let result = Calculator::with_default_rules().calculate_insurance(&create_ten_rich_deck()).unwrap();
+
+    // 28 张中 16 张10点牌，超过 1/3
+    assert!(result.should_insure());
+    // 保险有利时等额赔付也优于不要等额赔付
+    assert!(result.even_money > result.decline_even_money);
+}
+
+fn sum_of_parts(result: &TableEVResult) -> f64 {
+    result.ev_normal + result.ev_double + result.ev_surrender + result.ev_split + result.ev_insurance
+}
+
+#[test]
+fn test_table_ev_with_insurance_policy() {
+    // 满牌靴：保险从不有利，策略不影响 EV
+    let deck = create_full_8_deck();
+    let never = Calculator::with_default_rules().calculate_table_ev(&deck).unwrap();
+    let when_positive = Calculator::with_default_rules()
+        .with_insurance_policy(InsurancePolicy::WhenPositive)
+        .calculate_table_ev(&deck).unwrap();
+    assert_eq!(when_positive.insurance_prob, 0.0);
+    assert_eq!(when_positive.ev_insurance, 0.0);
+    assert!((when_positive.ev - never.ev).abs() < 1e-12);
+
+    // 10点牌丰富：保险提高 EV
+    let deck = create_ten_rich_deck();
+    let never = Calculator::with_default_rules().calculate_table_ev(&deck).unwrap();
+    let calculator =
+        Calculator::with_default_rules().with_insurance_policy(InsurancePolicy::WhenPositive);
+    assert_eq!(calculator.insurance_policy(), InsurancePolicy::WhenPositive);
+    let when_positive = calculator.calculate_table_ev(&deck).unwrap();
+
+    assert_eq!(never.ev_insurance, 0.0);
+    assert!(when_positive.insurance_prob > 0.0);
+    assert!(when_positive.ev_insurance > 0.0);
+    assert!(when_positive.ev > never.ev);
+    assert!((when_positive.ev - sum_of_parts(&when_positive)).abs() < 1e-12);
+}
+
+#[test]
+fn test_insurance_follows_deviation_index() {
+    // 剩余牌 Hi-Lo 计数为 0，但 10点牌占 10/23：按 EV 应买保险，按 true count 不买
+    let mut deck = CardCounts::new();
+    deck.insert(Card::Ace, 2);
+    deck.insert(Card::Number(2), 12);
+    deck.insert(Card::Face, 10);
+    let when_positive = Calculator::with_default_rules().with_insurance_policy(InsurancePolicy::WhenPositive);
+    assert!(when_positive.calculate_table_ev(&deck).unwrap().insurance_prob > 0.0);
+    let counting = Calculator::with_default_rules()
+        .with_insurance_policy(InsurancePolicy::WhenPositive)
+        .with_deviations(DeviationTable::illustrious_18());
+    let result = counting.calculate_table_ev(&deck).unwrap();
+    assert_eq!(result.insurance_prob, 0.0);
+    assert_eq!(result.ev_insurance, 0.0);
+
+    // 10点牌丰富时 true count 超过指数：即使策略为不买也按指数买保险
+    let deck = create_ten_rich_deck();
+    let expected = when_positive.calculate_table_ev(&deck).unwrap();
+    let result = Calculator::with_default_rules()
+        .with_deviations(DeviationTable::illustrious_18())
+        .calculate_table_ev(&deck)
+        .unwrap();
+    assert_eq!(result.insurance_prob, expected.insurance_prob);
+    assert!((result.ev_insurance - expected.ev_insurance).abs() < 1e-12);
+
+    // 没有保险指数的偏差表仍按保险策略
+    let fab_4 = Calculator::with_default_rules().with_deviations(DeviationTable::fab_4());
+    assert_eq!(fab_4.calculate_table_ev(&deck).unwrap().ev_insurance, 0.0);
+    let fab_4 = fab_4.with_insurance_policy(InsurancePolicy::WhenPositive);
+    assert!(fab_4.calculate_table_ev(&deck).unwrap().ev_insurance > 0.0);
+}
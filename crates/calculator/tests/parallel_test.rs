@@ -0,0 +1,44 @@
+//! 并行计算测试（需要开启 `parallel` 特性）
+#![cfg(feature = "parallel")]
+
+mod common;
+
+use calculator::{create_full_deck, Calculator, StrategyMode};
+use common::single_deck_no_split_rules;
+
+/// 在指定线程数的线程池中计算上桌 EV
+fn table_ev_with_threads(threads: usize, mode: StrategyMode) -> f64 {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .unwrap();
+    pool.install(|| {
+        Calculator::with_strategy_mode(single_deck_no_split_rules(), mode)
+            .calculate_table_ev(&create_full_deck(1)).unwrap()
+            .ev
+    })
+}
+
+#[test]
+fn test_result_independent_of_thread_count() {
+    for mode in [StrategyMode::Basic, StrategyMode::Optimal] {
+        let single = table_ev_with_threads(1, mode);
+        let multi = table_ev_with_threads(4, mode);
+        assert_eq!(single, multi, "{:?} 模式下结果不应依赖线程数", mode);
+    }
+}
+
+#[test]
+fn test_worker_caches_are_reused_and_cleared() {
+    let calculator = Calculator::new(single_deck_no_split_rules());
+    let first = calculator.calculate_table_ev(&create_full_deck(1)).unwrap();
+    let size = calculator.cache_size();
+    assert!(size > 0, "工作计算器的缓存应计入缓存大小");
+
+    let again = calculator.calculate_table_ev(&create_full_deck(1)).unwrap();
+    assert_eq!(first.ev, again.ev);
+    assert!(calculator.cache_size() >= size);
+
+    calculator.clear_cache();
+    assert_eq!(calculator.cache_size(), 0);
+}
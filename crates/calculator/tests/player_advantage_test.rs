@@ -7,7 +7,14 @@
 //! - 低牌（2-6）较少：庄家更容易爆牌
 
 use calculator::{Calculator, Card, CardCounts, GameRules};
-use std::collections::HashMap;
+
+/// 创建计算器（偏斜牌组中某些牌超过 8 副牌的张数，按 16 副牌检查牌组）
+fn skewed_deck_calculator() -> Calculator {
+    Calculator::new(GameRules {
+        deck_count: 16,
+        ..GameRules::default()
+    })
+}
 
 /// 创建高牌较多的牌组（对玩家有利）
 /// 高牌多意味着玩家更容易拿到黑杰克
@@ -100,9 +107,9 @@ fn create_only_high_and_ace_deck() -> CardCounts {
 #[test]
 fn test_high_card_rich_deck() {
     let deck = create_high_card_rich_deck();
-    let calculator = Calculator::with_default_rules();
+    let calculator = skewed_deck_calculator();
     
-    let result = calculator.calculate_table_ev(&deck);
+    let result = calculator.calculate_table_ev(&deck).unwrap();
     
     println!("高牌较多牌组 EV: {:.6}", result.ev);
     println!("玩家黑杰克率: {:.6}", result.player_blackjack_prob);
@@ -117,13 +124,14 @@ fn test_high_card_rich_deck() {
         + result.push_prob
         + result.player_blackjack_prob
         + result.dealer_blackjack_prob
-        + result.surrender_prob;
+        + result.surrender_prob
+        + result.split_prob;
     
     assert!((total_prob - 1.0).abs() < 0.01, "概率总和应该接近1，实际: {}", total_prob);
     
     // 高牌多时，EV 应该比满8副牌更好（更接近0或为正）
     let full_deck = calculator::create_full_8_deck();
-    let full_result = calculator.calculate_table_ev(&full_deck);
+    let full_result = calculator.calculate_table_ev(&full_deck).unwrap();
     
     println!("满8副牌 EV: {:.6}", full_result.ev);
     assert!(result.ev > full_result.ev, 
@@ -134,9 +142,9 @@ fn test_high_card_rich_deck() {
 #[test]
 fn test_ace_rich_deck() {
     let deck = create_ace_rich_deck();
-    let calculator = Calculator::with_default_rules();
+    let calculator = skewed_deck_calculator();
     
-    let result = calculator.calculate_table_ev(&deck);
+    let result = calculator.calculate_table_ev(&deck).unwrap();
     
     println!("A较多牌组 EV: {:.6}", result.ev);
     println!("玩家黑杰克率: {:.6}", result.player_blackjack_prob);
@@ -150,13 +158,14 @@ fn test_ace_rich_deck() {
         + result.push_prob
         + result.player_blackjack_prob
         + result.dealer_blackjack_prob
-        + result.surrender_prob;
+        + result.surrender_prob
+        + result.split_prob;
     
     assert!((total_prob - 1.0).abs() < 0.01, "概率总和应该接近1，实际: {}", total_prob);
     
     // A 多时，EV 应该比满8副牌更好
     let full_deck = calculator::create_full_8_deck();
-    let full_result = calculator.calculate_table_ev(&full_deck);
+    let full_result = calculator.calculate_table_ev(&full_deck).unwrap();
     
     assert!(result.ev > full_result.ev, 
         "A多时 EV 应该比满8副牌更好。A多牌组 EV: {}, 满8副牌 EV: {}", 
@@ -166,9 +175,9 @@ fn test_ace_rich_deck() {
 #[test]
 fn test_low_card_poor_deck() {
     let deck = create_low_card_poor_deck();
-    let calculator = Calculator::with_default_rules();
+    let calculator = skewed_deck_calculator();
     
-    let result = calculator.calculate_table_ev(&deck);
+    let result = calculator.calculate_table_ev(&deck).unwrap();
     
     println!("低牌较少牌组 EV: {:.6}", result.ev);
     println!("玩家胜率: {:.6}", result.player_win_prob);
@@ -185,13 +194,14 @@ fn test_low_card_poor_deck() {
         + result.push_prob
         + result.player_blackjack_prob
         + result.dealer_blackjack_prob
-        + result.surrender_prob;
+        + result.surrender_prob
+        + result.split_prob;
     
     assert!((total_prob - 1.0).abs() < 0.01, "概率总和应该接近1，实际: {}", total_prob);
     
     // 低牌少时，EV 应该比满8副牌更好
     let full_deck = calculator::create_full_8_deck();
-    let full_result = calculator.calculate_table_ev(&full_deck);
+    let full_result = calculator.calculate_table_ev(&full_deck).unwrap();
     
     assert!(result.ev > full_result.ev, 
         "低牌少时 EV 应该比满8副牌更好。低牌少牌组 EV: {}, 满8副牌 EV: {}", 
@@ -201,9 +211,9 @@ fn test_low_card_poor_deck() {
 #[test]
 fn test_extremely_favorable_deck() {
     let deck = create_extremely_favorable_deck();
-    let calculator = Calculator::with_default_rules();
+    let calculator = skewed_deck_calculator();
     
-    let result = calculator.calculate_table_ev(&deck);
+    let result = calculator.calculate_table_ev(&deck).unwrap();
     
     println!("极端有利牌组 EV: {:.6}", result.ev);
     println!("玩家黑杰克率: {:.6}", result.player_blackjack_prob);
@@ -220,13 +230,14 @@ fn test_extremely_favorable_deck() {
         + result.push_prob
         + result.player_blackjack_prob
         + result.dealer_blackjack_prob
-        + result.surrender_prob;
+        + result.surrender_prob
+        + result.split_prob;
     
     assert!((total_prob - 1.0).abs() < 0.01, "概率总和应该接近1，实际: {}", total_prob);
     
     // 与满8副牌对比
     let full_deck = calculator::create_full_8_deck();
-    let full_result = calculator.calculate_table_ev(&full_deck);
+    let full_result = calculator.calculate_table_ev(&full_deck).unwrap();
     
     println!("满8副牌 EV: {:.6}", full_result.ev);
     assert!(result.ev > full_result.ev, 
@@ -241,9 +252,9 @@ fn test_extremely_favorable_deck() {
 #[test]
 fn test_only_high_and_ace_deck() {
     let deck = create_only_high_and_ace_deck();
-    let calculator = Calculator::with_default_rules();
+    let calculator = skewed_deck_calculator();
     
-    let result = calculator.calculate_table_ev(&deck);
+    let result = calculator.calculate_table_ev(&deck).unwrap();
     
     println!("只有高牌和A的牌组 EV: {:.6}", result.ev);
     println!("玩家黑杰克率: {:.6}", result.player_blackjack_prob);
@@ -261,7 +272,8 @@ fn test_only_high_and_ace_deck() {
         + result.push_prob
         + result.player_blackjack_prob
         + result.dealer_blackjack_prob
-        + result.surrender_prob;
+        + result.surrender_prob
+        + result.split_prob;
     
     assert!((total_prob - 1.0).abs() < 0.01, "概率总和应该接近1，实际: {}", total_prob);
 }
@@ -282,8 +294,8 @@ fn test_balanced_favorable_deck() {
         deck.insert(Card::Number(n), 15);  // 较少
     }
     
-    let calculator = Calculator::with_default_rules();
-    let result = calculator.calculate_table_ev(&deck);
+    let calculator = skewed_deck_calculator();
+    let result = calculator.calculate_table_ev(&deck).unwrap();
     
     println!("平衡有利牌组 EV: {:.6}", result.ev);
     
@@ -293,13 +305,14 @@ fn test_balanced_favorable_deck() {
         + result.push_prob
         + result.player_blackjack_prob
         + result.dealer_blackjack_prob
-        + result.surrender_prob;
+        + result.surrender_prob
+        + result.split_prob;
     
     assert!((total_prob - 1.0).abs() < 0.01, "概率总和应该接近1，实际: {}", total_prob);
     
     // 应该比满8副牌好
     let full_deck = calculator::create_full_8_deck();
-    let full_result = calculator.calculate_table_ev(&full_deck);
+    let full_result = calculator.calculate_table_ev(&full_deck).unwrap();
     
     assert!(result.ev > full_result.ev, 
         "平衡有利牌组 EV 应该比满8副牌好。平衡有利牌组 EV: {}, 满8副牌 EV: {}", 
@@ -0,0 +1,157 @@
+//! 规则预设和规则文件加载测试
+
+use calculator::{CalculatorError, GameRules, HoleCardRule, SurrenderRule};
+
+#[test]
+fn test_all_presets_are_valid() {
+    let presets = GameRules::presets();
+    assert!(presets.len() >= 6);
+    for preset in presets {
+        let rules = GameRules::preset(preset.name).unwrap();
+        assert!(rules.validate().is_ok(), "预设 {} 无效", preset.name);
+        assert!(!preset.description.is_empty());
+    }
+
+    let strip = GameRules::preset("Strip-S17").unwrap();
+    assert_eq!(strip.deck_count, 4);
+    assert!(strip.dealer_stands_on_soft_17);
+    assert!(strip.allow_double_after_split);
+
+    let macau = GameRules::preset("macau-enhc").unwrap();
+    assert_eq!(macau.hole_card, HoleCardRule::NoHoleCard);
+    assert_eq!(macau.surrender, SurrenderRule::None);
+
+    let single = GameRules::preset("single-deck-6-5").unwrap();
+    assert_eq!(single.deck_count, 1);
+    assert_eq!(single.blackjack_payout, 1.2);
+
+    assert_eq!(
+        GameRules::preset("no-such-game").unwrap_err(),
+        CalculatorError::UnknownPreset("no-such-game".to_string())
+    );
+}
+
+#[test]
+fn test_load_from_toml_and_json() {
+    // 直接引用预设
+    let rules = GameRules::from_toml_str(r#"rules = "downtown-h17""#).unwrap();
+    assert_eq!(rules.deck_count, 2);
+    assert!(!rules.dealer_stands_on_soft_17);
+
+    // 在预设基础上覆盖字段
+    let rules = GameRules::from_toml_str(
+        r#"
+        [rules]
+        preset = "atlantic-city"
+        blackjack_payout = 1.2
+        surrender = "None"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(rules.deck_count, 8);
+    assert!(rules.allow_split);
+    assert_eq!(rules.blackjack_payout, 1.2);
+    assert_eq!(rules.surrender, SurrenderRule::None);
+
+    // 整个文件作为规则表，未写的字段取默认规则
+    let rules = GameRules::from_json_str(r#"{"deck_count": 6, "hole_card": "NoHoleCard"}"#).unwrap();
+    assert_eq!(rules.deck_count, 6);
+    assert_eq!(rules.hole_card, HoleCardRule::NoHoleCard);
+    assert_eq!(rules.blackjack_payout, GameRules::default().blackjack_payout);
+
+    let rules = GameRules::from_json_str(r#"{"rules": "european-obo"}"#).unwrap();
+    assert_eq!(rules.hole_card, HoleCardRule::NoHoleCardOriginalBetsOnly);
+}
+
+#[test]
+fn test_legacy_allow_surrender() {
+    // 旧版规则用 allow_surrender 布尔值：true 为后投降，false 为不允许投降
+    let rules = GameRules::from_toml_str("[rules]\npreset = \"strip-s17\"\nallow_surrender = false").unwrap();
+    assert_eq!(rules.surrender, SurrenderRule::None);
+    let rules = GameRules::from_json_str(r#"{"preset": "macau-enhc", "allow_surrender": true}"#).unwrap();
+    assert_eq!(rules.surrender, SurrenderRule::Late);
+
+    // 直接反序列化旧版序列化的规则（只有旧版的字段）；缺少的新字段取默认值
+    let legacy = r#"{"deck_count": 8, "allow_surrender": false, "allow_split": false,
+        "dealer_stands_on_soft_17": false, "allow_resplit": false,
+        "allow_double_after_split": false, "blackjack_payout": 1.5}"#;
+    let rules: GameRules = serde_json::from_str(legacy).unwrap();
+    assert_eq!(rules.surrender, SurrenderRule::None);
+    assert_eq!(rules.max_split_hands, GameRules::default().max_split_hands);
+    assert_eq!(rules.hole_card, HoleCardRule::Peek);
+    assert!(rules.validate().is_ok());
+    let without = legacy.replace(r#""allow_surrender": false, "#, "");
+    let rules: GameRules = serde_json::from_str(&without).unwrap();
+    assert_eq!(rules.surrender, SurrenderRule::Late);
+
+    let rules: GameRules = serde_json::from_str(&legacy.replace(r#""allow_surrender": false"#, r#""surrender": "EarlyVsTen""#)).unwrap();
+    assert_eq!(rules.surrender, SurrenderRule::EarlyVsTen);
+    assert!(matches!(
+        GameRules::from_json_str(r#"{"surrender": "Sometimes"}"#),
+        Err(CalculatorError::InvalidRulesFile(_))
+    ));
+}
+
+#[test]
+fn test_invalid_rules_documents() {
+    // 拼错的字段不会被静默忽略
+    assert!(matches!(
+        GameRules::from_toml_str("deck_cont = 6"),
+        Err(CalculatorError::InvalidRulesFile(_))
+    ));
+    assert!(matches!(
+        GameRules::from_json_str(r#"{"deck_count": "six"}"#),
+        Err(CalculatorError::InvalidRulesFile(_))
+    ));
+    assert!(matches!(
+        GameRules::from_toml_str("not toml ="),
+        Err(CalculatorError::InvalidRulesFile(_))
+    ));
+    assert_eq!(
+        GameRules::from_toml_str(r#"rules = "vegas""#).unwrap_err(),
+        CalculatorError::UnknownPreset("vegas".to_string())
+    );
+
+    // 加载后同样做规则校验
+    assert_eq!(
+        GameRules::from_toml_str("deck_count = 0").unwrap_err(),
+        CalculatorError::InvalidDeckCount(0)
+    );
+    assert_eq!(
+        GameRules::from_json_str(r#"{"preset": "strip-s17", "max_split_hands": 1}"#).unwrap_err(),
+        CalculatorError::InvalidMaxSplitHands(1)
+    );
+}
+
+#[test]
+fn test_load_from_file_and_spec() {
+    let dir = std::env::temp_dir().join(format!("calculator-presets-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let toml_path = dir.join("rules.toml");
+    std::fs::write(&toml_path, "[rules]\npreset = \"strip-s17\"\ndeck_count = 6\n").unwrap();
+    let rules = GameRules::from_file(&toml_path).unwrap();
+    assert_eq!(rules.deck_count, 6);
+    assert!(rules.dealer_stands_on_soft_17);
+
+    let json_path = dir.join("rules.json");
+    std::fs::write(&json_path, r#"{"rules": "macau-enhc"}"#).unwrap();
+    let rules = GameRules::from_spec(json_path.to_str().unwrap()).unwrap();
+    assert_eq!(rules.hole_card, HoleCardRule::NoHoleCard);
+
+    // 预设名称优先于路径
+    assert_eq!(GameRules::from_spec("strip-s17").unwrap().deck_count, 4);
+
+    let yaml_path = dir.join("rules.yaml");
+    std::fs::write(&yaml_path, "deck_count: 6").unwrap();
+    assert!(matches!(
+        GameRules::from_file(&yaml_path),
+        Err(CalculatorError::InvalidRulesFile(_))
+    ));
+    assert!(matches!(
+        GameRules::from_file(dir.join("missing.toml")),
+        Err(CalculatorError::InvalidRulesFile(_))
+    ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,78 @@
+//! 区分花色的牌靴测试
+
+use calculator::{
+    Calculator, Card, PlayingCard, Rank, Shoe, Suit, card_counts_to_point_counts,
+    create_full_8_deck,
+};
+
+#[test]
+fn test_full_shoe_counts() {
+    let shoe = Shoe::new(8);
+
+    assert_eq!(shoe.total(), 416);
+    assert_eq!(shoe.count(Rank::Queen, Suit::Hearts), 8);
+    assert_eq!(shoe.rank_count(Rank::Ace), 32);
+    assert_eq!(shoe.suit_count(Suit::Clubs), 104);
+    assert_eq!(Shoe::default(), shoe, "默认规则为 8 副牌");
+    assert!(Shoe::empty().is_empty());
+}
+
+#[test]
+fn test_projection_matches_card_counts() {
+    let shoe = Shoe::new(8);
+    let deck = create_full_8_deck();
+
+    assert_eq!(shoe.to_card_counts(), deck);
+    assert_eq!(shoe.to_point_counts(), card_counts_to_point_counts(&deck));
+
+    // 投影后的主注 EV 与按点数统计的牌组完全相同
+    let calculator = Calculator::with_default_rules();
+    let from_shoe = calculator.calculate_table_ev(&shoe.to_card_counts()).unwrap();
+    let from_deck = calculator.calculate_table_ev(&deck).unwrap();
+    assert_eq!(from_shoe.ev, from_deck.ev);
+}
+
+#[test]
+fn test_remove_dealt_cards() {
+    let mut shoe = Shoe::new(1);
+    assert!(shoe.remove(Rank::King, Suit::Spades));
+    assert!(!shoe.remove(Rank::King, Suit::Spades), "单副牌只有一张黑桃K");
+
+    assert_eq!(shoe.count(Rank::King, Suit::Spades), 0);
+    assert_eq!(shoe.rank_count(Rank::King), 3);
+    assert_eq!(shoe.to_card_counts()[&Card::Face], 11);
+
+    shoe.add(Rank::King, Suit::Spades, 1);
+    assert_eq!(shoe, Shoe::new(1));
+}
+
+#[test]
+fn test_blackjack_probabilities_single_deck() {
+    let shoe = Shoe::new(1);
+
+    // 有序发牌：A 在前或在后，各 4 × 16 种
+    let blackjack = 2.0 * 4.0 * 16.0 / (52.0 * 51.0);
+    assert!((shoe.blackjack_prob() - blackjack).abs() < 1e-12);
+
+    // 同花：每种花色 1 张A × 4 张10点牌
+    let suited = 2.0 * 4.0 * 4.0 / (52.0 * 51.0);
+    assert!((shoe.suited_blackjack_prob() - suited).abs() < 1e-12);
+
+    // 去掉黑桃A后，黑桃同花黑杰克不再可能
+    let mut shoe = shoe;
+    shoe.remove(Rank::Ace, Suit::Spades);
+    let suited = 2.0 * 3.0 * 4.0 / (51.0 * 50.0);
+    assert!((shoe.suited_blackjack_prob() - suited).abs() < 1e-12);
+}
+
+#[test]
+fn test_two_card_probability_pairs() {
+    // 完美对子（同牌面同花色）：单副牌不可能，两副牌为 1/103
+    let perfect_pair = |(rank1, suit1), (rank2, suit2)| rank1 == rank2 && suit1 == suit2;
+    assert_eq!(Shoe::new(1).two_card_probability(perfect_pair), 0.0);
+    assert!((Shoe::new(2).two_card_probability(perfect_pair) - 1.0 / 103.0).abs() < 1e-12);
+
+    let card = PlayingCard::from_rank(Rank::Jack, Suit::Diamonds);
+    assert_eq!(card.card, Card::Face);
+    assert_eq!(Rank::Ten.to_card(), Card::Number(10));
+}
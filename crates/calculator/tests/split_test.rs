@@ -0,0 +1,148 @@
+//! 分牌测试
+//! 
+//! 验证分牌、再分牌和分牌后加倍规则对上桌 EV 的影响
+
+use calculator::{
+    Calculator, Card, CardCounts, GameRules, StrategyMode, TableEVResult, create_full_8_deck,
+};
+
+/// 创建对子较多的小牌组（便于触发分牌）
+fn create_pair_rich_deck() -> CardCounts {
+    let mut deck = CardCounts::new();
+    deck.insert(Card::Ace, 3);
+    deck.insert(Card::Number(2), 2);
+    deck.insert(Card::Number(3), 2);
+    deck.insert(Card::Number(6), 2);
+    deck.insert(Card::Number(7), 2);
+    deck.insert(Card::Number(8), 4);
+    deck.insert(Card::Number(9), 3);
+    deck.insert(Card::Face, 6);
+    deck
+}
+
+/// 允许分牌的规则
+fn split_rules(allow_resplit: bool, allow_double_after_split: bool) -> GameRules {
+    GameRules {
+        allow_split: true,
+        allow_resplit,
+        allow_double_after_split,
+        ..GameRules::default()
+    }
+}
+
+/// 概率总和（含分牌概率）
+fn total_prob(result: &TableEVResult) -> f64 {
+    result.player_win_prob
+        + result.dealer_win_prob
+        + result.push_prob
+        + result.player_blackjack_prob
+        + result.dealer_blackjack_prob
+        + result.surrender_prob
+        + result.split_prob
+}
+
+#[test]
+fn test_split_disabled() {
+    let deck = create_pair_rich_deck();
+    let rules = GameRules {
+        allow_split: false,
+        ..GameRules::default()
+    };
+    let result = Calculator::new(rules).calculate_table_ev(&deck).unwrap();
+    
+    // 不允许分牌时，分牌概率和分牌 EV 都应该为0
+    assert_eq!(result.split_prob, 0.0);
+    assert_eq!(result.ev_split, 0.0);
+}
+
+#[test]
+fn test_split_enabled() {
+    let deck = create_pair_rich_deck();
+    let result = Calculator::new(split_rules(false, false)).calculate_table_ev(&deck).unwrap();
+    
+    assert!(result.split_prob > 0.0, "对子较多时应该出现分牌");
+    assert!((total_prob(&result) - 1.0).abs() < 1e-9, "概率总和应该为1，实际: {}", total_prob(&result));
+    
+    // 总 EV 由各部分组成
+    let sum = result.ev_normal + result.ev_double + result.ev_surrender + result.ev_split;
+    assert!((result.ev - sum).abs() < 1e-12);
+}
+
+#[test]
+fn test_resplit_limit() {
+    let deck = create_pair_rich_deck();
+    
+    // 最多2手时，允许再分牌等同于不允许再分牌
+    let limited = GameRules {
+        max_split_hands: 2,
+        ..split_rules(true, false)
+    };
+    let no_resplit = Calculator::new(split_rules(false, false)).calculate_table_ev(&deck).unwrap();
+    let resplit_limited = Calculator::new(limited).calculate_table_ev(&deck).unwrap();
+    assert!((no_resplit.ev - resplit_limited.ev).abs() < 1e-12);
+    
+    // 允许再分到4手时，分牌 EV 和总 EV 都应该提高
+    let resplit = Calculator::new(split_rules(true, false)).calculate_table_ev(&deck).unwrap();
+    assert!(resplit.ev_split > no_resplit.ev_split, "{} <= {}", resplit.ev_split, no_resplit.ev_split);
+    assert!(resplit.ev > no_resplit.ev, "{} <= {}", resplit.ev, no_resplit.ev);
+}
+
+#[test]
+fn test_double_after_split() {
+    let deck = create_pair_rich_deck();
+    
+    let no_das = Calculator::new(split_rules(false, false)).calculate_table_ev(&deck).unwrap();
+    let das = Calculator::new(split_rules(false, true)).calculate_table_ev(&deck).unwrap();
+    
+    // 分牌概率不受分牌后加倍影响，只影响分牌 EV
+    assert!((no_das.split_prob - das.split_prob).abs() < 1e-12);
+    assert!(das.ev_split > no_das.ev_split, "{} <= {}", das.ev_split, no_das.ev_split);
+    assert!(das.ev > no_das.ev, "{} <= {}", das.ev, no_das.ev);
+}
+
+#[test]
+fn test_full_shoe_split_gain() {
+    // 满8副牌：只允许分牌比不分牌约高 0.4%，再加上再分牌和分牌后加倍约高 0.6%
+    let deck = create_full_8_deck();
+    let no_split = GameRules {
+        allow_split: false,
+        ..GameRules::default()
+    };
+    let without = Calculator::new(no_split).calculate_table_ev(&deck).unwrap();
+    let split = Calculator::new(split_rules(false, false)).calculate_table_ev(&deck).unwrap();
+    let full = Calculator::new(split_rules(true, true)).calculate_table_ev(&deck).unwrap();
+
+    let split_gain = split.ev - without.ev;
+    let full_gain = full.ev - without.ev;
+    assert!(split_gain > 0.0030 && split_gain < 0.0045, "分牌带来的 EV: {}", split_gain);
+    assert!(full_gain > 0.0055 && full_gain < 0.0065, "分牌、再分牌和分牌后加倍带来的 EV: {}", full_gain);
+    assert!((total_prob(&full) - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_resplit_respects_total_hand_limit() {
+    // 只有 8：对明牌 8 的 8,8 每手停牌都赢 1（庄家 16 点要牌必然爆牌），
+    // 最优策略的 EV 因此等于总手数，不能超过 max_split_hands
+    let mut deck = CardCounts::new();
+    deck.insert(Card::Number(8), 16);
+    for max_split_hands in 2..=4 {
+        let rules = GameRules {
+            max_split_hands,
+            ..split_rules(true, false)
+        };
+        let result = Calculator::with_strategy_mode(rules, StrategyMode::Optimal).calculate_table_ev(&deck).unwrap();
+        assert!((result.ev - max_split_hands as f64).abs() < 1e-12, "最多 {} 手: {}", max_split_hands, result.ev);
+    }
+}
+
+#[test]
+fn test_baseline_rules_deserialize() {
+    // 分牌手数上限之前的规则字段：缺少 max_split_hands 时取 4
+    let baseline = r#"{"deck_count": 6, "allow_surrender": true, "allow_split": true,
+        "dealer_stands_on_soft_17": true, "allow_resplit": true,
+        "allow_double_after_split": true, "blackjack_payout": 1.5}"#;
+    let rules: GameRules = serde_json::from_str(baseline).unwrap();
+    assert_eq!(rules.max_split_hands, 4);
+    assert!(rules.allow_resplit);
+    assert!(rules.validate().is_ok());
+}
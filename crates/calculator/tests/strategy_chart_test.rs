@@ -0,0 +1,217 @@
+//! 策略表 JSON / CSV 导入导出测试
+
+mod common;
+
+use calculator::{
+    Action, BasicStrategy, CalculatorError, Card, DoubleRule, GameRules, StrategyChart, Strategy,
+};
+use calculator::error::Result;
+use common::hand_of;
+
+const RANKS: [Card; 10] = [
+    Card::Ace,
+    Card::Number(2),
+    Card::Number(3),
+    Card::Number(4),
+    Card::Number(5),
+    Card::Number(6),
+    Card::Number(7),
+    Card::Number(8),
+    Card::Number(9),
+    Card::Face,
+];
+
+/// 图表中一个单元格的代码
+fn cell(chart: &StrategyChart, hand: &str, upcard: &str) -> String {
+    let column = chart.upcards.iter().position(|label| label == upcard).unwrap();
+    let row = chart.rows.iter().find(|row| row.hand == hand).unwrap();
+    row.actions[column].clone()
+}
+
+/// 把手写表导出的 CSV 中一个单元格替换为 `code`
+fn edited_csv(hand: &str, upcard: &str, code: &str) -> String {
+    let mut chart = StrategyChart::from(&BasicStrategy::new());
+    let column = chart.upcards.iter().position(|label| label == upcard).unwrap();
+    let row = chart.rows.iter_mut().find(|row| row.hand == hand).unwrap();
+    row.actions[column] = code.to_string();
+    chart.to_csv()
+}
+
+/// 从 CSV 文本导入策略
+fn strategy_from_csv(text: &str) -> Result<BasicStrategy> {
+    StrategyChart::from_csv_str(text)?.to_strategy()
+}
+
+fn chart_error(text: &str) -> String {
+    match strategy_from_csv(text) {
+        Err(CalculatorError::InvalidStrategyChart(message)) => message,
+        other => panic!("应当返回 InvalidStrategyChart: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_export_builtin_chart() {
+    let chart = StrategyChart::from(&BasicStrategy::new());
+    assert_eq!(chart.upcards, ["2", "3", "4", "5", "6", "7", "8", "9", "10", "A"]);
+    // 硬5-20、软13-20、10 种对子
+    assert_eq!(chart.rows.len(), 16 + 8 + 10);
+
+    assert_eq!(cell(&chart, "H16", "10"), "R");
+    assert_eq!(cell(&chart, "H16", "6"), "S");
+    assert_eq!(cell(&chart, "H11", "A"), "H");
+    assert_eq!(cell(&chart, "H11", "10"), "D");
+    assert_eq!(cell(&chart, "S18", "3"), "Ds");
+    assert_eq!(cell(&chart, "S17", "3"), "D");
+    assert_eq!(cell(&chart, "P8", "A"), "P");
+    assert_eq!(cell(&chart, "P5", "6"), "D");
+    assert_eq!(cell(&chart, "P10", "6"), "S");
+
+    let csv = chart.to_csv();
+    assert!(csv.starts_with("hand,2,3,4,5,6,7,8,9,10,A\nH5,H,H,H"), "{csv}");
+}
+
+#[test]
+fn test_round_trip_keeps_two_card_decisions() {
+    let builtin = BasicStrategy::new();
+    let csv = builtin.to_csv();
+    let imported = strategy_from_csv(&csv).unwrap();
+    assert_eq!(imported.to_csv(), csv);
+
+    let json = builtin.to_json();
+    let from_json = StrategyChart::from_json_str(&json).and_then(|chart| chart.to_strategy()).unwrap();
+    assert_eq!(StrategyChart::from(&from_json), StrategyChart::from(&builtin));
+    assert_eq!(StrategyChart::from_json_str(&json).unwrap(), StrategyChart::from_csv_str(&csv).unwrap());
+
+    // 两张牌的决策与手写表一致
+    let rules = GameRules::preset("strip-s17").unwrap();
+    for (i, &first) in RANKS.iter().enumerate() {
+        for &second in &RANKS[i..] {
+            let hand = hand_of(&[first, second]);
+            for up in RANKS {
+                assert_eq!(
+                    imported.decide(&hand, up, &[0; 11], &rules),
+                    builtin.decide(&hand, up, &[0; 11], &rules),
+                    "{first:?},{second:?} vs {up:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_chart_codes_and_fallbacks() {
+    let rules = GameRules::preset("strip-s17").unwrap();
+    let no_das = GameRules {
+        allow_double_after_split: false,
+        ..rules.clone()
+    };
+    let fours = hand_of(&[Card::Number(4), Card::Number(4)]);
+    let strategy = strategy_from_csv(&edited_csv("P4", "5", "Ph")).unwrap();
+    assert_eq!(strategy.decide(&fours, Card::Number(5), &[0; 11], &rules), Action::Split);
+    assert_eq!(strategy.decide(&fours, Card::Number(5), &[0; 11], &no_das), Action::Hit);
+    assert_eq!(cell(&StrategyChart::from(&strategy), "P4", "5"), "Ph");
+
+    // 软17 Ds：不能加倍时停牌；要牌后的手牌也停牌
+    let strategy = strategy_from_csv(&edited_csv("S17", "3", "Ds")).unwrap();
+    let no_soft_double = GameRules {
+        double: DoubleRule::TenToEleven,
+        ..rules.clone()
+    };
+    let soft_17 = hand_of(&[Card::Ace, Card::Number(6)]);
+    assert_eq!(strategy.decide(&soft_17, Card::Number(3), &[0; 11], &rules), Action::Double);
+    assert_eq!(strategy.decide(&soft_17, Card::Number(3), &[0; 11], &no_soft_double), Action::Stand);
+    let three_card_soft_17 = hand_of(&[Card::Ace, Card::Number(2), Card::Number(4)]);
+    assert_eq!(strategy.decide(&three_card_soft_17, Card::Number(3), &[0; 11], &rules), Action::Stand);
+
+    // Rs：不能投降时停牌
+    let strategy = strategy_from_csv(&edited_csv("H17", "A", "Rs")).unwrap();
+    let no_surrender = GameRules {
+        surrender: calculator::SurrenderRule::None,
+        ..rules.clone()
+    };
+    let hard_17 = hand_of(&[Card::Face, Card::Number(7)]);
+    assert_eq!(strategy.decide(&hard_17, Card::Ace, &[0; 11], &rules), Action::Surrender);
+    assert_eq!(strategy.decide(&hard_17, Card::Ace, &[0; 11], &no_surrender), Action::Stand);
+}
+
+#[test]
+fn test_csv_comments_and_case() {
+    let csv = BasicStrategy::new().to_csv().to_lowercase();
+    let commented = format!("# 手写表\n\n{csv}");
+    let strategy = strategy_from_csv(&commented).unwrap();
+    assert_eq!(StrategyChart::from(&strategy), StrategyChart::from(&BasicStrategy::new()));
+}
+
+#[test]
+fn test_csv_saved_by_excel() {
+    // Excel 保存的 CSV：UTF-8 BOM、\r\n 换行，部分单元格带引号
+    let csv = BasicStrategy::new().to_csv();
+    let excel = format!("\u{feff}{}", csv.replace('\n', "\r\n").replace(",Ds,", ",\"Ds\","));
+    assert!(csv.contains(",Ds,"));
+    let strategy = strategy_from_csv(&excel).unwrap();
+    assert_eq!(strategy.to_csv(), csv);
+
+    let dir = std::env::temp_dir().join(format!("calculator-excel-chart-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("chart.csv");
+    std::fs::write(&path, &excel).unwrap();
+    assert_eq!(StrategyChart::from(&BasicStrategy::from_file(&path).unwrap()), StrategyChart::from(&BasicStrategy::new()));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_validation_errors() {
+    let builtin = BasicStrategy::new().to_csv();
+
+    let without_row: String = builtin
+        .lines()
+        .filter(|line| !line.starts_with("H12,"))
+        .map(|line| format!("{line}\n"))
+        .collect();
+    assert_eq!(chart_error(&without_row), "缺少行: H12");
+    let duplicate_row = format!("{builtin}H12,H,H,S,S,S,H,H,H,H,H\n");
+    assert_eq!(chart_error(&duplicate_row), "重复的行: H12");
+    assert_eq!(chart_error(&format!("{builtin}H22,H,H,H,H,H,H,H,H,H,H\n")), "未知的行: H22");
+
+    assert_eq!(chart_error(&builtin.replacen(",A\n", ",10\n", 1)), "重复的列: 10");
+    assert_eq!(chart_error(&builtin.replacen(",A\n", "\n", 1)), "缺少列: A");
+    assert_eq!(chart_error(&edited_csv("H12", "4", "")), "行 H12 列 4 缺少单元格");
+    assert_eq!(chart_error(&edited_csv("H12", "4", "X")), "行 H12 列 4: 未知的动作代码 X");
+    assert_eq!(chart_error(&edited_csv("H12", "4", "P")), "行 H12 列 4: 只有对子行可以分牌");
+    assert_eq!(chart_error(&edited_csv("S17", "4", "R")), "行 S17 列 4: 软点数不能投降");
+    assert_eq!(chart_error(&edited_csv("PA", "4", "R")), "行 PA 列 4: 软点数不能投降");
+
+    // 对子行不分牌的单元格必须与总点数行一致
+    assert_eq!(chart_error(&edited_csv("P5", "6", "H")), "行 P5 列 6 为 H，与行 H10 的 D 矛盾");
+    assert_eq!(chart_error(&edited_csv("P5", "6", "Ph")), "行 P5 列 6 为 Ph，与行 H10 的 D 矛盾");
+
+    let short_row = builtin.replacen("H5,H,H,H,H,H,H,H,H,H,H", "H5,H,H", 1);
+    assert_eq!(chart_error(&short_row), "行 H5 有 2 个单元格，应为 10 个");
+    assert_eq!(chart_error(""), "CSV 缺少表头");
+    assert!(matches!(
+        StrategyChart::from_json_str("{\"upcards\": []}").and_then(|chart| chart.to_strategy()),
+        Err(CalculatorError::InvalidStrategyChart(_))
+    ));
+}
+
+#[test]
+fn test_load_chart_file() {
+    let dir = std::env::temp_dir().join(format!("strategy-chart-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let csv_path = dir.join("chart.csv");
+    let json_path = dir.join("chart.json");
+    std::fs::write(&csv_path, BasicStrategy::new().to_csv()).unwrap();
+    std::fs::write(&json_path, BasicStrategy::new().to_json()).unwrap();
+
+    let from_csv = BasicStrategy::from_file(&csv_path).unwrap();
+    let from_json = BasicStrategy::from_file(&json_path).unwrap();
+    assert_eq!(StrategyChart::from(&from_csv), StrategyChart::from(&from_json));
+
+    let txt_path = dir.join("chart.txt");
+    std::fs::write(&txt_path, "").unwrap();
+    let Err(CalculatorError::InvalidStrategyChart(message)) = BasicStrategy::from_file(&txt_path) else {
+        panic!("不支持的扩展名应当返回错误");
+    };
+    assert!(message.contains("chart.txt"), "{message}");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
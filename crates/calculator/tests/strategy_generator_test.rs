@@ -0,0 +1,129 @@
+//! 基础策略生成器测试：按规则生成的策略表
+
+mod common;
+
+use calculator::{
+    create_full_deck, Action, BasicStrategy, Calculator, CalculatorError, Card, CardCounts, GameRules,
+    Strategy, StrategyMode, SurrenderRule,
+};
+use common::hand_of;
+
+/// 允许分牌（不再分牌）和后投降的规则
+fn rules(deck_count: u8, dealer_stands_on_soft_17: bool, allow_double_after_split: bool) -> GameRules {
+    GameRules {
+        deck_count,
+        dealer_stands_on_soft_17,
+        allow_double_after_split,
+        allow_split: true,
+        allow_resplit: false,
+        surrender: SurrenderRule::Late,
+        ..GameRules::default()
+    }
+}
+
+fn decide(strategy: &BasicStrategy, rules: &GameRules, cards: &[Card], up: Card) -> Action {
+    strategy.decide(&hand_of(cards), up, &[0; 11], rules)
+}
+
+#[test]
+fn test_double_after_split_chart() {
+    let das = rules(1, true, true);
+    let no_das = rules(1, true, false);
+    let with_das = BasicStrategy::for_rules(&das).unwrap();
+    let without_das = BasicStrategy::for_rules(&no_das).unwrap();
+
+    let fours = [Card::Number(4), Card::Number(4)];
+    let twos = [Card::Number(2), Card::Number(2)];
+    let sixes = [Card::Number(6), Card::Number(6)];
+    assert_eq!(decide(&with_das, &das, &fours, Card::Number(5)), Action::Split);
+    assert_eq!(decide(&without_das, &no_das, &fours, Card::Number(5)), Action::Double);
+    assert_eq!(decide(&with_das, &das, &twos, Card::Number(2)), Action::Split);
+    assert_eq!(decide(&without_das, &no_das, &twos, Card::Number(2)), Action::Hit);
+    assert_eq!(decide(&with_das, &das, &sixes, Card::Number(7)), Action::Split);
+    assert_eq!(decide(&without_das, &no_das, &sixes, Card::Number(7)), Action::Hit);
+
+    // 单副牌：硬11 对A、硬8 对6 加倍，9-9 对7 停牌
+    for (strategy, rules) in [(&with_das, &das), (&without_das, &no_das)] {
+        assert_eq!(decide(strategy, rules, &[Card::Number(5), Card::Number(6)], Card::Ace), Action::Double);
+        assert_eq!(decide(strategy, rules, &[Card::Number(5), Card::Number(3)], Card::Number(6)), Action::Double);
+        assert_eq!(decide(strategy, rules, &[Card::Number(9), Card::Number(9)], Card::Number(7)), Action::Stand);
+        assert_eq!(decide(strategy, rules, &[Card::Face, Card::Number(6)], Card::Face), Action::Surrender);
+    }
+
+    // 按规则生成的表优于手写表，接近组合依赖最优策略
+    let deck = create_full_deck(1);
+    let ev = |calculator: Calculator| calculator.calculate_table_ev(&deck).unwrap().ev;
+    let generated = ev(Calculator::new(das.clone()).with_strategy(with_das));
+    let handwritten = ev(Calculator::new(das.clone()));
+    let optimal = ev(Calculator::with_strategy_mode(das, StrategyMode::Optimal));
+    assert!(generated > handwritten, "{} <= {}", generated, handwritten);
+    assert!(optimal - generated < 0.001, "{} vs {}", generated, optimal);
+}
+
+#[test]
+fn test_soft_17_rule_chart() {
+    let h17 = rules(2, false, true);
+    let s17 = rules(2, true, true);
+    let hits_soft_17 = BasicStrategy::for_rules(&h17).unwrap();
+    let stands_soft_17 = BasicStrategy::for_rules(&s17).unwrap();
+
+    // 庄家软17要牌时 A-8 对6 加倍，15、17 对A 投降
+    let soft_19 = [Card::Ace, Card::Number(8)];
+    assert_eq!(decide(&hits_soft_17, &h17, &soft_19, Card::Number(6)), Action::Double);
+    assert_eq!(decide(&stands_soft_17, &s17, &soft_19, Card::Number(6)), Action::Stand);
+    let hard_15 = [Card::Face, Card::Number(5)];
+    assert_eq!(decide(&hits_soft_17, &h17, &hard_15, Card::Ace), Action::Surrender);
+    assert_eq!(decide(&stands_soft_17, &s17, &hard_15, Card::Ace), Action::Hit);
+    let hard_17 = [Card::Face, Card::Number(7)];
+    assert_eq!(decide(&hits_soft_17, &h17, &hard_17, Card::Ace), Action::Surrender);
+    assert_eq!(decide(&stands_soft_17, &s17, &hard_17, Card::Ace), Action::Stand);
+
+    // 要牌后的手牌：硬16 对6 停牌，对10 要牌
+    let three_card_16 = [Card::Number(4), Card::Number(2), Card::Face];
+    assert_eq!(decide(&stands_soft_17, &s17, &three_card_16, Card::Number(6)), Action::Stand);
+    assert_eq!(decide(&stands_soft_17, &s17, &three_card_16, Card::Face), Action::Hit);
+}
+
+#[test]
+fn test_double_fallback_from_evs() {
+    let rules = rules(1, true, true);
+    let hit_or_stand = [Action::Hit, Action::Stand];
+    let soft_18 = hand_of(&[Card::Ace, Card::Number(7)]);
+
+    // 完整牌靴：软18 对4 加倍，不能加倍时停牌；硬11 不能加倍时要牌
+    let full = BasicStrategy::for_rules(&rules).unwrap();
+    assert_eq!(full.decide(&soft_18, Card::Number(4), &[0; 11], &rules), Action::Double);
+    assert_eq!(full.decide_among(&soft_18, Card::Number(4), &[0; 11], &rules, &hit_or_stand), Action::Stand);
+    let hard_11 = hand_of(&[Card::Number(5), Card::Number(6)]);
+    assert_eq!(full.decide_among(&hard_11, Card::Number(6), &[0; 11], &rules, &hit_or_stand), Action::Hit);
+
+    // A 和小牌很多的牌组：软18 对6 加倍，不能加倍时要牌（要到A、2、3 都更好）
+    let deck: CardCounts = [
+        (Card::Ace, 4),
+        (Card::Number(2), 4),
+        (Card::Number(3), 4),
+        (Card::Number(6), 4),
+        (Card::Number(7), 4),
+        (Card::Face, 8),
+    ]
+    .into_iter()
+    .collect();
+    let small_rich = BasicStrategy::generate(&rules, &deck).unwrap();
+    assert_eq!(small_rich.decide(&soft_18, Card::Number(6), &[0; 11], &rules), Action::Double);
+    assert_eq!(small_rich.decide_among(&soft_18, Card::Number(6), &[0; 11], &rules, &hit_or_stand), Action::Hit);
+
+    // 两张牌不能组成的 21 点单元格来自手写表
+    assert_eq!(decide(&small_rich, &rules, &[Card::Face, Card::Number(5), Card::Number(6)], Card::Ace), Action::Stand);
+    assert_eq!(decide(&small_rich, &rules, &[Card::Ace, Card::Number(3), Card::Number(7)], Card::Face), Action::Stand);
+}
+
+#[test]
+fn test_generate_validates_deck() {
+    let rules = rules(1, true, true);
+    let mut deck = create_full_deck(1);
+    deck.insert(Card::Ace, 5);
+    assert!(matches!(
+        BasicStrategy::generate(&rules, &deck),
+        Err(CalculatorError::ImpossibleComposition { .. })
+    ));
+}
@@ -0,0 +1,93 @@
+//! 策略模式测试
+//! 
+//! 验证组合依赖最优策略（StrategyMode::Optimal）的结果不差于基础策略
+
+use calculator::{Calculator, Card, CardCounts, GameRules, StrategyMode, TableEVResult};
+
+/// 创建单副牌
+fn create_single_deck() -> CardCounts {
+    let mut deck = CardCounts::new();
+    deck.insert(Card::Ace, 4);
+    for n in 2..=10 {
+        deck.insert(Card::Number(n), 4);
+    }
+    deck.insert(Card::Face, 12);
+    deck
+}
+
+/// 创建严重偏斜的小牌组（低牌耗尽，高牌较多）
+fn create_depleted_deck() -> CardCounts {
+    let mut deck = CardCounts::new();
+    deck.insert(Card::Ace, 3);
+    deck.insert(Card::Number(5), 2);
+    deck.insert(Card::Number(7), 3);
+    deck.insert(Card::Number(8), 3);
+    deck.insert(Card::Number(9), 3);
+    deck.insert(Card::Number(10), 4);
+    deck.insert(Card::Face, 8);
+    deck
+}
+
+/// 概率总和（含分牌概率）
+fn total_prob(result: &TableEVResult) -> f64 {
+    result.player_win_prob
+        + result.dealer_win_prob
+        + result.push_prob
+        + result.player_blackjack_prob
+        + result.dealer_blackjack_prob
+        + result.surrender_prob
+        + result.split_prob
+}
+
+#[test]
+fn test_default_mode_is_basic() {
+    assert_eq!(Calculator::with_default_rules().strategy_mode(), StrategyMode::Basic);
+    
+    let calculator = Calculator::with_strategy_mode(GameRules::default(), StrategyMode::Optimal);
+    assert_eq!(calculator.strategy_mode(), StrategyMode::Optimal);
+}
+
+#[test]
+fn test_optimal_not_worse_than_basic() {
+    let rules = GameRules {
+        allow_split: true,
+        allow_double_after_split: true,
+        ..GameRules::default()
+    };
+    let calculator = Calculator::new(rules);
+    
+    for deck in [create_single_deck(), create_depleted_deck()] {
+        let comparison = calculator.compare_strategies(&deck).unwrap();
+        
+        assert!(
+            comparison.ev_gain >= -1e-12,
+            "最优策略的 EV 不应低于基础策略，基础: {}，最优: {}",
+            comparison.basic.ev,
+            comparison.optimal.ev
+        );
+        assert!((comparison.ev_gain - (comparison.optimal.ev - comparison.basic.ev)).abs() < 1e-15);
+        assert!((total_prob(&comparison.optimal) - 1.0).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_optimal_mode_matches_comparison() {
+    let deck = create_depleted_deck();
+    let rules = GameRules::default();
+    
+    let optimal = Calculator::with_strategy_mode(rules.clone(), StrategyMode::Optimal)
+        .calculate_table_ev(&deck).unwrap();
+    let comparison = Calculator::new(rules).compare_strategies(&deck).unwrap();
+    
+    assert!((optimal.ev - comparison.optimal.ev).abs() < 1e-12);
+}
+
+#[test]
+fn test_optimal_single_deck_range() {
+    let deck = create_single_deck();
+    let result = Calculator::with_strategy_mode(GameRules::default(), StrategyMode::Optimal)
+        .calculate_table_ev(&deck).unwrap();
+    
+    // 单副牌（不分牌、软17要牌、可投降）最优策略的庄家优势应在1%以内
+    assert!(result.ev < 0.0 && result.ev > -0.01, "EV 超出合理范围: {}", result.ev);
+}
@@ -0,0 +1,164 @@
+//! 可替换的玩家策略（`Strategy`）测试
+
+mod common;
+
+use calculator::{
+    create_full_deck, Action, BasicStrategy, Calculator, Card, GameRules, Hand, PointCounts,
+    Strategy, StrategyMode, SurrenderRule,
+};
+use std::sync::Mutex;
+use common::{hand_of, single_deck_rules};
+
+/// 模仿庄家：17 点以下要牌，其余停牌
+struct MimicDealer;
+
+impl Strategy for MimicDealer {
+    fn decide(&self, hand: &Hand, _: Card, _: &PointCounts, _: &GameRules) -> Action {
+        if hand.value() < 17 {
+            Action::Hit
+        } else {
+            Action::Stand
+        }
+    }
+}
+
+/// 按基础策略决策，同时记录收到的剩余牌组的最大张数
+#[derive(Default)]
+struct ShoeRecorder {
+    basic: BasicStrategy,
+    max_cards: Mutex<u32>,
+}
+
+impl Strategy for ShoeRecorder {
+    fn decide(&self, hand: &Hand, dealer_up_card: Card, shoe: &PointCounts, rules: &GameRules) -> Action {
+        let mut max_cards = self.max_cards.lock().unwrap();
+        *max_cards = (*max_cards).max(shoe.iter().sum());
+        self.basic.decide(hand, dealer_up_card, shoe, rules)
+    }
+}
+
+#[test]
+fn test_basic_strategy_decisions() {
+    let strategy = BasicStrategy::new();
+    let shoe: PointCounts = [0; 11];
+    let rules = GameRules {
+        surrender: SurrenderRule::Late,
+        ..single_deck_rules()
+    };
+    let decide = |cards: &[Card], up: Card, rules: &GameRules| {
+        strategy.decide(&hand_of(cards), up, &shoe, rules)
+    };
+
+    let hard_16 = [Card::Face, Card::Number(6)];
+    assert_eq!(decide(&hard_16, Card::Face, &rules), Action::Surrender);
+    let no_surrender = GameRules {
+        surrender: SurrenderRule::None,
+        ..rules.clone()
+    };
+    assert_eq!(decide(&hard_16, Card::Face, &no_surrender), Action::Hit);
+    assert_eq!(decide(&hard_16, Card::Number(6), &rules), Action::Stand);
+
+    assert_eq!(decide(&[Card::Number(5), Card::Number(6)], Card::Number(6), &rules), Action::Double);
+    // 三张牌的软18不能加倍时停牌
+    let soft_18 = [Card::Ace, Card::Number(2), Card::Number(5)];
+    assert_eq!(decide(&soft_18, Card::Number(6), &rules), Action::Stand);
+
+    let eights = [Card::Number(8), Card::Number(8)];
+    let split_rules = GameRules::preset("strip-s17").unwrap();
+    assert_eq!(decide(&eights, Card::Number(6), &split_rules), Action::Split);
+    let no_split = GameRules {
+        allow_split: false,
+        ..split_rules
+    };
+    assert_eq!(decide(&eights, Card::Number(6), &no_split), Action::Stand);
+}
+
+#[test]
+fn test_decide_among_falls_back() {
+    let shoe: PointCounts = [0; 11];
+    let rules = single_deck_rules();
+    let hit_or_stand = [Action::Hit, Action::Stand];
+
+    // 默认回退：不能加倍的软18停牌，硬11要牌
+    let always_double = |hand: &[Card]| {
+        struct AlwaysDouble;
+        impl Strategy for AlwaysDouble {
+            fn decide(&self, _: &Hand, _: Card, _: &PointCounts, _: &GameRules) -> Action {
+                Action::Double
+            }
+        }
+        AlwaysDouble.decide_among(&hand_of(hand), Card::Number(6), &shoe, &rules, &hit_or_stand)
+    };
+    assert_eq!(always_double(&[Card::Ace, Card::Number(7)]), Action::Stand);
+    assert_eq!(always_double(&[Card::Number(5), Card::Number(6)]), Action::Hit);
+
+    let allowed = [Action::Hit, Action::Stand, Action::Double];
+    let hand = hand_of(&[Card::Number(5), Card::Number(6)]);
+    assert_eq!(MimicDealer.decide_among(&hand, Card::Face, &shoe, &rules, &allowed), Action::Hit);
+}
+
+#[test]
+fn test_default_strategy_matches_basic_strategy() {
+    let rules = GameRules::preset("strip-s17").unwrap();
+    let deck = create_full_deck(rules.deck_count);
+    let default = Calculator::new(rules.clone()).calculate_table_ev(&deck).unwrap();
+    let explicit = Calculator::new(rules)
+        .with_strategy(BasicStrategy::new())
+        .calculate_table_ev(&deck)
+        .unwrap();
+    assert_eq!(default.ev, explicit.ev);
+    assert_eq!(default.variance, explicit.variance);
+}
+
+#[test]
+fn test_custom_strategy_changes_ev() {
+    let rules = single_deck_rules();
+    let deck = create_full_deck(1);
+    let basic = Calculator::new(rules.clone()).calculate_table_ev(&deck).unwrap();
+    let calculator = Calculator::new(rules).with_strategy(MimicDealer);
+    let mimic = calculator.calculate_table_ev(&deck).unwrap();
+
+    // 模仿庄家不加倍、不投降，约输 5%
+    assert!(mimic.ev < basic.ev - 0.03, "{} vs {}", mimic.ev, basic.ev);
+    assert_eq!(mimic.ev_double, 0.0);
+    assert_eq!(mimic.surrender_prob, 0.0);
+    let hard_16 = hand_of(&[Card::Number(9), Card::Number(7)]);
+    let action = calculator.strategy().decide(&hard_16, Card::Number(6), &[0; 11], calculator.rules());
+    assert_eq!(action, Action::Hit);
+
+    // 最优策略模式不受策略影响
+    let optimal = |calculator: Calculator| calculator.calculate_table_ev(&deck).unwrap().ev;
+    let rules = single_deck_rules();
+    assert_eq!(
+        optimal(Calculator::with_strategy_mode(rules.clone(), StrategyMode::Optimal)),
+        optimal(Calculator::with_strategy_mode(rules, StrategyMode::Optimal).with_strategy(MimicDealer))
+    );
+}
+
+#[test]
+fn test_strategy_receives_remaining_shoe() {
+    let rules = single_deck_rules();
+    let recorder = std::sync::Arc::new(ShoeRecorder::default());
+    let calculator = Calculator::new(rules).with_strategy(recorder.clone());
+    calculator.calculate_table_ev(&create_full_deck(1)).unwrap();
+
+    // 第一次决策时玩家两张牌和庄家明牌已经发出
+    assert_eq!(*recorder.max_cards.lock().unwrap(), 49);
+}
+
+#[cfg(feature = "exact")]
+#[test]
+fn test_exact_ev_uses_custom_strategy() {
+    let mut deck = calculator::CardCounts::new();
+    deck.insert(Card::Ace, 1);
+    for n in 2..=9 {
+        deck.insert(Card::Number(n), 1);
+    }
+    deck.insert(Card::Face, 4);
+
+    let calculator = Calculator::new(single_deck_rules()).with_strategy(MimicDealer);
+    let result = calculator.calculate_exact_ev(&deck).unwrap();
+    assert!(result.deviation.abs() < 1e-10, "偏差: {}", result.deviation);
+    let basic = Calculator::new(single_deck_rules()).calculate_exact_ev(&deck).unwrap();
+    assert_ne!(result.ev, basic.ev);
+}
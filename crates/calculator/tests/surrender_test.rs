@@ -0,0 +1,109 @@
+//! 早投降 / 后投降规则测试
+
+mod common;
+
+use calculator::strategy::BasicStrategy;
+use calculator::{
+    Action, Calculator, Card, CardCounts, GameRules, HoleCardRule, SurrenderRule,
+    create_full_8_deck,
+};
+use common::hand_of;
+
+/// 剩余全是10点牌（明牌A时庄家必然黑杰克）
+fn create_tens_only_deck() -> CardCounts {
+    let mut deck = CardCounts::new();
+    deck.insert(Card::Face, 10);
+    deck
+}
+
+fn rules_with(surrender: SurrenderRule, hole_card: HoleCardRule) -> GameRules {
+    GameRules {
+        surrender,
+        hole_card,
+        ..GameRules::default()
+    }
+}
+
+#[test]
+fn test_surrender_rules_ordering() {
+    let deck = create_full_8_deck();
+    let table_ev = |surrender| {
+        let result = Calculator::new(rules_with(surrender, HoleCardRule::Peek)).calculate_table_ev(&deck).unwrap();
+        let total_prob = result.player_win_prob
+            + result.dealer_win_prob
+            + result.push_prob
+            + result.player_blackjack_prob
+            + result.dealer_blackjack_prob
+            + result.surrender_prob
+            + result.split_prob;
+        assert!((total_prob - 1.0).abs() < 1e-9, "{:?} 概率总和: {}", surrender, total_prob);
+        result.ev
+    };
+
+    let none = table_ev(SurrenderRule::None);
+    let late = table_ev(SurrenderRule::Late);
+    let early_vs_ten = table_ev(SurrenderRule::EarlyVsTen);
+    let early_vs_ace = table_ev(SurrenderRule::EarlyVsAce);
+    let early = table_ev(SurrenderRule::Early);
+
+    assert!(none < late, "后投降应该对玩家有利");
+    assert!(late < early_vs_ten && late < early_vs_ace);
+    assert!(early_vs_ten < early && early_vs_ace < early);
+    // 8副牌早投降约值 0.6%
+    assert!(early - none > 0.004 && early - none < 0.009, "早投降收益: {}", early - none);
+}
+
+#[test]
+fn test_early_surrender_before_peek() {
+    let deck = create_tens_only_deck();
+    let hand = hand_of(&[Card::Number(10), Card::Number(6)]);
+
+    // 后投降：庄家偷看到黑杰克，玩家没有决策
+    let late = Calculator::new(rules_with(SurrenderRule::Late, HoleCardRule::Peek))
+        .calculate_action_evs(&hand, Card::Ace, &deck).unwrap();
+    assert!(late.actions.is_empty());
+
+    // 早投降：在偷看之前投降只输一半
+    let early = Calculator::new(rules_with(SurrenderRule::Early, HoleCardRule::Peek))
+        .calculate_action_evs(&hand, Card::Ace, &deck).unwrap();
+    assert_eq!(early.get(Action::Surrender), Some(-0.5));
+    assert_eq!(early.get(Action::Stand), Some(-1.0));
+    assert_eq!(early.get(Action::Double), Some(-1.0));
+    assert_eq!(early.best().unwrap().action, Action::Surrender);
+
+    // 只对10点早投降时，对A仍是后投降
+    let vs_ten = Calculator::new(rules_with(SurrenderRule::EarlyVsTen, HoleCardRule::Peek))
+        .calculate_action_evs(&hand, Card::Ace, &deck).unwrap();
+    assert!(vs_ten.actions.is_empty());
+}
+
+#[test]
+fn test_late_surrender_void_against_blackjack_without_hole_card() {
+    let deck = create_tens_only_deck();
+    let hand = hand_of(&[Card::Number(10), Card::Number(6)]);
+
+    let late = Calculator::new(rules_with(SurrenderRule::Late, HoleCardRule::NoHoleCard))
+        .calculate_action_evs(&hand, Card::Ace, &deck).unwrap();
+    assert_eq!(late.get(Action::Surrender), Some(-1.0));
+
+    let early = Calculator::new(rules_with(SurrenderRule::Early, HoleCardRule::NoHoleCard))
+        .calculate_action_evs(&hand, Card::Ace, &deck).unwrap();
+    assert_eq!(early.get(Action::Surrender), Some(-0.5));
+}
+
+#[test]
+fn test_early_surrender_strategy() {
+    let strategy = BasicStrategy::new();
+
+    assert!(strategy.should_surrender_early(&hand_of(&[Card::Number(8), Card::Number(8)]), Card::Ace));
+    assert!(strategy.should_surrender_early(&hand_of(&[Card::Face, Card::Number(4)]), Card::Face));
+    assert!(!strategy.should_surrender_early(&hand_of(&[Card::Face, Card::Number(3)]), Card::Face));
+    // 软点数不投降
+    assert!(!strategy.should_surrender_early(&hand_of(&[Card::Ace, Card::Number(5)]), Card::Ace));
+
+    assert!(SurrenderRule::Early.is_early_against(Card::Number(10)));
+    assert!(SurrenderRule::EarlyVsAce.is_early_against(Card::Ace));
+    assert!(!SurrenderRule::EarlyVsAce.is_early_against(Card::Face));
+    assert!(!SurrenderRule::Late.is_early_against(Card::Ace));
+    assert!(!SurrenderRule::None.allowed());
+}
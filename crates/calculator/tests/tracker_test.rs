@@ -0,0 +1,82 @@
+//! 牌靴跟踪器测试
+
+mod common;
+
+use calculator::{create_full_deck, BetRamp, Calculator, CalculatorError, Card, ShoeTracker};
+use common::single_deck_no_split_rules;
+
+#[test]
+fn test_deal_matches_fresh_calculation() {
+    let mut tracker = ShoeTracker::new(single_deck_no_split_rules());
+    let start = tracker.update().unwrap();
+    assert_eq!(start.cards_remaining, 52);
+
+    tracker.deal(Card::Number(5)).unwrap();
+    let update = tracker.deal(Card::Face).unwrap();
+    assert_eq!(update.cards_remaining, 50);
+    assert_eq!(tracker.dealt_cards(), vec![Card::Number(5), Card::Face]);
+
+    let mut deck = create_full_deck(1);
+    *deck.get_mut(&Card::Number(5)).unwrap() -= 1;
+    *deck.get_mut(&Card::Face).unwrap() -= 1;
+    let fresh = Calculator::new(single_deck_no_split_rules()).calculate_table_ev(&deck).unwrap();
+    assert_eq!(update.table_ev.ev, fresh.ev, "跟踪器的 EV 应与全新计算一致");
+}
+
+#[test]
+fn test_undo_restores_previous_result() {
+    let mut tracker = ShoeTracker::new(single_deck_no_split_rules());
+    let start = tracker.update().unwrap();
+
+    tracker.deal(Card::Ace).unwrap();
+    assert_eq!(tracker.undo(), Some(Card::Ace));
+    let undone = tracker.update().unwrap();
+    assert_eq!(undone.cards_remaining, 52);
+    assert_eq!(undone.table_ev.ev, start.table_ev.ev);
+    assert!(tracker.dealt_cards().is_empty());
+    assert!(tracker.undo().is_none(), "没有可撤销的牌时应返回 None");
+}
+
+#[test]
+fn test_dealing_missing_card_is_rejected() {
+    let mut tracker = ShoeTracker::new(single_deck_no_split_rules());
+    for _ in 0..4 {
+        tracker.record(Card::Number(7)).unwrap();
+    }
+    assert_eq!(
+        tracker.deal(Card::Number(7)).unwrap_err(),
+        CalculatorError::CardNotInShoe(Card::Number(7)),
+        "第五张 7 不在单副牌中"
+    );
+    assert_eq!(tracker.cards_remaining(), 48);
+
+    tracker.reshuffle();
+    assert_eq!(tracker.cards_remaining(), 52);
+    assert!(tracker.dealt_cards().is_empty());
+}
+
+#[test]
+fn test_recommended_bet_follows_ramp() {
+    let ramp = BetRamp {
+        min_bet: 10.0,
+        max_bet: 50.0,
+        advantage_per_unit: 0.005,
+    };
+    assert_eq!(ramp.bet_for(-0.01), 10.0);
+    assert_eq!(ramp.bet_for(0.004), 10.0);
+    assert_eq!(ramp.bet_for(0.011), 30.0);
+    assert_eq!(ramp.bet_for(0.1), 50.0, "不应超过最大注");
+
+    // 拿掉小牌后玩家有优势，建议下注随之提高
+    let mut tracker = ShoeTracker::new(single_deck_no_split_rules()).with_bet_ramp(ramp);
+    assert_eq!(tracker.update().unwrap().recommended_bet, 10.0);
+    for card in [2, 3, 4, 5, 6].map(Card::Number) {
+        for _ in 0..3 {
+            tracker.record(card).unwrap();
+        }
+    }
+    let update = tracker.update().unwrap();
+    assert!(update.table_ev.ev > 0.0, "拿掉 15 张小牌后 EV 应为正: {}", update.table_ev.ev);
+    assert_eq!(update.recommended_bet, ramp.bet_for(update.table_ev.ev));
+    assert!(update.recommended_bet > 10.0);
+}
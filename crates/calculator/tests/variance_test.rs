@@ -0,0 +1,87 @@
+//! 每局净收益的方差和标准差测试
+
+mod common;
+
+use calculator::{
+    create_full_deck, Calculator, Card, CardCounts, GameRules, InsurancePolicy, TableEVResult,
+};
+use common::single_deck_rules;
+
+fn table_ev(calculator: &Calculator) -> TableEVResult {
+    let deck = create_full_deck(calculator.rules().deck_count);
+    calculator.calculate_table_ev(&deck).unwrap()
+}
+
+#[test]
+fn test_all_tens_has_no_variance() {
+    // 只有十点牌：每局都是 20 点对 20 点平局
+    let mut deck = CardCounts::new();
+    deck.insert(Card::Face, 20);
+
+    let result = Calculator::with_default_rules().calculate_table_ev(&deck).unwrap();
+    assert_eq!(result.ev, 0.0);
+    assert!(result.variance.abs() < 1e-12, "方差: {}", result.variance);
+}
+
+#[test]
+fn test_standard_deviation_of_default_rules() {
+    let result = table_ev(&Calculator::with_default_rules());
+    assert!(result.variance > 1.15 && result.variance < 1.3, "方差: {}", result.variance);
+    assert_eq!(result.std_dev, result.variance.sqrt());
+}
+
+#[test]
+fn test_blackjack_payout_second_moment() {
+    // 赔率只影响玩家赢黑杰克的局：EV 之差 = 0.3 × P，收益平方之差 = (2.25 - 1.44) × P
+    let rules = |blackjack_payout| GameRules {
+        blackjack_payout,
+        ..single_deck_rules()
+    };
+    let three_to_two = table_ev(&Calculator::new(rules(1.5)));
+    let six_to_five = table_ev(&Calculator::new(rules(1.2)));
+
+    let second_moment = |result: &TableEVResult| result.variance + result.ev * result.ev;
+    let ev_difference = three_to_two.ev - six_to_five.ev;
+    let moment_difference = second_moment(&three_to_two) - second_moment(&six_to_five);
+    assert!(ev_difference > 0.0);
+    assert!(
+        (moment_difference - ev_difference * 0.81 / 0.3).abs() < 1e-12,
+        "{} vs {}",
+        moment_difference,
+        ev_difference
+    );
+}
+
+#[test]
+fn test_splits_increase_variance() {
+    let with_splits = GameRules::preset("strip-s17").unwrap();
+    let without_splits = GameRules {
+        allow_split: false,
+        ..with_splits.clone()
+    };
+
+    let split = table_ev(&Calculator::new(with_splits));
+    let no_split = table_ev(&Calculator::new(without_splits));
+    assert!(split.split_prob > 0.0);
+    assert!(split.variance > no_split.variance, "{} <= {}", split.variance, no_split.variance);
+    assert!(split.std_dev < 1.2, "标准差: {}", split.std_dev);
+}
+
+#[test]
+fn test_insurance_changes_variance() {
+    // 拿掉全部小牌后十点牌占一半，保险 EV 为正
+    let mut deck = create_full_deck(1);
+    for n in 2..=6 {
+        deck.remove(&Card::Number(n));
+    }
+    let rules = single_deck_rules();
+
+    let never = Calculator::new(rules.clone()).calculate_table_ev(&deck).unwrap();
+    let insured = Calculator::new(rules)
+        .with_insurance_policy(InsurancePolicy::WhenPositive)
+        .calculate_table_ev(&deck)
+        .unwrap();
+    assert!(insured.ev > never.ev);
+    assert!(insured.variance.is_finite() && insured.variance > 0.0);
+    assert_ne!(insured.variance, never.variance);
+}